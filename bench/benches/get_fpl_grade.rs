@@ -0,0 +1,41 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+/// A paragraph of a typical announcement, full of words starting like a trigger but no mention.
+const DUTIES: &str = "The incumbent performs complex analyses of program operations, coordinates \
+    with federal, state and local partners, and prepares correspondence for the director. \
+    Candidates must meet time-in-grade requirements within 30 days of the closing date. Travel \
+    of up to 25% may be required. Pay, benefits and work schedules vary by position. ";
+
+/// The same paragraph with accented and non-Latin characters, as scraped text often has.
+const DUTIES_UNICODE: &str = "L’agent coordonne les opérations du programme – «\u{a0}Führung\u{a0}» \
+    und Prüfung der Vorgänge; 職務の範囲は広い. Candidates must meet time-in-grade requirements. ";
+
+fn bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get_fpl_grade");
+
+    for repeat in [10, 100, 1000] {
+        let duties = DUTIES.repeat(repeat);
+
+        let texts = [
+            ("no_match", duties.clone()),
+            ("match_first", format!("Full performance level: GS-13. {duties}")),
+            ("match_last", format!("{duties}This position has a promotion potential of GS-12.")),
+            ("target", format!("{duties}Target grade GS-0343-11/12/13.")),
+            ("unicode", DUTIES_UNICODE.repeat(repeat)),
+        ];
+
+        for (name, text) in &texts {
+            group.throughput(Throughput::Bytes(text.len() as u64));
+            group.bench_with_input(BenchmarkId::new(*name, text.len()), text, |b, text| {
+                b.iter(|| fpl::get_fpl_grade(black_box(text)))
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
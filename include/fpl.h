@@ -0,0 +1,33 @@
+#ifndef FPL_H
+#define FPL_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* The grade found in a text. The strings are NUL-terminated and the offsets are byte offsets into
+ * the UTF-8 text. */
+typedef struct FplResult {
+    uint32_t grade;
+    char pay_plan[8];
+    char series[8];
+    char rule[32];
+    double confidence;
+    size_t trigger_start;
+    size_t start;
+    size_t end;
+} FplResult;
+
+/* Extracts the full performance level from the NUL-terminated UTF-8 text into result. Returns 1 if
+ * a grade was found, 0 if not, leaving result zeroed, and -1 if text or result is NULL or text is
+ * not valid UTF-8. */
+int fpl_extract(const char *text, FplResult *result);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,109 @@
+//! Python bindings of the extractor, built with `maturin build --release` into the `fpl` module.
+
+use fpl::extract as extract_grade;
+use pyo3::{prelude::*, types::PyDict};
+use rayon::prelude::*;
+
+/// The keys of the dictionaries returned for each text.
+const KEYS: [&str; 7] = [
+    "grade",
+    "pay_plan",
+    "series",
+    "rule",
+    "confidence",
+    "start",
+    "end",
+];
+
+/// A grade found in a text, with the offsets of the grade in characters, as Python strings are
+/// indexed.
+struct Extraction {
+    grade: String,
+    pay_plan: Option<String>,
+    series: Option<String>,
+    rule: &'static str,
+    confidence: f64,
+    start: usize,
+    end: usize,
+}
+
+impl Extraction {
+    fn find(text: &str) -> Option<Self> {
+        let extraction = extract_grade(text)?;
+        let char_offset = |offset: usize| text[0..offset].chars().count();
+
+        Some(Self {
+            grade: extraction.grade.to_string(),
+            pay_plan: extraction.pay_plan.map(str::to_uppercase),
+            series: extraction.series.map(str::to_string),
+            rule: extraction.rule.as_str(),
+            confidence: (extraction.confidence * 100.0).round() / 100.0,
+            start: char_offset(extraction.start),
+            end: char_offset(extraction.end),
+        })
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+
+        dict.set_item("grade", &self.grade)?;
+        dict.set_item("pay_plan", &self.pay_plan)?;
+        dict.set_item("series", &self.series)?;
+        dict.set_item("rule", self.rule)?;
+        dict.set_item("confidence", self.confidence)?;
+        dict.set_item("start", self.start)?;
+        dict.set_item("end", self.end)?;
+
+        Ok(dict)
+    }
+}
+
+/// Returns the full performance level found in `text` as a dictionary with the keys `grade`,
+/// `pay_plan`, `series`, `rule`, `confidence`, `start` and `end`, or `None` if there is none.
+#[pyfunction]
+fn extract(py: Python<'_>, text: &str) -> PyResult<Option<Bound<'_, PyDict>>> {
+    Extraction::find(text).map(|e| e.to_dict(py)).transpose()
+}
+
+/// Extracts the full performance levels of all texts of `texts` in parallel, without holding the
+/// GIL. The texts without one get a dictionary of `None` values, so that the result loads into a
+/// `pandas.DataFrame` directly.
+#[pyfunction]
+fn extract_batch(py: Python<'_>, texts: &Bound<'_, PyAny>) -> PyResult<Vec<Bound<'_, PyDict>>> {
+    let texts = texts
+        .iter()?
+        .map(|text| text?.extract::<String>())
+        .collect::<PyResult<Vec<_>>>()?;
+
+    let extractions = py.allow_threads(|| {
+        texts
+            .par_iter()
+            .map(|text| Extraction::find(text))
+            .collect::<Vec<_>>()
+    });
+
+    extractions
+        .iter()
+        .map(|extraction| match extraction {
+            Some(extraction) => extraction.to_dict(py),
+            None => {
+                let dict = PyDict::new_bound(py);
+
+                for key in KEYS {
+                    dict.set_item(key, py.None())?;
+                }
+
+                Ok(dict)
+            }
+        })
+        .collect()
+}
+
+#[pymodule]
+#[pyo3(name = "fpl")]
+fn fpl_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(extract, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_batch, m)?)?;
+
+    Ok(())
+}
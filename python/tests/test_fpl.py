@@ -0,0 +1,30 @@
+import fpl
+
+# The accent makes the character offsets differ from the byte offsets.
+TEXT = "Ré: The full performance level is GS-0510-13."
+
+
+def test_extract():
+    extraction = fpl.extract(TEXT)
+
+    assert extraction == {
+        "grade": "13",
+        "pay_plan": "GS",
+        "series": "0510",
+        "rule": "full_performance",
+        "confidence": 0.98,
+        "start": 42,
+        "end": 44,
+    }
+    assert TEXT[extraction["start"] : extraction["end"]] == "13"
+
+
+def test_extract_none():
+    assert fpl.extract("no grade") is None
+
+
+def test_extract_batch():
+    extractions = fpl.extract_batch([TEXT, "no grade"])
+
+    assert extractions[0] == fpl.extract(TEXT)
+    assert extractions[1] == dict.fromkeys(extractions[0])
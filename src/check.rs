@@ -0,0 +1,160 @@
+use std::{
+    io::{Error, ErrorKind, Result, Write},
+    path::Path,
+};
+
+use csv::ReaderBuilder;
+use fpl::{get_fpl_mention_with, Rules};
+
+use crate::{output::Output, Args};
+
+/// Runs the extraction over a labeled corpus, a CSV file with `text` and `grade` columns where the
+/// grade is written as `13` or `GS-13` and left empty when no grade should be found. Writes the
+/// records whose grade differs and returns whether there were none.
+pub fn check(path: &Path, rules: &Rules, args: &Args) -> Result<bool> {
+    let mut reader = ReaderBuilder::new()
+        .delimiter(args.delimiter)
+        .from_path(path)?;
+
+    let headers = reader.headers()?.clone();
+    let column = |name| {
+        headers.iter().position(|h| h == name).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("column '{name}' not found in header"),
+            )
+        })
+    };
+
+    let text_index = column("text")?;
+    let grade_index = column("grade")?;
+
+    let mut out = Output::new(args.output.as_deref())?;
+    let mut failed = 0;
+    let mut total = 0;
+
+    for record in reader.records() {
+        let record = record?;
+        let text = record.get(text_index).unwrap_or_default();
+        let expected = record.get(grade_index).unwrap_or_default().trim();
+        let actual = get_fpl_mention_with(rules, text).map(|(_, m)| m.grade());
+
+        let passed = match actual {
+            Some(grade) => grade.matches(expected),
+            None => expected.is_empty(),
+        };
+
+        total += 1;
+
+        if !passed {
+            let line = record.position().map_or(0, |p| p.line());
+            let expected = if expected.is_empty() {
+                "none"
+            } else {
+                expected
+            };
+
+            failed += 1;
+            writeln!(
+                out,
+                "line {line}: expected {expected}, got {}: {text}",
+                actual.map_or("none".to_string(), |g| g.to_string())
+            )?;
+        }
+    }
+
+    writeln!(out, "{} of {total} passed", total - failed)?;
+    out.commit()?;
+
+    Ok(failed == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf, process};
+
+    use clap::Parser;
+
+    use crate::check::*;
+
+    /// Checks a corpus with `text`, writing to a temporary file, and returns the result and what
+    /// was written.
+    fn checked(name: &str, text: &str) -> Result<(bool, String)> {
+        let corpus = temp(&format!("{name}.csv"));
+        let output = temp(&format!("{name}.txt"));
+        let args = Args::parse_from([
+            "fpl",
+            "check",
+            "--output",
+            output.to_str().unwrap(),
+            corpus.to_str().unwrap(),
+        ]);
+
+        fs::write(&corpus, text)?;
+
+        let passed = check(&corpus, &Rules::default(), &args);
+
+        fs::remove_file(corpus)?;
+
+        let passed = passed?;
+        let printed = fs::read_to_string(&output)?;
+
+        fs::remove_file(output)?;
+        Ok((passed, printed))
+    }
+
+    fn temp(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("fpl-{}-{name}", process::id()))
+    }
+
+    #[test]
+    fn test_check() {
+        let (passed, printed) = checked(
+            "check",
+            concat!(
+                "text,grade\n",
+                "Full performance level: GS-12,GS-12\n",
+                "The FPL is 13,13\n",
+                "No grade here,\n",
+            ),
+        )
+        .unwrap();
+
+        assert!(passed);
+        assert_eq!(printed, "3 of 3 passed\n");
+    }
+
+    #[test]
+    fn test_check_failures() {
+        let (passed, printed) = checked(
+            "check-failures",
+            concat!(
+                "id,grade,text\n",
+                "1,GS-13,Full performance level: GS-12\n",
+                "2,,The FPL is WG-10\n",
+                "3, 9 ,No grade here\n",
+                "4,11,The FPL is GS-11\n",
+            ),
+        )
+        .unwrap();
+
+        assert!(!passed);
+        assert_eq!(
+            printed,
+            concat!(
+                "line 2: expected GS-13, got GS-12: Full performance level: GS-12\n",
+                "line 3: expected none, got WG-10: The FPL is WG-10\n",
+                "line 4: expected 9, got none: No grade here\n",
+                "1 of 4 passed\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_check_missing_column() {
+        let error = checked("check-missing-column", "text,expected\nGS-12,12\n").unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(error.to_string(), "column 'grade' not found in header");
+    }
+}
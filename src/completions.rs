@@ -0,0 +1,629 @@
+use std::io::{Result, Write};
+
+use clap::{Command, CommandFactory, ValueEnum, ValueHint};
+
+use crate::{output::Output, Args};
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Fish,
+    Powershell,
+    Zsh,
+}
+
+/// An option as completions describe it.
+struct Opt {
+    long: String,
+    help: String,
+    /// Whether the value of the option is a path, which is completed with file names.
+    path: bool,
+    /// The values the option accepts, empty if it takes a path or any text.
+    values: Vec<String>,
+    takes_value: bool,
+}
+
+/// Writes the completion script of `shell`, which completes the subcommands, their options and
+/// the values of the options with a fixed set of them.
+pub fn completions(shell: Shell, args: &Args) -> Result<bool> {
+    let mut command = Args::command();
+    command.build();
+
+    let name = command.get_name().to_string();
+    let mut out = Output::new(args.output.as_deref())?;
+
+    match shell {
+        Shell::Bash => bash(&mut out, &name, &command)?,
+        Shell::Fish => fish(&mut out, &name, &command)?,
+        Shell::Powershell => powershell(&mut out, &name, &command)?,
+        Shell::Zsh => zsh(&mut out, &name, &command)?,
+    }
+
+    out.commit()?;
+
+    Ok(true)
+}
+
+fn about(command: &Command) -> String {
+    command
+        .get_about()
+        .map(|a| a.to_string())
+        .unwrap_or_default()
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn bash(out: &mut impl Write, name: &str, command: &Command) -> Result<()> {
+    let subcommands = subcommands(command);
+    let names = subcommands.iter().map(|c| c.get_name()).collect::<Vec<_>>();
+
+    writeln!(out, "_{name}() {{")?;
+    writeln!(out, "    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\"")?;
+    writeln!(out, "    local prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"")?;
+    writeln!(out, "    local command=\"\"")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    for word in \"${{COMP_WORDS[@]:1:COMP_CWORD-1}}\"; do"
+    )?;
+    writeln!(out, "        case \"$word\" in")?;
+    writeln!(out, "            {})", names.join("|"))?;
+    writeln!(out, "                command=\"$word\"")?;
+    writeln!(out, "                break")?;
+    writeln!(out, "                ;;")?;
+    writeln!(out, "        esac")?;
+    writeln!(out, "    done")?;
+    writeln!(out)?;
+    writeln!(out, "    case \"$prev\" in")?;
+
+    let mut seen = Vec::new();
+
+    for option in iter_commands(command).flat_map(options) {
+        if !option.takes_value || seen.contains(&option.long) {
+            continue;
+        }
+
+        let reply = if !option.values.is_empty() {
+            format!("$(compgen -W \"{}\" -- \"$cur\")", option.values.join(" "))
+        } else if option.path {
+            "$(compgen -f -- \"$cur\")".to_string()
+        } else {
+            String::new()
+        };
+
+        writeln!(out, "        --{})", option.long)?;
+        writeln!(out, "            COMPREPLY=({reply})")?;
+        writeln!(out, "            return")?;
+        writeln!(out, "            ;;")?;
+
+        seen.push(option.long);
+    }
+
+    writeln!(out, "    esac")?;
+    writeln!(out)?;
+    writeln!(out, "    case \"$command\" in")?;
+
+    let top = options(command)
+        .into_iter()
+        .map(|o| format!("--{}", o.long));
+    let words = names.iter().map(|n| n.to_string()).chain(top);
+
+    writeln!(out, "        \"\")")?;
+    writeln!(
+        out,
+        "            COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+        words.collect::<Vec<_>>().join(" ")
+    )?;
+    writeln!(out, "            ;;")?;
+
+    for subcommand in &subcommands {
+        let words = options(subcommand)
+            .into_iter()
+            .map(|o| format!("--{}", o.long))
+            .collect::<Vec<_>>();
+
+        writeln!(out, "        {})", subcommand.get_name())?;
+        writeln!(
+            out,
+            "            COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+            words.join(" ")
+        )?;
+
+        if has_positionals(subcommand) {
+            writeln!(out, "            if [[ \"$cur\" != -* ]]; then")?;
+            writeln!(
+                out,
+                "                COMPREPLY+=($(compgen -f -- \"$cur\"))"
+            )?;
+            writeln!(out, "            fi")?;
+        }
+
+        writeln!(out, "            ;;")?;
+    }
+
+    writeln!(out, "    esac")?;
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    writeln!(out, "complete -o filenames -F _{name} {name}")
+}
+
+fn fish(out: &mut impl Write, name: &str, command: &Command) -> Result<()> {
+    let subcommands = subcommands(command);
+
+    for subcommand in &subcommands {
+        writeln!(
+            out,
+            "complete -c {name} -f -n __fish_use_subcommand -a {} -d '{}'",
+            subcommand.get_name(),
+            fish_escape(&about(subcommand))
+        )?;
+    }
+
+    for option in options(command) {
+        writeln!(out, "complete -c {name} -l {}", fish_option(&option))?;
+    }
+
+    let globals = options(command)
+        .into_iter()
+        .map(|o| o.long)
+        .collect::<Vec<_>>();
+
+    for subcommand in &subcommands {
+        for option in options(subcommand) {
+            if globals.contains(&option.long) {
+                continue;
+            }
+
+            writeln!(
+                out,
+                "complete -c {name} -n '__fish_seen_subcommand_from {}' -l {}",
+                subcommand.get_name(),
+                fish_option(&option)
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn fish_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+fn fish_option(option: &Opt) -> String {
+    let mut text = option.long.clone();
+
+    if option.takes_value {
+        if !option.values.is_empty() {
+            text.push_str(&format!(" -r -f -a '{}'", option.values.join(" ")));
+        } else if option.path {
+            text.push_str(" -r -F");
+        } else {
+            text.push_str(" -r -f");
+        }
+    }
+
+    if !option.help.is_empty() {
+        text.push_str(&format!(" -d '{}'", fish_escape(&option.help)));
+    }
+
+    text
+}
+
+fn has_positionals(command: &Command) -> bool {
+    command.get_positionals().next().is_some()
+}
+
+/// Returns `command` followed by its visible subcommands.
+fn iter_commands(command: &Command) -> impl Iterator<Item = &Command> {
+    std::iter::once(command).chain(subcommands(command))
+}
+
+fn options(command: &Command) -> Vec<Opt> {
+    command
+        .get_arguments()
+        .filter(|a| !a.is_hide_set())
+        .filter_map(|a| {
+            let long = a.get_long()?.to_string();
+            let takes_value = a.get_num_args().is_some_and(|n| n.takes_values());
+
+            let path = matches!(
+                a.get_value_hint(),
+                ValueHint::AnyPath | ValueHint::DirPath | ValueHint::FilePath
+            );
+
+            let values = a
+                .get_possible_values()
+                .iter()
+                .filter(|v| !v.is_hide_set())
+                .map(|v| v.get_name().to_string())
+                .collect();
+
+            let help = a
+                .get_help()
+                .map(|h| h.to_string())
+                .unwrap_or_default()
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string();
+
+            Some(Opt {
+                long,
+                help,
+                path,
+                values,
+                takes_value,
+            })
+        })
+        .collect()
+}
+
+fn powershell(out: &mut impl Write, name: &str, command: &Command) -> Result<()> {
+    let quote = |words: Vec<String>| {
+        words
+            .iter()
+            .map(|w| format!("'{w}'"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let subcommands = subcommands(command);
+    let top = subcommands
+        .iter()
+        .map(|c| c.get_name().to_string())
+        .chain(
+            options(command)
+                .into_iter()
+                .map(|o| format!("--{}", o.long)),
+        )
+        .collect();
+
+    writeln!(
+        out,
+        "Register-ArgumentCompleter -Native -CommandName '{name}' -ScriptBlock {{"
+    )?;
+    writeln!(
+        out,
+        "    param($wordToComplete, $commandAst, $cursorPosition)"
+    )?;
+    writeln!(out)?;
+    writeln!(out, "    $words = @{{")?;
+    writeln!(out, "        '' = @({})", quote(top))?;
+
+    for subcommand in &subcommands {
+        let words = options(subcommand)
+            .into_iter()
+            .map(|o| format!("--{}", o.long))
+            .collect();
+
+        writeln!(
+            out,
+            "        '{}' = @({})",
+            subcommand.get_name(),
+            quote(words)
+        )?;
+    }
+
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+    writeln!(out, "    $command = ''")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    foreach ($element in $commandAst.CommandElements | Select-Object -Skip 1) {{"
+    )?;
+    writeln!(
+        out,
+        "        if ($words.ContainsKey([string]$element) -and [string]$element -ne '') {{"
+    )?;
+    writeln!(out, "            $command = [string]$element")?;
+    writeln!(out, "            break")?;
+    writeln!(out, "        }}")?;
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    $words[$command] | Where-Object {{ $_ -like \"$wordToComplete*\" }} | ForEach-Object {{"
+    )?;
+    writeln!(
+        out,
+        "        [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterName', $_)"
+    )?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")
+}
+
+fn subcommands(command: &Command) -> Vec<&Command> {
+    command
+        .get_subcommands()
+        .filter(|c| !c.is_hide_set())
+        .collect()
+}
+
+fn zsh(out: &mut impl Write, name: &str, command: &Command) -> Result<()> {
+    let subcommands = subcommands(command);
+
+    writeln!(out, "#compdef {name}")?;
+    writeln!(out)?;
+    writeln!(out, "_{name}() {{")?;
+    writeln!(out, "    local line state")?;
+    writeln!(out)?;
+    writeln!(out, "    _arguments -C \\")?;
+
+    for option in options(command) {
+        writeln!(out, "        {} \\", zsh_option(&option))?;
+    }
+
+    writeln!(out, "        '1: :->command' \\")?;
+    writeln!(out, "        '*:: :->args'")?;
+    writeln!(out)?;
+    writeln!(out, "    case $state in")?;
+    writeln!(out, "        command)")?;
+    writeln!(out, "            _values 'command' \\")?;
+
+    for (i, subcommand) in subcommands.iter().enumerate() {
+        let end = if i + 1 < subcommands.len() { " \\" } else { "" };
+
+        writeln!(
+            out,
+            "                '{}[{}]'{end}",
+            subcommand.get_name(),
+            zsh_escape(&about(subcommand))
+        )?;
+    }
+
+    writeln!(out, "            ;;")?;
+    writeln!(out, "        args)")?;
+    writeln!(out, "            case $line[1] in")?;
+
+    for subcommand in &subcommands {
+        let mut specs = options(subcommand)
+            .iter()
+            .map(zsh_option)
+            .collect::<Vec<_>>();
+
+        if has_positionals(subcommand) {
+            specs.push("'*:file:_files'".to_string());
+        }
+
+        writeln!(out, "                {})", subcommand.get_name())?;
+        writeln!(
+            out,
+            "                    _arguments {}",
+            specs.join(" \\\n                        ")
+        )?;
+        writeln!(out, "                    ;;")?;
+    }
+
+    writeln!(out, "            esac")?;
+    writeln!(out, "            ;;")?;
+    writeln!(out, "    esac")?;
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    writeln!(out, "_{name} \"$@\"")
+}
+
+fn zsh_escape(text: &str) -> String {
+    text.replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+        .replace(':', "\\:")
+}
+
+fn zsh_option(option: &Opt) -> String {
+    let help = zsh_escape(&option.help);
+
+    if !option.takes_value {
+        return format!("'--{}[{help}]'", option.long);
+    }
+
+    let action = if !option.values.is_empty() {
+        format!("({})", option.values.join(" "))
+    } else if option.path {
+        "_files".to_string()
+    } else {
+        " ".to_string()
+    };
+
+    format!("'--{}=[{help}]: :{action}'", option.long)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use clap::{value_parser, Arg, ArgAction};
+
+    use crate::completions::*;
+
+    type Print = fn(&mut Vec<u8>, &str, &Command) -> Result<()>;
+
+    /// Returns a command with an option of each kind: a flag, a path, a fixed set of values and
+    /// any text, in a subcommand that also takes files.
+    fn command() -> Command {
+        let extract = Command::new("extract")
+            .about("Extracts grades")
+            .arg(
+                Arg::new("format")
+                    .long("format")
+                    .help("The output format")
+                    .value_parser(["csv", "json"]),
+            )
+            .arg(Arg::new("port").long("port").help("The port"))
+            .arg(Arg::new("input").value_parser(value_parser!(PathBuf)));
+
+        let mut command = Command::new("demo")
+            .arg(
+                Arg::new("output")
+                    .long("output")
+                    .help("Where to write")
+                    .value_parser(value_parser!(PathBuf)),
+            )
+            .arg(
+                Arg::new("quiet")
+                    .long("quiet")
+                    .help("Prints nothing")
+                    .action(ArgAction::SetTrue),
+            )
+            .subcommand(extract);
+
+        command.build();
+        command
+    }
+
+    fn print(print: Print) -> String {
+        let mut out = Vec::new();
+
+        print(&mut out, "demo", &command()).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_bash() {
+        assert_eq!(
+            print(bash),
+            concat!(
+                "_demo() {\n",
+                "    local cur=\"${COMP_WORDS[COMP_CWORD]}\"\n",
+                "    local prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n",
+                "    local command=\"\"\n",
+                "\n",
+                "    for word in \"${COMP_WORDS[@]:1:COMP_CWORD-1}\"; do\n",
+                "        case \"$word\" in\n",
+                "            extract|help)\n",
+                "                command=\"$word\"\n",
+                "                break\n",
+                "                ;;\n",
+                "        esac\n",
+                "    done\n",
+                "\n",
+                "    case \"$prev\" in\n",
+                "        --output)\n",
+                "            COMPREPLY=($(compgen -f -- \"$cur\"))\n",
+                "            return\n",
+                "            ;;\n",
+                "        --format)\n",
+                "            COMPREPLY=($(compgen -W \"csv json\" -- \"$cur\"))\n",
+                "            return\n",
+                "            ;;\n",
+                "        --port)\n",
+                "            COMPREPLY=()\n",
+                "            return\n",
+                "            ;;\n",
+                "    esac\n",
+                "\n",
+                "    case \"$command\" in\n",
+                "        \"\")\n",
+                "            COMPREPLY=($(compgen -W \"extract help --output --quiet --help\" -- \"$cur\"))\n",
+                "            ;;\n",
+                "        extract)\n",
+                "            COMPREPLY=($(compgen -W \"--format --port --help\" -- \"$cur\"))\n",
+                "            if [[ \"$cur\" != -* ]]; then\n",
+                "                COMPREPLY+=($(compgen -f -- \"$cur\"))\n",
+                "            fi\n",
+                "            ;;\n",
+                "        help)\n",
+                "            COMPREPLY=($(compgen -W \"\" -- \"$cur\"))\n",
+                "            ;;\n",
+                "    esac\n",
+                "}\n",
+                "\n",
+                "complete -o filenames -F _demo demo\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_fish() {
+        assert_eq!(
+            print(fish),
+            concat!(
+                "complete -c demo -f -n __fish_use_subcommand -a extract -d 'Extracts grades'\n",
+                "complete -c demo -f -n __fish_use_subcommand -a help -d 'Print this message or the help of the given subcommand(s)'\n",
+                "complete -c demo -l output -r -F -d 'Where to write'\n",
+                "complete -c demo -l quiet -d 'Prints nothing'\n",
+                "complete -c demo -l help -d 'Print help'\n",
+                "complete -c demo -n '__fish_seen_subcommand_from extract' -l format -r -f -a 'csv json' -d 'The output format'\n",
+                "complete -c demo -n '__fish_seen_subcommand_from extract' -l port -r -f -d 'The port'\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_powershell() {
+        assert_eq!(
+            print(powershell),
+            concat!(
+                "Register-ArgumentCompleter -Native -CommandName 'demo' -ScriptBlock {\n",
+                "    param($wordToComplete, $commandAst, $cursorPosition)\n",
+                "\n",
+                "    $words = @{\n",
+                "        '' = @('extract', 'help', '--output', '--quiet', '--help')\n",
+                "        'extract' = @('--format', '--port', '--help')\n",
+                "        'help' = @()\n",
+                "    }\n",
+                "\n",
+                "    $command = ''\n",
+                "\n",
+                "    foreach ($element in $commandAst.CommandElements | Select-Object -Skip 1) {\n",
+                "        if ($words.ContainsKey([string]$element) -and [string]$element -ne '') {\n",
+                "            $command = [string]$element\n",
+                "            break\n",
+                "        }\n",
+                "    }\n",
+                "\n",
+                "    $words[$command] | Where-Object { $_ -like \"$wordToComplete*\" } | ForEach-Object {\n",
+                "        [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterName', $_)\n",
+                "    }\n",
+                "}\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_zsh() {
+        assert_eq!(
+            print(zsh),
+            concat!(
+                "#compdef demo\n",
+                "\n",
+                "_demo() {\n",
+                "    local line state\n",
+                "\n",
+                "    _arguments -C \\\n",
+                "        '--output=[Where to write]: :_files' \\\n",
+                "        '--quiet[Prints nothing]' \\\n",
+                "        '--help[Print help]' \\\n",
+                "        '1: :->command' \\\n",
+                "        '*:: :->args'\n",
+                "\n",
+                "    case $state in\n",
+                "        command)\n",
+                "            _values 'command' \\\n",
+                "                'extract[Extracts grades]' \\\n",
+                "                'help[Print this message or the help of the given subcommand(s)]'\n",
+                "            ;;\n",
+                "        args)\n",
+                "            case $line[1] in\n",
+                "                extract)\n",
+                "                    _arguments '--format=[The output format]: :(csv json)' \\\n",
+                "                        '--port=[The port]: : ' \\\n",
+                "                        '--help[Print help]' \\\n",
+                "                        '*:file:_files'\n",
+                "                    ;;\n",
+                "                help)\n",
+                "                    _arguments \n",
+                "                    ;;\n",
+                "            esac\n",
+                "            ;;\n",
+                "    esac\n",
+                "}\n",
+                "\n",
+                "_demo \"$@\"\n"
+            )
+        );
+    }
+}
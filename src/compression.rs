@@ -0,0 +1,125 @@
+use std::{
+    fs::File,
+    io::{Error, ErrorKind, Read, Result},
+    path::Path,
+    process::{Child, ChildStdout, Command, Stdio},
+};
+
+/// A compression format recognized by the extension of a file name, which is handled by the
+/// `gzip` or `zstd` program so that large files are streamed rather than read into memory.
+#[derive(Clone, Copy)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+}
+
+/// Reads the output of a decompressing process, failing at the end if the process did.
+pub struct Decompressor {
+    child: Child,
+    compression: Compression,
+    stdout: ChildStdout,
+}
+
+impl Compression {
+    /// Returns the compression of `path` if its extension is `.gz` or `.zst`.
+    pub fn of(path: &Path) -> Option<Self> {
+        let extension = path.extension()?;
+
+        if extension.eq_ignore_ascii_case("gz") {
+            Some(Self::Gzip)
+        } else if extension.eq_ignore_ascii_case("zst") {
+            Some(Self::Zstd)
+        } else {
+            None
+        }
+    }
+
+    /// Starts compressing what is written to the standard input of the returned process into
+    /// `file`.
+    pub fn compress(self, file: File) -> Result<Child> {
+        self.command()
+            .args(["-c", "-q"])
+            .stdin(Stdio::piped())
+            .stdout(file)
+            .spawn()
+            .map_err(|e| self.spawn_error(e))
+    }
+
+    pub fn decompress(self, path: &Path) -> Result<Decompressor> {
+        // The file is opened here, so that a missing one is reported like any other input.
+        let file = File::open(path)?;
+
+        let mut child = self
+            .command()
+            .args(["-d", "-c", "-q"])
+            .stdin(file)
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| self.spawn_error(e))?;
+
+        let stdout = child.stdout.take().expect("stdout is piped");
+
+        Ok(Decompressor {
+            child,
+            compression: self,
+            stdout,
+        })
+    }
+
+    /// Checks how a compressing or decompressing process exited.
+    pub fn finish(self, child: &mut Child) -> Result<()> {
+        let status = child.wait()?;
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(Error::other(format!(
+                "{} failed with {status}",
+                self.program()
+            )))
+        }
+    }
+
+    fn command(self) -> Command {
+        Command::new(self.program())
+    }
+
+    fn program(self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    fn spawn_error(self, error: Error) -> Error {
+        if error.kind() == ErrorKind::NotFound {
+            Error::new(
+                ErrorKind::NotFound,
+                format!("{} is required for compressed files", self.program()),
+            )
+        } else {
+            error
+        }
+    }
+}
+
+impl Read for Decompressor {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let count = self.stdout.read(buf)?;
+
+        if count == 0 && !buf.is_empty() {
+            self.compression.finish(&mut self.child)?;
+        }
+
+        Ok(count)
+    }
+}
+
+impl Drop for Decompressor {
+    fn drop(&mut self) {
+        if let Ok(None) = self.child.try_wait() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
@@ -0,0 +1,406 @@
+use std::{
+    env,
+    ffi::OsString,
+    fs,
+    io::{Error, ErrorKind, Result},
+    iter,
+    path::PathBuf,
+};
+
+use clap::{parser::ValueSource, Arg, ArgMatches, Command, CommandFactory, Parser};
+
+use crate::Args;
+
+/// The output formats `format` can name, with the flags selecting them. CSV is the default.
+const FORMATS: [(&str, Option<&str>); 7] = [
+    ("annotations", Some("annotations")),
+    ("csv", None),
+    ("html", Some("html")),
+    ("json", Some("json")),
+    ("jsonl", Some("jsonl")),
+    ("markdown", Some("markdown")),
+    ("pretty", Some("pretty")),
+];
+
+/// The options selecting an output format, of which the command line may give one.
+const FORMAT_OPTIONS: [&str; 9] = [
+    "annotations",
+    "html",
+    "json",
+    "jsonl",
+    "markdown",
+    "parquet",
+    "pretty",
+    "sqlite",
+    "xlsx",
+];
+
+enum Value {
+    Array(Vec<String>),
+    Boolean(bool),
+    Text(String),
+}
+
+/// Parses the command line, taking the options it does not give from the configuration file.
+///
+/// The file is `--config` or, without it, `fpl.toml` in `$XDG_CONFIG_HOME` or `~/.config`, where
+/// a missing file is not an error. Each line sets an option by its long name with underscores,
+/// as in `out_delimiter = ";"` or `pay_plans = ["gs", "wg"]`, with a boolean for a flag, and
+/// `format` names the output format. An array must fit on one line. Options that the subcommand does not have are ignored.
+pub fn parse() -> Result<Args> {
+    parse_from(env::args_os().collect())
+}
+
+fn has_option(command: &Command, id: &str) -> bool {
+    option(command, id).is_some()
+}
+
+fn is_given(matches: &ArgMatches, id: &str) -> bool {
+    matches.value_source(id) == Some(ValueSource::CommandLine)
+}
+
+fn location() -> Option<PathBuf> {
+    let dir = env::var_os("XDG_CONFIG_HOME")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|d| PathBuf::from(d).join(".config")))?;
+
+    Some(dir.join("fpl.toml"))
+}
+
+fn option<'a>(command: &'a Command, id: &str) -> Option<&'a Arg> {
+    command
+        .get_arguments()
+        .find(|a| a.get_id() == id && a.get_long().is_some())
+}
+
+/// Returns the options of the configuration file that the command line `argv` does not give.
+/// Errors in `argv` are left to the final parse, so that e.g. the required `--api-key` of
+/// `fetch` can come from the file.
+fn options(argv: &[OsString]) -> Result<Vec<OsString>> {
+    let mut command = Args::command().ignore_errors(true);
+    command.build();
+
+    let matches = command
+        .clone()
+        .try_get_matches_from(argv)
+        .unwrap_or_else(|e| e.exit());
+
+    let Some((name, matches)) = matches.subcommand() else {
+        return Ok(Vec::new());
+    };
+
+    let (path, required) = match matches.get_one::<PathBuf>("config") {
+        Some(path) => (path.clone(), true),
+        None => match location() {
+            Some(path) => (path, false),
+            None => return Ok(Vec::new()),
+        },
+    };
+
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(error) if error.kind() == ErrorKind::NotFound && !required => return Ok(Vec::new()),
+        Err(error) => {
+            return Err(Error::new(
+                error.kind(),
+                format!("{}: {error}", path.display()),
+            ))
+        }
+    };
+
+    let subcommand = command.find_subcommand(name).expect("unknown subcommand");
+    let mut options = Vec::new();
+
+    for (line, entry) in text.lines().enumerate() {
+        let entry = strip_comment(entry).trim();
+
+        if entry.is_empty() {
+            continue;
+        }
+
+        let error = |message: String| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("{}, line {}: {message}", path.display(), line + 1),
+            )
+        };
+
+        let (key, value) = entry
+            .split_once('=')
+            .and_then(|(key, value)| Some((key.trim(), parse_value(value.trim())?)))
+            .ok_or_else(|| error("invalid syntax".to_string()))?;
+
+        if key == "format" {
+            let Value::Text(format) = value else {
+                return Err(error("'format' must be a string".to_string()));
+            };
+
+            let flag = FORMATS
+                .iter()
+                .find(|(name, _)| *name == format)
+                .ok_or_else(|| error(format!("unknown format '{format}'")))?
+                .1;
+
+            let known = FORMAT_OPTIONS.iter().all(|&id| has_option(subcommand, id));
+
+            if let Some(flag) = flag.filter(|_| known) {
+                if !FORMAT_OPTIONS.iter().any(|id| is_given(matches, id)) {
+                    options.push(OsString::from(format!("--{flag}")));
+                }
+            }
+
+            continue;
+        }
+
+        let arg = iter::once(&command)
+            .chain(command.get_subcommands())
+            .filter(|_| key != "config")
+            .find_map(|c| option(c, key))
+            .ok_or_else(|| error(format!("unknown option '{key}'")))?;
+
+        match (arg.get_action().takes_values(), &value) {
+            (true, Value::Boolean(_)) => {
+                return Err(error(format!("'{key}' must be a string or an array")))
+            }
+            (false, Value::Array(_) | Value::Text(_)) => {
+                return Err(error(format!("'{key}' must be a boolean")))
+            }
+            _ => {}
+        }
+
+        if !has_option(subcommand, key) || is_given(matches, key) {
+            continue;
+        }
+
+        let flag = format!("--{}", key.replace('_', "-"));
+
+        match value {
+            Value::Array(values) => options.push(format!("{flag}={}", values.join(",")).into()),
+            Value::Boolean(true) => options.push(flag.into()),
+            Value::Boolean(false) => {}
+            Value::Text(text) => options.push(format!("{flag}={text}").into()),
+        }
+    }
+
+    Ok(options)
+}
+
+/// Parses `argv` like [`parse`].
+fn parse_from(mut argv: Vec<OsString>) -> Result<Args> {
+    let options = options(&argv)?;
+
+    // The options go before a `--`, after which everything is an input.
+    let end = argv.iter().position(|a| a == "--").unwrap_or(argv.len());
+
+    argv.splice(end..end, options);
+
+    Ok(Args::parse_from(argv))
+}
+
+fn parse_string(s: &str) -> Option<String> {
+    let quote = s.chars().next().filter(|&c| c == '"' || c == '\'')?;
+    let text = s[1..].strip_suffix(quote)?;
+
+    (!text.contains(quote)).then(|| text.to_string())
+}
+
+/// Parses a string, a boolean, an integer or an array of strings.
+fn parse_value(s: &str) -> Option<Value> {
+    if let Some(items) = s.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+        return items
+            .split(',')
+            .map(str::trim)
+            .filter(|i| !i.is_empty())
+            .map(parse_string)
+            .collect::<Option<_>>()
+            .map(Value::Array);
+    }
+
+    match s {
+        "false" => Some(Value::Boolean(false)),
+        "true" => Some(Value::Boolean(true)),
+        _ if s.parse::<u64>().is_ok() => Some(Value::Text(s.to_string())),
+        _ => parse_string(s).map(Value::Text),
+    }
+}
+
+/// Removes a comment from the end of a line, unless the `#` is within a string.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '#') => return &line[0..i],
+            (Some(q), _) if q == c => quote = None,
+            _ => {}
+        }
+    }
+
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process;
+
+    use crate::{config::*, Command};
+
+    /// Returns the options that a configuration file with `text` adds to `fpl extract` with
+    /// `args`.
+    fn options_of(name: &str, text: &str, args: &[&str]) -> Result<Vec<OsString>> {
+        let path = env::temp_dir().join(format!("fpl-{}-{name}.toml", process::id()));
+
+        fs::write(&path, text).unwrap();
+
+        let argv = ["fpl", "extract", "--config", path.to_str().unwrap()]
+            .iter()
+            .chain(args)
+            .map(OsString::from)
+            .collect::<Vec<_>>();
+
+        let options = options(&argv);
+
+        fs::remove_file(path).unwrap();
+        options
+    }
+
+    fn error_of(name: &str, text: &str) -> String {
+        let error = options_of(name, text, &["input.csv"]).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+        let message = error.to_string();
+
+        message[message.find(", line ").unwrap() + 2..].to_string()
+    }
+
+    #[test]
+    fn test_options() {
+        let options = options_of(
+            "config-options",
+            concat!(
+                "# defaults\n",
+                "out_delimiter = \";\" # semicolons\n",
+                "pay_plans = [\"gs\", \"wg\"]\n",
+                "max_grade = 15\n",
+                "unique = true\n",
+                "fuzzy = false\n",
+                "format = \"json\"\n",
+                "port = 9000\n",
+            ),
+            &["input.csv"],
+        )
+        .unwrap();
+
+        assert_eq!(
+            options,
+            [
+                "--out-delimiter=;",
+                "--pay-plans=gs,wg",
+                "--max-grade=15",
+                "--unique",
+                "--json"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_options_precedence() {
+        let text = concat!(
+            "out_delimiter = \";\"\n",
+            "unique = true\n",
+            "format = \"json\"\n",
+        );
+
+        assert_eq!(
+            options_of(
+                "config-precedence",
+                text,
+                &["--out-delimiter", "|", "--pretty", "input.csv"]
+            )
+            .unwrap(),
+            ["--unique"]
+        );
+    }
+
+    #[test]
+    fn test_options_type_errors() {
+        assert_eq!(
+            error_of("config-boolean", "unique = \"yes\""),
+            "line 1: 'unique' must be a boolean"
+        );
+        assert_eq!(
+            error_of("config-text", "\n\npay_plans = true"),
+            "line 3: 'pay_plans' must be a string or an array"
+        );
+        assert_eq!(
+            error_of("config-format", "format = true"),
+            "line 1: 'format' must be a string"
+        );
+        assert_eq!(
+            error_of("config-unknown-format", "format = \"xml\""),
+            "line 1: unknown format 'xml'"
+        );
+        assert_eq!(
+            error_of("config-syntax", "unique"),
+            "line 1: invalid syntax"
+        );
+        assert_eq!(
+            error_of("config-array", "pay_plans = [gs]"),
+            "line 1: invalid syntax"
+        );
+    }
+
+    #[test]
+    fn test_options_unknown_key() {
+        assert_eq!(
+            error_of("config-unknown", "colour = \"always\""),
+            "line 1: unknown option 'colour'"
+        );
+        assert_eq!(
+            error_of("config-config", "config = \"other.toml\""),
+            "line 1: unknown option 'config'"
+        );
+    }
+
+    #[test]
+    fn test_parse_from() {
+        let path = env::temp_dir().join(format!("fpl-{}-config-parse.toml", process::id()));
+
+        fs::write(
+            &path,
+            "out_delimiter = \";\"\nunique = true\nformat = \"json\"\n",
+        )
+        .unwrap();
+
+        let args = parse_from(
+            [
+                "fpl",
+                "extract",
+                "--config",
+                path.to_str().unwrap(),
+                "--out-delimiter",
+                "|",
+                "--",
+                "input.csv",
+            ]
+            .map(OsString::from)
+            .to_vec(),
+        )
+        .unwrap();
+
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(args.out_delimiter, b'|');
+        assert!(args.unique);
+
+        let Command::Extract { input, options, .. } = args.command else {
+            panic!("not extract");
+        };
+
+        assert_eq!(input, [PathBuf::from("input.csv")]);
+        assert!(options.json);
+    }
+}
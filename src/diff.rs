@@ -0,0 +1,165 @@
+use std::{
+    collections::BTreeMap,
+    io::{Error, ErrorKind, Result},
+    path::Path,
+};
+
+use csv::ReaderBuilder;
+
+use crate::{output::Output, Args};
+
+/// Compares two CSV outputs of earlier runs by record ID and writes the records whose grade
+/// changed, appeared or disappeared. Returns whether the outputs agree.
+pub fn diff(old: &Path, new: &Path, args: &Args) -> Result<bool> {
+    let old = read_grades(old, args)?;
+    let new = read_grades(new, args)?;
+
+    let mut out = Output::new(args.output.as_deref())?;
+    let mut writer = args.csv_writer().from_writer(&mut out);
+
+    let mut ids = old.keys().chain(new.keys()).collect::<Vec<_>>();
+    let mut same = true;
+
+    ids.sort();
+    ids.dedup();
+
+    writer.write_record(["id", "change", "old_grade", "new_grade"])?;
+
+    for id in ids {
+        let old = old.get(id).map_or("", String::as_str);
+        let new = new.get(id).map_or("", String::as_str);
+
+        let change = match (old.is_empty(), new.is_empty()) {
+            _ if old == new => continue,
+            (true, _) => "appeared",
+            (_, true) => "disappeared",
+            _ => "changed",
+        };
+
+        same = false;
+        writer.write_record([&id.to_string(), change, old, new])?;
+    }
+
+    writer.flush()?;
+    drop(writer);
+    out.commit()?;
+
+    Ok(same)
+}
+
+/// Reads the grades of an output, with the pay plan if there is one, keyed by record ID.
+fn read_grades(path: &Path, args: &Args) -> Result<BTreeMap<usize, String>> {
+    let mut reader = ReaderBuilder::new()
+        .delimiter(args.delimiter)
+        .flexible(true)
+        .has_headers(false)
+        .from_path(path)?;
+
+    let mut grades = BTreeMap::new();
+
+    for record in reader.records() {
+        let record = record?;
+
+        let id =
+            record.get(0).unwrap_or_default().parse().map_err(|e| {
+                Error::new(ErrorKind::InvalidData, format!("{}: {e}", path.display()))
+            })?;
+
+        let grade = record.get(1).unwrap_or_default();
+        let pay_plan = record.get(2).unwrap_or_default();
+
+        let grade = if grade.is_empty() || pay_plan.is_empty() {
+            grade.to_string()
+        } else {
+            format!("{pay_plan}-{grade}")
+        };
+
+        grades.insert(id, grade);
+    }
+
+    Ok(grades)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf, process};
+
+    use clap::Parser;
+
+    use crate::diff::*;
+
+    /// Compares outputs with the texts `old` and `new`, writing to a temporary file, and returns
+    /// the result and what was written.
+    fn diffed(name: &str, old: &str, new: &str) -> Result<(bool, String)> {
+        let (old_path, new_path) = (
+            temp(&format!("{name}-old.csv")),
+            temp(&format!("{name}-new.csv")),
+        );
+        let output = temp(&format!("{name}.csv"));
+        let args = Args::parse_from([
+            "fpl",
+            "diff",
+            "--output",
+            output.to_str().unwrap(),
+            old_path.to_str().unwrap(),
+            new_path.to_str().unwrap(),
+        ]);
+
+        fs::write(&old_path, old)?;
+        fs::write(&new_path, new)?;
+
+        let same = diff(&old_path, &new_path, &args);
+
+        fs::remove_file(old_path)?;
+        fs::remove_file(new_path)?;
+
+        let same = same?;
+        let printed = fs::read_to_string(&output)?;
+
+        fs::remove_file(output)?;
+        Ok((same, printed))
+    }
+
+    fn temp(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("fpl-{}-{name}", process::id()))
+    }
+
+    #[test]
+    fn test_diff() {
+        let (same, printed) = diffed(
+            "diff",
+            "1,12,gs\n2,13\n3,\n4,10,wg\n5,9\n",
+            "1,12,gs\n2,14\n3,7\n4,10,gs\n6,11\n",
+        )
+        .unwrap();
+
+        assert!(!same);
+        assert_eq!(
+            printed,
+            concat!(
+                "id,change,old_grade,new_grade\n",
+                "2,changed,13,14\n",
+                "3,appeared,,7\n",
+                "4,changed,wg-10,gs-10\n",
+                "5,disappeared,9,\n",
+                "6,appeared,,11\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_diff_invalid_id() {
+        let error = diffed("diff-invalid-id", "id,grade\n1,12\n", "1,12\n").unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(error.to_string().ends_with("invalid digit found in string"));
+    }
+
+    #[test]
+    fn test_diff_same() {
+        let (same, printed) = diffed("diff-same", "1,12,gs\n2,\n", "2,\n1,12,gs,extra\n").unwrap();
+
+        assert!(same);
+        assert_eq!(printed, "id,change,old_grade,new_grade\n");
+    }
+}
@@ -0,0 +1,311 @@
+use std::io::{Cursor, Error, ErrorKind, Read, Result};
+
+use clap::ValueEnum;
+
+/// The number of bytes the encoding is guessed from.
+const SAMPLE: usize = 1024;
+
+/// The characters of the Windows-1252 bytes from 0x80 to 0x9f. The bytes it leaves undefined are
+/// mapped to the C1 control characters, like the rest of the bytes are mapped to Latin-1.
+const WINDOWS_1252: [char; 32] = [
+    '\u{20ac}', '\u{81}', '\u{201a}', '\u{192}', '\u{201e}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{2c6}', '\u{2030}', '\u{160}', '\u{2039}', '\u{152}', '\u{8d}', '\u{17d}', '\u{8f}',
+    '\u{90}', '\u{2018}', '\u{2019}', '\u{201c}', '\u{201d}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{2dc}', '\u{2122}', '\u{161}', '\u{203a}', '\u{153}', '\u{9d}', '\u{17e}', '\u{178}',
+];
+
+/// Decodes the content of an input file into UTF-8 as it is read, without its byte order mark.
+pub struct Decoder<R> {
+    /// The decoded bytes not returned yet.
+    decoded: Vec<u8>,
+    encoding: Encoding,
+    /// Whether `inner` is read to its end.
+    eof: bool,
+    /// Whether the rest of the input is read as Windows-1252 once it stops being UTF-8, as
+    /// detected UTF-8 does.
+    fallback: bool,
+    inner: R,
+    /// The bytes read but not decoded yet, such as the start of a character split between reads.
+    pending: Vec<u8>,
+    position: usize,
+    /// Whether nothing has been decoded yet, so that a byte order mark is still to be removed.
+    start: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum Encoding {
+    /// Detects UTF-16 by its byte order mark or zero bytes, and falls back from UTF-8 to
+    /// Windows-1252.
+    Auto,
+    #[value(name = "utf-8", alias = "utf8")]
+    Utf8,
+    #[value(name = "utf-16be", alias = "utf16be")]
+    Utf16Be,
+    #[value(name = "utf-16le", alias = "utf16le")]
+    Utf16Le,
+    #[value(name = "windows-1252", alias = "cp1252")]
+    Windows1252,
+}
+
+impl<R: Read> Decoder<R> {
+    /// Starts decoding `inner`, guessing an `Auto` encoding from the first bytes.
+    pub fn new(mut inner: R, encoding: Encoding) -> Result<Self> {
+        let mut pending = Vec::new();
+        let mut eof = false;
+
+        let (encoding, fallback) = match encoding {
+            Encoding::Auto => {
+                eof = (&mut inner).take(SAMPLE as u64).read_to_end(&mut pending)? < SAMPLE;
+
+                match detect(&pending) {
+                    Encoding::Utf8 => (Encoding::Utf8, true),
+                    encoding => (encoding, false),
+                }
+            }
+            encoding => (encoding, false),
+        };
+
+        Ok(Self {
+            decoded: Vec::new(),
+            encoding,
+            eof,
+            fallback,
+            inner,
+            pending,
+            position: 0,
+            start: true,
+        })
+    }
+
+    /// Decodes as much of the pending bytes as makes whole characters, or all of them at the end
+    /// of the input.
+    fn decode(&mut self) -> Result<()> {
+        let used = match self.encoding {
+            Encoding::Utf16Be => utf16(
+                &self.pending,
+                self.eof,
+                u16::from_be_bytes,
+                &mut self.decoded,
+            )?,
+            Encoding::Utf16Le => utf16(
+                &self.pending,
+                self.eof,
+                u16::from_le_bytes,
+                &mut self.decoded,
+            )?,
+            Encoding::Windows1252 => windows_1252(&self.pending, &mut self.decoded),
+            _ => match std::str::from_utf8(&self.pending) {
+                Ok(_) => {
+                    self.decoded.extend_from_slice(&self.pending);
+                    self.pending.len()
+                }
+                Err(error) => {
+                    let valid = error.valid_up_to();
+
+                    self.decoded.extend_from_slice(&self.pending[..valid]);
+
+                    if error.error_len().is_none() && !self.eof {
+                        valid
+                    } else if self.fallback {
+                        self.encoding = Encoding::Windows1252;
+                        valid + windows_1252(&self.pending[valid..], &mut self.decoded)
+                    } else {
+                        return Err(Error::new(ErrorKind::InvalidData, error));
+                    }
+                }
+            },
+        };
+
+        self.pending.drain(..used);
+
+        if self.start && !self.decoded.is_empty() {
+            self.start = false;
+
+            if self.decoded.starts_with("\u{feff}".as_bytes()) {
+                self.decoded.drain(..3);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for Decoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        while self.position == self.decoded.len() {
+            self.decoded.clear();
+            self.position = 0;
+
+            if self.eof && self.pending.is_empty() {
+                return Ok(0);
+            }
+
+            if !self.eof {
+                let mut chunk = [0; 64 * 1024];
+                let read = self.inner.read(&mut chunk)?;
+
+                self.eof = read == 0;
+                self.pending.extend_from_slice(&chunk[..read]);
+            }
+
+            self.decode()?;
+        }
+
+        let length = buf.len().min(self.decoded.len() - self.position);
+
+        buf[..length].copy_from_slice(&self.decoded[self.position..][..length]);
+        self.position += length;
+        Ok(length)
+    }
+}
+
+/// Decodes the content of an input file into UTF-8, without its byte order mark.
+pub fn decode(bytes: Vec<u8>, encoding: Encoding) -> Result<Vec<u8>> {
+    let mut text = Vec::with_capacity(bytes.len());
+
+    Decoder::new(Cursor::new(bytes), encoding)?.read_to_end(&mut text)?;
+    Ok(text)
+}
+
+/// Guesses the encoding of the content of an input file from its first bytes.
+pub fn detect(bytes: &[u8]) -> Encoding {
+    if bytes.starts_with(&[0xff, 0xfe]) {
+        return Encoding::Utf16Le;
+    }
+
+    if bytes.starts_with(&[0xfe, 0xff]) {
+        return Encoding::Utf16Be;
+    }
+
+    let sample = &bytes[0..bytes.len().min(SAMPLE) & !1];
+    let zeros = |parity| {
+        sample
+            .iter()
+            .skip(parity)
+            .step_by(2)
+            .filter(|&&b| b == 0)
+            .count()
+    };
+
+    if zeros(1) * 4 > sample.len() {
+        Encoding::Utf16Le
+    } else if zeros(0) * 4 > sample.len() {
+        Encoding::Utf16Be
+    } else {
+        // The sample can end in the middle of a character.
+        match std::str::from_utf8(&bytes[0..bytes.len().min(SAMPLE)]) {
+            Err(error) if error.error_len().is_some() => Encoding::Windows1252,
+            _ => Encoding::Utf8,
+        }
+    }
+}
+
+/// Decodes the whole UTF-16 characters of `bytes` into `text`, and returns the number of bytes
+/// decoded.
+fn utf16(bytes: &[u8], eof: bool, unit: fn([u8; 2]) -> u16, text: &mut Vec<u8>) -> Result<usize> {
+    if eof && !bytes.len().is_multiple_of(2) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "UTF-16 input has an odd number of bytes",
+        ));
+    }
+
+    let mut units = bytes
+        .chunks_exact(2)
+        .map(|c| unit([c[0], c[1]]))
+        .collect::<Vec<_>>();
+
+    // A high surrogate at the end of a read is completed by the next one.
+    if !eof && units.last().is_some_and(|u| (0xd800..0xdc00).contains(u)) {
+        units.pop();
+    }
+
+    for c in char::decode_utf16(units.iter().copied()) {
+        let c = c.map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        text.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+    }
+
+    Ok(units.len() * 2)
+}
+
+/// Decodes `bytes` into `text`, and returns the number of bytes decoded, which is all of them.
+fn windows_1252(bytes: &[u8], text: &mut Vec<u8>) -> usize {
+    for &b in bytes {
+        let c = match b {
+            0x80..=0x9f => WINDOWS_1252[b as usize - 0x80],
+            _ => b as char,
+        };
+
+        text.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+    }
+
+    bytes.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use crate::encoding::*;
+
+    /// Reads one byte at a time, so that every character is split between reads.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            let Some((&byte, rest)) = self.0.split_first() else {
+                return Ok(0);
+            };
+
+            buf[0] = byte;
+            self.0 = rest;
+            Ok(1)
+        }
+    }
+
+    fn read(bytes: &[u8], encoding: Encoding) -> Result<String> {
+        let mut text = String::new();
+
+        Decoder::new(Trickle(bytes), encoding)?.read_to_string(&mut text)?;
+        Ok(text)
+    }
+
+    #[test]
+    fn test_decoder() {
+        let text = "GS-12 \u{e9}\u{20ac} \u{1f600}";
+        let utf16le = text
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect::<Vec<_>>();
+        let utf16be = [0xfe, 0xff]
+            .into_iter()
+            .chain(text.encode_utf16().flat_map(u16::to_be_bytes))
+            .collect::<Vec<_>>();
+
+        assert_eq!(read(text.as_bytes(), Encoding::Auto).unwrap(), text);
+        assert_eq!(read(text.as_bytes(), Encoding::Utf8).unwrap(), text);
+        assert_eq!(read(&utf16le, Encoding::Auto).unwrap(), text);
+        assert_eq!(read(&utf16le, Encoding::Utf16Le).unwrap(), text);
+        assert_eq!(read(&utf16be, Encoding::Auto).unwrap(), text);
+        assert_eq!(read(b"\xef\xbb\xbfGS-12", Encoding::Auto).unwrap(), "GS-12");
+        assert_eq!(
+            read(b"GS-12 \x80", Encoding::Auto).unwrap(),
+            "GS-12 \u{20ac}"
+        );
+        assert_eq!(
+            read(b"GS-12 \x80", Encoding::Windows1252).unwrap(),
+            "GS-12 \u{20ac}"
+        );
+        assert!(read(b"GS-12 \x80", Encoding::Utf8).is_err());
+        assert!(read(b"G\0S\0-", Encoding::Utf16Le).is_err());
+
+        let mut late = vec![b'a'; 2 * SAMPLE];
+
+        late.extend_from_slice(b"\xc3\xa9 \x80\xe9");
+
+        assert_eq!(
+            read(&late, Encoding::Auto).unwrap(),
+            "a".repeat(2 * SAMPLE) + "\u{e9} \u{20ac}\u{e9}"
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use std::{
+    io::{Result, Write},
+    sync::Arc,
+};
+
+use fpl::{explain_with, extract_with, Grade, Rules};
+
+use crate::{input::Record, output::Output, preprocess_records, Args};
+
+/// The number of characters shown from where parsing stopped.
+const CONTEXT: usize = 30;
+
+/// Writes every trigger phrase found in `text` with the connector and grades after it, or where
+/// parsing stopped, followed by the grade extracted. Returns whether a grade was found.
+pub fn explain(text: &str, rules: &Rules, args: &Args) -> Result<bool> {
+    let mut records = [Record {
+        id: 0,
+        normalized: None,
+        source: Arc::from(""),
+        text: text.to_string(),
+    }];
+
+    preprocess_records(&mut records, args);
+
+    let text = records[0].input();
+    let attempts = explain_with(rules, text);
+    let extraction = extract_with(rules, text);
+    let mut out = Output::new(args.output.as_deref())?;
+
+    if attempts.is_empty() {
+        writeln!(out, "No trigger phrase found")?;
+    }
+
+    for (n, attempt) in attempts.iter().enumerate() {
+        let grades = attempt
+            .ladder
+            .iter()
+            .map(|&g| format_grade(g))
+            .collect::<Vec<_>>();
+
+        let rest = text[attempt.end..]
+            .chars()
+            .take(CONTEXT)
+            .collect::<String>();
+
+        writeln!(
+            out,
+            "Trigger {}: {:?} at {}..{} ({})",
+            n + 1,
+            attempt.trigger,
+            attempt.offset,
+            attempt.offset + attempt.trigger.len(),
+            attempt.rule.as_str()
+        )?;
+
+        writeln!(out, "  Connector: {:?}", attempt.connector)?;
+
+        if grades.is_empty() {
+            writeln!(out, "  Grades: none")?;
+        } else {
+            writeln!(out, "  Grades: {}", grades.join(", "))?;
+        }
+
+        if rest.is_empty() {
+            writeln!(out, "  Stopped at {}: end of text", attempt.end)?;
+        } else {
+            writeln!(out, "  Stopped at {}: {rest:?}", attempt.end)?;
+        }
+    }
+
+    match &extraction {
+        Some(extraction) => {
+            let n = attempts
+                .iter()
+                .position(|a| a.offset == extraction.trigger_start)
+                .map_or(0, |n| n + 1);
+
+            let pay_plan = extraction
+                .pay_plan
+                .map(|p| format!("{}-", p.to_uppercase()))
+                .unwrap_or_default();
+
+            writeln!(
+                out,
+                "Result: {pay_plan}{} from trigger {n} ({}, confidence {:.2}{})",
+                extraction.grade,
+                extraction.rule.as_str(),
+                extraction.confidence,
+                if extraction.valid { "" } else { ", invalid" }
+            )?;
+        }
+        None => writeln!(out, "Result: none")?,
+    }
+
+    out.commit()?;
+
+    Ok(extraction.is_some())
+}
+
+/// Writes the grade with the text it was read from when that differs, e.g. `10 (ten)`.
+fn format_grade(grade: Grade) -> String {
+    let mut text = grade.to_string();
+
+    if grade.text != grade.grade {
+        text.push_str(&format!(" ({})", grade.text));
+    }
+
+    if !grade.is_valid() {
+        text.push_str(" (invalid)");
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use clap::Parser;
+
+    use crate::explain::*;
+
+    /// Explains `text`, writing to a temporary file, and returns the result and what was written.
+    fn explained(name: &str, text: &str) -> (bool, String) {
+        let output = env::temp_dir().join(format!("fpl-{}-{name}.txt", process::id()));
+        let args = Args::parse_from(["fpl", "explain", "--output", output.to_str().unwrap(), text]);
+        let found = explain(text, &Rules::default(), &args).unwrap();
+        let printed = fs::read_to_string(&output).unwrap();
+
+        fs::remove_file(output).unwrap();
+        (found, printed)
+    }
+
+    #[test]
+    fn test_explain() {
+        assert_eq!(
+            explained("explain", "Promotion potential to GS-0343-12. FPL: ten"),
+            (
+                true,
+                concat!(
+                    "Trigger 1: \"Promotion potential\" at 0..19 (promotion_potential)\n",
+                    "  Connector: \" to \"\n",
+                    "  Grades: GS-0343-12\n",
+                    "  Stopped at 33: \". FPL: ten\"\n",
+                    "Trigger 2: \"FPL\" at 35..38 (fpl_abbrev)\n",
+                    "  Connector: \": \"\n",
+                    "  Grades: 10 (ten)\n",
+                    "  Stopped at 43: end of text\n",
+                    "Result: GS-12 from trigger 1 (promotion_potential, confidence 0.98)\n",
+                )
+                .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_explain_invalid() {
+        assert_eq!(
+            explained("explain-invalid", "The full performance level is GS-17"),
+            (
+                true,
+                concat!(
+                    "Trigger 1: \"full performance level\" at 4..26 (full_performance)\n",
+                    "  Connector: \" is \"\n",
+                    "  Grades: GS-17 (invalid)\n",
+                    "  Stopped at 35: end of text\n",
+                    "Result: GS-17 from trigger 1 (full_performance, confidence 0.49, invalid)\n",
+                )
+                .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_explain_none() {
+        assert_eq!(
+            explained("explain-none", "No grade here"),
+            (false, "No trigger phrase found\nResult: none\n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_grade() {
+        let grade = |grade, text, pay_plan, series| Grade {
+            grade,
+            text,
+            pay_plan,
+            series,
+            kind: fpl::Kind::Grade,
+        };
+
+        assert_eq!(format_grade(grade("12", "12", Some("gs"), None)), "GS-12");
+        assert_eq!(format_grade(grade("10", "ten", None, None)), "10 (ten)");
+        assert_eq!(
+            format_grade(grade("17", "17", Some("gs"), Some("0343"))),
+            "GS-0343-17 (invalid)"
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use std::{
+    io::{Error, ErrorKind, Result, Write},
+    process::{Command, Stdio},
+    sync::Arc,
+};
+
+use serde_json::Value;
+
+use crate::input::Record;
+
+/// The number of announcements requested per page, the maximum the API allows.
+const PAGE_SIZE: usize = 500;
+
+const SEARCH_URL: &str = "https://data.usajobs.gov/api/search";
+
+/// Searches the USAJOBS API for the announcements matching `keyword` and returns their duties and
+/// qualifications as records identified by their announcement numbers.
+///
+/// The requests are made with `curl`, which has to be installed. The API key and the email it was
+/// requested with are passed to it on standard input, so that they do not show in process lists.
+pub fn fetch(keyword: &str, api_key: &str, email: &str) -> Result<Vec<Record>> {
+    let source: Arc<str> = SEARCH_URL.into();
+    let mut records = Vec::new();
+    let mut page = 1;
+
+    loop {
+        let response = search(keyword, api_key, email, page)?;
+        let result = &response["SearchResult"];
+
+        for item in result["SearchResultItems"].as_array().into_iter().flatten() {
+            records.push(Record {
+                id: announcement_id(item)?,
+                normalized: None,
+                source: source.clone(),
+                text: announcement_text(&item["MatchedObjectDescriptor"]),
+            });
+        }
+
+        let pages = number(&result["UserArea"]["NumberOfPages"]).unwrap_or_default();
+
+        if page >= pages {
+            return Ok(records);
+        }
+
+        page += 1;
+    }
+}
+
+fn announcement_id(item: &Value) -> Result<usize> {
+    number(&item["MatchedObjectId"]).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            "USAJOBS announcement without a numeric MatchedObjectId",
+        )
+    })
+}
+
+/// Joins the major duties and the qualification summary of an announcement, the parts that state
+/// its full performance level.
+fn announcement_text(descriptor: &Value) -> String {
+    let duties = descriptor["UserArea"]["Details"]["MajorDuties"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str);
+
+    let qualifications = descriptor["QualificationSummary"].as_str();
+
+    duties.chain(qualifications).collect::<Vec<_>>().join("\n")
+}
+
+/// Reads a number the API returns either as a JSON number or as a string.
+fn number(value: &Value) -> Option<usize> {
+    match value {
+        Value::Number(number) => number.as_u64().map(|n| n as usize),
+        Value::String(text) => text.parse().ok(),
+        _ => None,
+    }
+}
+
+fn search(keyword: &str, api_key: &str, email: &str, page: usize) -> Result<Value> {
+    let mut curl = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--get", SEARCH_URL])
+        .args(["--header", "@-"])
+        .arg("--data-urlencode")
+        .arg(format!("Keyword={keyword}"))
+        .args(["--data", &format!("ResultsPerPage={PAGE_SIZE}")])
+        .args(["--data", &format!("Page={page}")])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| Error::new(e.kind(), format!("cannot run curl: {e}")))?;
+
+    if let Some(mut stdin) = curl.stdin.take() {
+        write!(stdin, "User-Agent: {email}\nAuthorization-Key: {api_key}\n")?;
+    }
+
+    let output = curl.wait_with_output()?;
+
+    if !output.status.success() {
+        return Err(Error::other(format!(
+            "USAJOBS request failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    serde_json::from_slice(&output.stdout).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::fetch::*;
+
+    #[test]
+    fn test_announcement_id() {
+        assert_eq!(
+            announcement_id(&json!({ "MatchedObjectId": "812345" })).unwrap(),
+            812345
+        );
+        assert_eq!(
+            announcement_id(&json!({ "MatchedObjectId": "none" }))
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn test_announcement_text() {
+        let descriptor = json!({
+            "QualificationSummary": "Specialized experience at the GS-11 level.",
+            "UserArea": {
+                "Details": {
+                    "MajorDuties": ["Reviews claims.", 7, "The full performance level is GS-12."]
+                }
+            }
+        });
+
+        assert_eq!(
+            announcement_text(&descriptor),
+            concat!(
+                "Reviews claims.\n",
+                "The full performance level is GS-12.\n",
+                "Specialized experience at the GS-11 level."
+            )
+        );
+        assert_eq!(
+            announcement_text(&json!({ "QualificationSummary": "GS-9" })),
+            "GS-9"
+        );
+        assert_eq!(
+            announcement_text(&json!({ "UserArea": { "Details": { "MajorDuties": ["GS-7"] } } })),
+            "GS-7"
+        );
+        assert_eq!(announcement_text(&json!({})), "");
+    }
+
+    #[test]
+    fn test_number() {
+        assert_eq!(number(&json!(3)), Some(3));
+        assert_eq!(number(&json!("3")), Some(3));
+        assert_eq!(number(&json!(-3)), None);
+        assert_eq!(number(&json!(3.5)), None);
+        assert_eq!(number(&json!("three")), None);
+        assert_eq!(number(&json!(null)), None);
+    }
+}
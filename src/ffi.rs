@@ -0,0 +1,82 @@
+//! A C interface to the extractor, enabled by the `ffi` feature. Build it as a shared library
+//! with `cargo rustc --release --lib --features ffi --crate-type cdylib` and declare it with
+//! `include/fpl.h`.
+
+use std::ffi::{c_char, c_int, CStr};
+
+use crate::extract;
+
+/// The grade found in a text. The strings are NUL-terminated and the offsets are byte offsets
+/// into the UTF-8 text.
+#[repr(C)]
+pub struct FplResult {
+    pub grade: u32,
+    pub pay_plan: [c_char; 8],
+    pub series: [c_char; 8],
+    pub rule: [c_char; 32],
+    pub confidence: f64,
+    /// The start of the trigger phrase.
+    pub trigger_start: usize,
+    /// The start of the grade.
+    pub start: usize,
+    /// The end of the grade, exclusive.
+    pub end: usize,
+}
+
+/// Extracts the full performance level from the NUL-terminated UTF-8 `text` into `result`.
+/// Returns 1 if a grade was found, 0 if not, leaving `result` zeroed, and -1 if `text` or `result`
+/// is null or `text` is not valid UTF-8.
+///
+/// # Safety
+///
+/// `text` must be null or point to a NUL-terminated string and `result` must be null or point to
+/// writable memory for an `FplResult`.
+#[no_mangle]
+pub unsafe extern "C" fn fpl_extract(text: *const c_char, result: *mut FplResult) -> c_int {
+    if text.is_null() || result.is_null() {
+        return -1;
+    }
+
+    let Ok(text) = CStr::from_ptr(text).to_str() else {
+        return -1;
+    };
+
+    let result = &mut *result;
+
+    *result = FplResult {
+        grade: 0,
+        pay_plan: [0; 8],
+        series: [0; 8],
+        rule: [0; 32],
+        confidence: 0.0,
+        trigger_start: 0,
+        start: 0,
+        end: 0,
+    };
+
+    let Some(extraction) = extract(text) else {
+        return 0;
+    };
+
+    let pay_plan = extraction.pay_plan.unwrap_or_default().to_uppercase();
+
+    result.grade = extraction.grade.parse().unwrap_or_default();
+    copy(&mut result.pay_plan, &pay_plan);
+    copy(&mut result.series, extraction.series.unwrap_or_default());
+    copy(&mut result.rule, extraction.rule.as_str());
+    result.confidence = extraction.confidence;
+    result.trigger_start = extraction.trigger_start;
+    result.start = extraction.start;
+    result.end = extraction.end;
+
+    1
+}
+
+/// Copies `s` into `buffer`, truncating it to leave room for the terminating NUL.
+fn copy(buffer: &mut [c_char], s: &str) {
+    let length = s.len().min(buffer.len() - 1);
+
+    for (c, &b) in buffer.iter_mut().zip(&s.as_bytes()[0..length]) {
+        *c = b as c_char;
+    }
+}
@@ -0,0 +1,315 @@
+use std::{
+    fs,
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+};
+
+use crate::compression::Compression;
+
+/// The extensions of the files read from directories.
+const EXTENSIONS: [&str; 3] = ["csv", "tsv", "xlsx"];
+
+/// The extensions of the files read from directories when they are compressed.
+const COMPRESSED_EXTENSIONS: [&str; 2] = ["csv", "tsv"];
+
+/// Expands the input paths into the files to read.
+///
+/// A directory stands for the input files in it, including those in its subdirectories if
+/// `recursive` is set. A path that does not exist is treated as a pattern where `*` and `?` match
+/// within a file name and `**` matches any number of directories.
+pub fn expand(paths: &[PathBuf], recursive: bool) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for path in paths {
+        if path.is_dir() {
+            let start = files.len();
+
+            list(path, recursive, &mut files)?;
+            files[start..].sort();
+        } else if !path.exists() && is_pattern(path) {
+            let start = files.len();
+            let (base, components) = split_pattern(path);
+
+            walk(&base, &components, &mut files)?;
+
+            if files.len() == start {
+                return Err(Error::new(
+                    ErrorKind::NotFound,
+                    format!("no files match '{}'", path.display()),
+                ));
+            }
+
+            files[start..].sort();
+        } else {
+            files.push(path.clone());
+        }
+    }
+
+    Ok(files)
+}
+
+fn entries(dir: &Path) -> Result<Vec<(PathBuf, bool)>> {
+    let read_dir = if dir.as_os_str().is_empty() {
+        fs::read_dir(".")?
+    } else {
+        fs::read_dir(dir)?
+    };
+
+    read_dir
+        .map(|entry| {
+            let path = dir.join(entry?.file_name());
+            let is_dir = path.is_dir();
+
+            Ok((path, is_dir))
+        })
+        .collect()
+}
+
+fn has_input_extension(path: &Path) -> bool {
+    let (path, extensions) = match Compression::of(path) {
+        Some(_) => (path.with_extension(""), &COMPRESSED_EXTENSIONS[..]),
+        None => (path.to_path_buf(), &EXTENSIONS[..]),
+    };
+
+    path.extension()
+        .is_some_and(|e| extensions.iter().any(|x| e.eq_ignore_ascii_case(x)))
+}
+
+fn is_pattern(path: &Path) -> bool {
+    path.to_string_lossy().contains(['*', '?'])
+}
+
+fn list(dir: &Path, recursive: bool, files: &mut Vec<PathBuf>) -> Result<()> {
+    for (path, is_dir) in entries(dir)? {
+        if is_dir {
+            if recursive {
+                list(&path, recursive, files)?;
+            }
+        } else if has_input_extension(&path) {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Matches a file name against a pattern component, where `*` matches any number of characters
+/// and `?` matches one. Hidden files only match patterns that start with a dot.
+///
+/// On a mismatch, the last `*` is retried a character further instead of recursing, which keeps
+/// the matching linear in the length of the name for each `*`.
+fn matches(pattern: &str, name: &str) -> bool {
+    if name.starts_with('.') && !pattern.starts_with('.') {
+        return false;
+    }
+
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+    let (mut p, mut n) = (0, 0);
+    let mut star = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    star = Some((star_p, star_n + 1));
+                    p = star_p + 1;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Splits a pattern into the directory without wildcards it starts in and the components after it.
+fn split_pattern(path: &Path) -> (PathBuf, Vec<String>) {
+    let mut base = PathBuf::new();
+    let mut components = Vec::new();
+
+    for component in path.components() {
+        let text = component.as_os_str().to_string_lossy();
+
+        if components.is_empty() && !text.contains(['*', '?']) {
+            base.push(component);
+        } else {
+            components.push(text.into_owned());
+        }
+    }
+
+    (base, components)
+}
+
+fn walk(dir: &Path, components: &[String], files: &mut Vec<PathBuf>) -> Result<()> {
+    let Some((component, rest)) = components.split_first() else {
+        return Ok(());
+    };
+
+    if component == "**" {
+        walk(dir, rest, files)?;
+
+        for (path, is_dir) in entries(dir)? {
+            let hidden = path
+                .file_name()
+                .is_some_and(|n| n.to_string_lossy().starts_with('.'));
+
+            if is_dir && !hidden {
+                walk(&path, components, files)?;
+            }
+        }
+
+        return Ok(());
+    }
+
+    for (path, is_dir) in entries(dir)? {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+        if !matches(component, &name) {
+            continue;
+        }
+
+        if rest.is_empty() {
+            if !is_dir {
+                files.push(path);
+            }
+        } else if is_dir {
+            walk(&path, rest, files)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process, slice};
+
+    use crate::glob::*;
+
+    fn temp(name: &str, files: &[&str]) -> PathBuf {
+        let dir = env::temp_dir().join(format!("fpl-{}-{name}", process::id()));
+
+        let _ = fs::remove_dir_all(&dir);
+
+        for file in files {
+            let path = dir.join(file);
+
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        dir
+    }
+
+    #[test]
+    fn test_expand_hidden() {
+        let dir = temp(
+            "glob-hidden",
+            &[".a.csv", "b.csv", ".hidden/c.csv", "d/e.csv"],
+        );
+
+        assert_eq!(
+            expand(&[dir.join("*.csv")], false).unwrap(),
+            [dir.join("b.csv")]
+        );
+        assert_eq!(
+            expand(&[dir.join(".*.csv")], false).unwrap(),
+            [dir.join(".a.csv")]
+        );
+        assert_eq!(
+            expand(&[dir.join("**/*.csv")], false).unwrap(),
+            [dir.join("b.csv"), dir.join("d/e.csv")]
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_expand_nested() {
+        let dir = temp(
+            "glob-nested",
+            &[
+                "a/x/1.csv",
+                "a/y/2.csv",
+                "a/y/3.tsv",
+                "b/x/4.csv",
+                "a/z/w/5.csv",
+            ],
+        );
+
+        assert_eq!(
+            expand(&[dir.join("a/*/*.csv")], false).unwrap(),
+            [dir.join("a/x/1.csv"), dir.join("a/y/2.csv")]
+        );
+        assert_eq!(
+            expand(&[dir.join("*/x/*.csv")], false).unwrap(),
+            [dir.join("a/x/1.csv"), dir.join("b/x/4.csv")]
+        );
+        assert_eq!(
+            expand(&[dir.join("a/**/*.csv")], false).unwrap(),
+            [
+                dir.join("a/x/1.csv"),
+                dir.join("a/y/2.csv"),
+                dir.join("a/z/w/5.csv")
+            ]
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_expand_no_match() {
+        let dir = temp("glob-no-match", &["a.csv"]);
+        let pattern = dir.join("*.tsv");
+        let error = expand(slice::from_ref(&pattern), false).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+        assert_eq!(
+            error.to_string(),
+            format!("no files match '{}'", pattern.display())
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_expand_question_mark() {
+        let dir = temp("glob-question-mark", &["a1.csv", "a12.csv", "b1.csv"]);
+
+        assert_eq!(
+            expand(&[dir.join("a?.csv")], false).unwrap(),
+            [dir.join("a1.csv")]
+        );
+        assert_eq!(
+            expand(&[dir.join("??.csv")], false).unwrap(),
+            [dir.join("a1.csv"), dir.join("b1.csv")]
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(matches("*", "a.csv"));
+        assert!(matches("*.csv", "a.b.csv"));
+        assert!(matches("a*b*c", "abc"));
+        assert!(matches("a*b*c", "axxbyyc"));
+        assert!(matches("a?c", "abc"));
+        assert!(matches("**", "a"));
+        assert!(!matches("*", ".a"));
+        assert!(!matches("a*b", "ab c"));
+        assert!(!matches("a?c", "ac"));
+        assert!(!matches("a*d", "abc"));
+        assert!(!matches("*a*a*a*a*a*a*a*a*b", &"a".repeat(1000)));
+    }
+}
@@ -0,0 +1,432 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    fs::{self, File},
+    io::{Cursor, Error, ErrorKind, Read, Result},
+    num::ParseIntError,
+    path::{Path, PathBuf},
+    slice,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    vec,
+};
+
+use csv::{ReaderBuilder, StringRecord, StringRecordsIntoIter};
+
+use crate::{
+    compression::Compression,
+    encoding::{decode, Decoder, Encoding},
+    mmap::Map,
+    xlsx::read_sheet,
+    Args,
+};
+
+/// The byte order mark, which Excel exports start with and concatenated exports can contain.
+const BOM: char = '\u{feff}';
+
+/// Reads the records of all input files, one file after another.
+pub struct Reader<'a> {
+    args: &'a Args,
+    /// The number of bytes read from the inputs, for reporting progress.
+    bytes: Option<Arc<AtomicU64>>,
+    /// The size of the inputs read completely.
+    done: u64,
+    paths: slice::Iter<'a, PathBuf>,
+    table: Option<Table>,
+}
+
+struct Table {
+    delimiter: u8,
+    /// The number of fields of every record, checked when the CSV reader is lenient.
+    fields: Option<usize>,
+    headers: Option<StringRecord>,
+    id_index: usize,
+    /// The offset of the last record read.
+    position: u64,
+    records: Records,
+    /// The number of the last record read, not counting the header.
+    row: usize,
+    size: u64,
+    skip_bad_rows: bool,
+    source: Arc<str>,
+    text_index: usize,
+}
+
+enum Records {
+    Csv(StringRecordsIntoIter<Box<dyn Read>>),
+    Sheet(vec::IntoIter<StringRecord>),
+}
+
+/// Why a record could not be read, and where it is.
+#[derive(Debug)]
+pub struct RecordError {
+    pub source: Arc<str>,
+    /// The number of the record, not counting the header.
+    pub row: usize,
+    pub kind: RecordErrorKind,
+}
+
+#[derive(Debug)]
+pub enum RecordErrorKind {
+    /// The record is not valid CSV, e.g. it is not UTF-8 or has an unexpected number of fields.
+    Csv(csv::Error),
+    FieldCount {
+        fields: usize,
+        expected: usize,
+    },
+    InvalidId {
+        column: String,
+        value: String,
+        error: ParseIntError,
+    },
+    MissingField {
+        column: String,
+    },
+}
+
+pub struct Record {
+    pub id: usize,
+    /// The normalized text the grades are extracted from, if it differs from the emitted text.
+    pub normalized: Option<String>,
+    /// The file the record was read from.
+    pub source: Arc<str>,
+    pub text: String,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(args: &'a Args, paths: &'a [PathBuf]) -> Self {
+        Self {
+            args,
+            bytes: None,
+            done: 0,
+            paths: paths.iter(),
+            table: None,
+        }
+    }
+
+    /// Keeps `bytes` updated with the number of bytes read from the inputs.
+    pub fn track(mut self, bytes: Arc<AtomicU64>) -> Self {
+        self.bytes = Some(bytes);
+        self
+    }
+}
+
+impl Display for RecordError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}, row {}: ", self.source, self.row)?;
+
+        match &self.kind {
+            RecordErrorKind::Csv(error) => write!(f, "{error}"),
+            RecordErrorKind::FieldCount { fields, expected } => {
+                write!(f, "record has {fields} fields, expected {expected}")
+            }
+            RecordErrorKind::InvalidId {
+                column,
+                value,
+                error,
+            } => write!(f, "invalid id '{value}' in {column}: {error}"),
+            RecordErrorKind::MissingField { column } => write!(f, "record has no {column}"),
+        }
+    }
+}
+
+impl std::error::Error for RecordError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            RecordErrorKind::Csv(error) => Some(error),
+            RecordErrorKind::InvalidId { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<RecordError> for Error {
+    fn from(error: RecordError) -> Self {
+        Error::new(ErrorKind::InvalidData, error)
+    }
+}
+
+impl Record {
+    /// Returns the text the grades are extracted from.
+    pub fn input(&self) -> &str {
+        self.normalized.as_deref().unwrap_or(&self.text)
+    }
+}
+
+impl Table {
+    fn new(
+        args: &Args,
+        headers: Option<StringRecord>,
+        records: Records,
+        source: &str,
+    ) -> Result<Self> {
+        let (id_index, text_index) = match &headers {
+            Some(headers) => (
+                column_index(headers, args.id_column.as_deref(), 0)?,
+                column_index(headers, args.text_column.as_deref(), 1)?,
+            ),
+            None => (0, 1),
+        };
+
+        Ok(Self {
+            delimiter: args.delimiter,
+            fields: headers.as_ref().map(StringRecord::len),
+            headers,
+            id_index,
+            position: 0,
+            records,
+            row: 0,
+            size: 0,
+            skip_bad_rows: args.skip_bad_rows,
+            source: source.into(),
+            text_index,
+        })
+    }
+
+    fn open(args: &Args, path: &Path) -> Result<Self> {
+        let has_headers = has_headers(args);
+
+        let is_xlsx = path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("xlsx"));
+
+        let (headers, records) = if is_xlsx {
+            let mut rows = read_sheet(path, args.sheet.as_deref())?
+                .into_iter()
+                .map(StringRecord::from)
+                .collect::<Vec<_>>();
+
+            let headers = (has_headers && !rows.is_empty()).then(|| rows.remove(0));
+
+            (headers, Records::Sheet(rows.into_iter()))
+        } else {
+            let reader: Box<dyn Read> = if let Some(compression) = Compression::of(path) {
+                let decompressor = compression.decompress(path)?;
+
+                if args.encoding == Encoding::Utf8 {
+                    Box::new(decompressor)
+                } else {
+                    Box::new(Decoder::new(decompressor, args.encoding)?)
+                }
+            } else if args.mmap {
+                mapped(path, args.encoding)?
+            } else if args.encoding == Encoding::Utf8 {
+                Box::new(File::open(path)?)
+            } else {
+                Box::new(Decoder::new(File::open(path)?, args.encoding)?)
+            };
+
+            csv_records(args, reader)?
+        };
+
+        let mut table = Self::new(args, headers, records, &path.display().to_string())?;
+
+        table.size = fs::metadata(path)?.len();
+        Ok(table)
+    }
+
+    fn read(&mut self) -> Option<Result<StringRecord>> {
+        match &mut self.records {
+            Records::Csv(records) => {
+                let record = records.next()?;
+
+                self.row += 1;
+
+                if let Some(position) = record.as_ref().ok().and_then(StringRecord::position) {
+                    self.position = position.byte();
+                }
+
+                Some(record.map_err(|error| self.error(RecordErrorKind::Csv(error)).into()))
+            }
+            Records::Sheet(records) => {
+                let record = records.next()?;
+
+                self.row += 1;
+                Some(Ok(record))
+            }
+        }
+    }
+
+    /// Describes the column at `index`, by its name if the table has a header.
+    fn column(&self, index: usize) -> String {
+        match self.headers.as_ref().and_then(|h| h.get(index)) {
+            Some(name) => format!("field '{}'", name.trim_start_matches(BOM)),
+            None => format!("column {}", index + 1),
+        }
+    }
+
+    fn error(&self, kind: RecordErrorKind) -> RecordError {
+        RecordError {
+            source: self.source.clone(),
+            row: self.row,
+            kind,
+        }
+    }
+
+    fn field<'r>(&self, record: &'r StringRecord, index: usize) -> Result<&'r str> {
+        record.get(index).ok_or_else(|| {
+            let column = self.column(index);
+            self.error(RecordErrorKind::MissingField { column }).into()
+        })
+    }
+
+    /// Reports a record that is skipped because it could not be read.
+    fn warn_skipped(&self, error: &Error, raw: Option<&StringRecord>) {
+        let raw = raw.map(|r| {
+            let fields = r.iter().collect::<Vec<_>>();
+            fields.join(&char::from(self.delimiter).to_string())
+        });
+
+        eprintln!(
+            "Warning: skipping {error}: {}",
+            raw.as_deref().unwrap_or("(unreadable)")
+        );
+    }
+
+    fn record(&mut self, record: &StringRecord) -> Result<Record> {
+        if self.skip_bad_rows {
+            let expected = *self.fields.get_or_insert(record.len());
+
+            if record.len() != expected {
+                let fields = record.len();
+                return Err(self
+                    .error(RecordErrorKind::FieldCount { fields, expected })
+                    .into());
+            }
+        }
+
+        let value = self.field(record, self.id_index)?.trim_matches(BOM);
+
+        let id = value.parse().map_err(|error| {
+            self.error(RecordErrorKind::InvalidId {
+                column: self.column(self.id_index),
+                value: value.to_string(),
+                error,
+            })
+        })?;
+
+        let text = self.field(record, self.text_index)?.replace(BOM, "");
+
+        Ok(Record {
+            id,
+            normalized: None,
+            source: self.source.clone(),
+            text,
+        })
+    }
+}
+
+impl Iterator for Reader<'_> {
+    type Item = Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(table) = &mut self.table {
+                if let Some(record) = table.next() {
+                    if let Some(bytes) = &self.bytes {
+                        bytes.store(
+                            self.done + table.position.min(table.size),
+                            Ordering::Relaxed,
+                        );
+                    }
+
+                    return Some(record);
+                }
+
+                self.done += table.size;
+                self.table = None;
+
+                if let Some(bytes) = &self.bytes {
+                    bytes.store(self.done, Ordering::Relaxed);
+                }
+            }
+
+            let path = self.paths.next()?;
+
+            match Table::open(self.args, path) {
+                Ok(table) => self.table = Some(table),
+                Err(error) => {
+                    let message = format!("{}: {error}", path.display());
+                    return Some(Err(Error::new(error.kind(), message)));
+                }
+            }
+        }
+    }
+}
+
+impl Iterator for Table {
+    type Item = Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let record = self.read()?;
+            let (result, raw) = match record {
+                Ok(record) => (self.record(&record), Some(record)),
+                Err(error) => (Err(error), None),
+            };
+
+            match result {
+                Err(error) if self.skip_bad_rows => self.warn_skipped(&error, raw.as_ref()),
+                result => return Some(result),
+            }
+        }
+    }
+}
+
+fn column_index(headers: &StringRecord, name: Option<&str>, default: usize) -> Result<usize> {
+    let Some(name) = name else {
+        return Ok(default);
+    };
+
+    headers
+        .iter()
+        .position(|h| h.trim_start_matches(BOM) == name)
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("column '{name}' not found in header"),
+            )
+        })
+}
+
+fn csv_records(args: &Args, reader: Box<dyn Read>) -> Result<(Option<StringRecord>, Records)> {
+    let mut csv = ReaderBuilder::new()
+        .delimiter(args.delimiter)
+        .flexible(args.skip_bad_rows)
+        .has_headers(has_headers(args))
+        .from_reader(reader);
+
+    let headers = if has_headers(args) {
+        Some(csv.headers()?.clone())
+    } else {
+        None
+    };
+
+    Ok((headers, Records::Csv(csv.into_records())))
+}
+
+fn has_headers(args: &Args) -> bool {
+    args.headers || args.id_column.is_some() || args.text_column.is_some()
+}
+
+/// Reads a memory-mapped file in place if it is UTF-8, or decodes it as it is read otherwise. A
+/// file whose encoding is guessed is decoded too, which checks that it is UTF-8 as it goes
+/// rather than in a pass of its own, and falls back to Windows-1252 if it is not.
+fn mapped(path: &Path, encoding: Encoding) -> Result<Box<dyn Read>> {
+    let map = Map::open(path)?;
+
+    Ok(if encoding == Encoding::Utf8 {
+        Box::new(Cursor::new(map))
+    } else {
+        Box::new(Decoder::new(Cursor::new(map), encoding)?)
+    })
+}
+
+/// Reads the records of CSV data, such as an upload, in the same layout as the input files.
+pub fn read_csv(args: &Args, data: Vec<u8>, source: &str) -> Result<Vec<Record>> {
+    let data = decode(data, args.encoding)?;
+    let (headers, records) = csv_records(args, Box::new(Cursor::new(data)))?;
+
+    Table::new(args, headers, records, source)?.collect()
+}
@@ -0,0 +1,2061 @@
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod markup;
+mod pdf;
+mod rules;
+mod series;
+
+use std::fmt::{self, Display, Formatter};
+
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, tag_no_case, take_while},
+    character::complete::{alpha1, char, digit1, one_of},
+    combinator::{fail, map, opt, value, verify},
+    error::Error,
+    sequence::terminated,
+    IResult,
+};
+
+pub use markup::strip_html;
+pub use pdf::dehyphenate;
+pub use rules::Rules;
+pub use series::series_title;
+
+/// Hyphens and dashes, which scraped text often has in place of `-`.
+const DASHES: &str = "-\u{2010}\u{2011}\u{2012}\u{2013}\u{2014}\u{2212}";
+
+/// The characters separating a pay plan from its series or grade.
+const GRADE_SEPARATORS: &str = " \u{a0}.-\u{2010}\u{2011}\u{2012}\u{2013}\u{2014}\u{2212}";
+
+/// The characters separating the words of a trigger or a spelled number.
+const SEPARATORS: &str = " \u{a0}-\u{2010}\u{2011}\u{2012}\u{2013}\u{2014}\u{2212}";
+
+const SPACES: &str = " \u{a0}";
+
+/// The words allowed between an entry grade and the trigger after it, as in `GS-9 with a
+/// promotion potential of GS-12`.
+const ENTRY_CONNECTORS: [&str; 5] = ["a", "and", "has", "the", "with"];
+
+/// How many bytes before a trigger an entry grade is looked for.
+const ENTRY_WINDOW: usize = 32;
+
+/// The words saying that a position has no full performance level, as in `FPL: N/A`.
+const NO_FPL_WORDS: [&[&str]; 4] = [&["n/a"], &["na"], &["none"], &["not", "applicable"]];
+
+/// The words between the grades of an interval such as `gs-11 to gs-13`.
+const INTERVAL_WORDS: [&[&str]; 4] = [&["through"], &["thru"], &["up", "to"], &["to"]];
+
+/// The first letters of the full performance level triggers: `fpl`, `full`, `poll`, `promotion`,
+/// `journey`, `career`, `highest`, `maximum` and `max`.
+const FPL_TRIGGER_STARTS: &[u8] = b"cfhjmpCFHJMP";
+
+/// The first letters of the `target` trigger.
+const TARGET_TRIGGER_STARTS: &[u8] = b"tT";
+
+/// Pay plans of demonstration projects (e.g. AcqDemo or lab demos), whose grades are pay bands.
+const DEMO_PAY_PLANS: [&str; 10] = ["db", "de", "dj", "dk", "dp", "ds", "dt", "nh", "nj", "nk"];
+
+const MAX_GRADES: [(&str, u32); 5] = [("es", 0), ("gs", 15), ("wg", 15), ("wl", 15), ("ws", 19)];
+
+const NUMBERS: [&str; 100] = [
+    "0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16",
+    "17", "18", "19", "20", "21", "22", "23", "24", "25", "26", "27", "28", "29", "30", "31", "32",
+    "33", "34", "35", "36", "37", "38", "39", "40", "41", "42", "43", "44", "45", "46", "47", "48",
+    "49", "50", "51", "52", "53", "54", "55", "56", "57", "58", "59", "60", "61", "62", "63", "64",
+    "65", "66", "67", "68", "69", "70", "71", "72", "73", "74", "75", "76", "77", "78", "79", "80",
+    "81", "82", "83", "84", "85", "86", "87", "88", "89", "90", "91", "92", "93", "94", "95", "96",
+    "97", "98", "99",
+];
+
+/// Pay plans of senior positions, which are not graded or use grade `00` only.
+const SENIOR_PAY_PLANS: [&str; 4] = ["es", "ses", "sl", "st"];
+
+const ROMAN: [&str; 21] = [
+    "", "i", "ii", "iii", "iv", "v", "vi", "vii", "viii", "ix", "x", "xi", "xii", "xiii", "xiv",
+    "xv", "xvi", "xvii", "xviii", "xix", "xx",
+];
+
+const TENS: [&str; 10] = [
+    "", "ten", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+const UNITS: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+/// A list of grades parsed by `ladder_span`, with the byte range of each and the number of bytes
+/// after the end of the last grade that were consumed.
+type LadderSpan<'a> = (Vec<Grade<'a>>, Vec<(usize, usize)>, usize);
+
+/// A trigger phrase found in a text and how far the grades after it could be parsed, as reported
+/// by [`explain`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Attempt<'a> {
+    pub rule: Rule,
+    /// The byte offset of the trigger.
+    pub offset: usize,
+    pub trigger: &'a str,
+    /// The text between the trigger and the grades, such as ` of this position is `.
+    pub connector: &'a str,
+    /// The grades after the connector, empty if there was none.
+    pub ladder: Vec<Grade<'a>>,
+    /// The byte offset where parsing stopped: after the last grade, or where a grade was expected.
+    pub end: usize,
+}
+
+/// The full performance level found in a text, with the byte offsets of the trigger phrase and
+/// the grade in it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Extraction<'a> {
+    /// The grade, always written in digits.
+    pub grade: &'a str,
+    pub pay_plan: Option<&'a str>,
+    pub series: Option<&'a str>,
+    pub kind: Kind,
+    pub rule: Rule,
+    pub confidence: f64,
+    pub valid: bool,
+    pub trigger_start: usize,
+    /// The end of the trigger phrase, exclusive.
+    pub trigger_end: usize,
+    pub start: usize,
+    /// The end of the grade, exclusive.
+    pub end: usize,
+}
+
+/// A grade found in the text, optionally prefixed by its pay plan and occupational series.
+///
+/// `grade` is always written in digits, while `text` is the grade as it appears in the text
+/// (e.g. `thirteen`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Grade<'a> {
+    pub grade: &'a str,
+    pub text: &'a str,
+    pub pay_plan: Option<&'a str>,
+    pub series: Option<&'a str>,
+    pub kind: Kind,
+}
+
+/// A trigger phrase together with the grades it refers to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Mention<'a> {
+    pub rule: Rule,
+    pub trigger: &'a str,
+    /// Number of bytes between the end of the trigger and the first grade.
+    pub gap: usize,
+    /// Number of bytes from the start of the trigger to the end of the last grade.
+    pub end: usize,
+    pub ladder: Vec<Grade<'a>>,
+    /// The byte range of each grade of `ladder` from the start of the trigger, including its pay
+    /// plan when that is in the text, as in `GS-13` and `gs-11/12/13`, but not the `VN` of
+    /// `Nurse III`.
+    pub spans: Vec<(usize, usize)>,
+}
+
+/// The kind of trigger phrase a mention was found by.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Rule {
+    CareerLadder,
+    FplAbbrev,
+    FullGrade,
+    FullPerformance,
+    FullPerformanceTypo,
+    FullPromotion,
+    FullWorking,
+    HighestGrade,
+    JourneyLevel,
+    PromotionPotential,
+    Target,
+}
+
+/// Whether a grade is a regular grade, a pay band level (e.g. `Band III`), a level of the title 38
+/// VA nurse schedule (e.g. `VN-II` or `Nurse III`) or a senior pay plan (e.g. `SL` or `ES-00`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Kind {
+    Band,
+    Grade,
+    Nurse,
+    Senior,
+}
+
+/// What separates two grades of a ladder.
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Separator {
+    /// A word like `to` or `through`.
+    Interval,
+    /// A comma, or nothing but whitespace.
+    List,
+    Slash,
+}
+
+impl<'a> Extraction<'a> {
+    /// Describes `mention`, whose trigger was found at byte `offset` of the text.
+    pub fn new(offset: usize, mention: &Mention<'a>) -> Self {
+        let grade = mention.grade();
+        let end = offset + mention.end;
+
+        Self {
+            grade: grade.grade,
+            pay_plan: grade.pay_plan,
+            series: grade.series,
+            kind: grade.kind,
+            rule: mention.rule,
+            confidence: mention.confidence(),
+            valid: grade.is_valid(),
+            trigger_start: offset,
+            trigger_end: offset + mention.trigger.len(),
+            start: end - grade.text.len(),
+            end,
+        }
+    }
+}
+
+impl Grade<'_> {
+    /// Checks that the grade is plausible for its pay plan, e.g. that a GS grade is not above 15
+    /// or that grade `00` appears only with SES pay plans.
+    pub fn is_valid(&self) -> bool {
+        let value = self.value();
+
+        match self.kind {
+            Kind::Band => return (1..=9).contains(&value),
+            Kind::Nurse => return (1..=5).contains(&value),
+            Kind::Senior => return value == 0,
+            Kind::Grade => {}
+        }
+
+        let pay_plan = self.pay_plan.unwrap_or_default();
+
+        let max = MAX_GRADES
+            .iter()
+            .find(|(p, _)| p.eq_ignore_ascii_case(pay_plan))
+            .map_or(15, |(_, max)| *max);
+
+        if value == 0 {
+            max == 0
+        } else {
+            value <= max
+        }
+    }
+
+    /// Checks the grade against an expected grade written as `13` or, with a pay plan, `GS-13`.
+    pub fn matches(&self, expected: &str) -> bool {
+        let (pay_plan, grade) = match expected.rsplit_once('-') {
+            Some((pay_plan, grade)) => (Some(pay_plan.trim()), grade),
+            None => (None, expected),
+        };
+
+        grade.trim().parse() == Ok(self.value())
+            && pay_plan.is_none_or(|p| self.pay_plan.is_some_and(|q| q.eq_ignore_ascii_case(p)))
+    }
+
+    /// Returns the numeric value of the grade.
+    pub fn value(&self) -> u32 {
+        self.grade.parse().unwrap_or_default()
+    }
+}
+
+/// Writes the grade as `GS-12`, or as `GS-0343-12` when it has a series.
+impl Display for Grade<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match (self.pay_plan, self.series) {
+            (Some(pay_plan), Some(series)) => {
+                write!(f, "{}-{series}-{}", pay_plan.to_uppercase(), self.grade)
+            }
+            (Some(pay_plan), None) => write!(f, "{}-{}", pay_plan.to_uppercase(), self.grade),
+            (None, _) => f.write_str(self.grade),
+        }
+    }
+}
+
+impl<'a> Mention<'a> {
+    /// Estimates how reliable the mention is, from 0 (unreliable) to 1 (reliable), based on its
+    /// rule, the distance between the trigger and the grade and whether the grade is valid.
+    pub fn confidence(&self) -> f64 {
+        let distance = self.gap.min(40) as f64 / 200.0;
+        let validity = if self.grade().is_valid() { 1.0 } else { 0.5 };
+
+        ((self.rule.confidence() - distance) * validity).max(0.0)
+    }
+
+    /// Returns the last grade of the ladder.
+    pub fn grade(&self) -> Grade<'a> {
+        last_grade(&self.ladder)
+    }
+}
+
+impl Kind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Band => "band",
+            Self::Grade => "grade",
+            Self::Nurse => "nurse",
+            Self::Senior => "senior",
+        }
+    }
+}
+
+impl Rule {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CareerLadder => "career_ladder",
+            Self::FplAbbrev => "fpl_abbrev",
+            Self::FullGrade => "full_grade",
+            Self::FullPerformance => "full_performance",
+            Self::FullPerformanceTypo => "full_performance_typo",
+            Self::FullPromotion => "full_promotion",
+            Self::FullWorking => "full_working",
+            Self::HighestGrade => "highest_grade",
+            Self::JourneyLevel => "journey_level",
+            Self::PromotionPotential => "promotion_potential",
+            Self::Target => "target",
+        }
+    }
+
+    /// Returns `target` for a target grade trigger, which is less reliable, and `fpl` for the
+    /// full performance level triggers.
+    pub fn source(&self) -> &'static str {
+        match self {
+            Self::Target => "target",
+            _ => "fpl",
+        }
+    }
+
+    fn confidence(&self) -> f64 {
+        match self {
+            Self::FplAbbrev | Self::FullPerformance | Self::PromotionPotential => 1.0,
+            Self::CareerLadder
+            | Self::FullPromotion
+            | Self::FullWorking
+            | Self::HighestGrade
+            | Self::JourneyLevel => 0.9,
+            Self::FullGrade | Self::FullPerformanceTypo => 0.8,
+            Self::Target => 0.6,
+        }
+    }
+}
+
+fn alphas(count: usize, s: &str) -> IResult<&str, &str> {
+    verify(alpha1, |s: &str| s.len() == count)(s)
+}
+
+fn band(s: &str) -> IResult<&str, Grade<'_>> {
+    let (s, keyword) = opt(terminated(tag_no_case("band"), space0))(s)?;
+
+    let (s, (text, grade)) = if keyword.is_some() {
+        alt((roman, number))(s)?
+    } else {
+        roman(s)?
+    };
+
+    Ok((
+        s,
+        Grade {
+            grade,
+            text,
+            pay_plan: None,
+            series: None,
+            kind: Kind::Band,
+        },
+    ))
+}
+
+/// Parses a "career ladder" trigger, optionally followed by "position" or "grades".
+fn career_ladder(s: &str) -> IResult<&str, &str> {
+    let start = s;
+
+    let (s, _) = words(&["career", "ladder"])(s)?;
+
+    let (s, _) = opt(|s| {
+        let (s, _) = space0(s)?;
+        alt((
+            tag_no_case("position"),
+            tag_no_case("grades"),
+            tag_no_case("grade"),
+        ))(s)
+    })(s)?;
+
+    Ok((s, &start[0..start.len() - s.len()]))
+}
+
+/// Parses the whitespace and the connector phrase between a trigger and its grades.
+fn connector_gap<'a>(connectors: &[Vec<String>], s: &'a str) -> IResult<&'a str, &'a str> {
+    let start = s;
+    let (s, _) = space0(s)?;
+
+    let (s, _) = opt(|s| connector_phrase(connectors, s))(s)?;
+
+    let (s, _) = space0(s)?;
+
+    Ok((s, &start[0..start.len() - s.len()]))
+}
+
+fn connector_phrase<'a>(connectors: &[Vec<String>], s: &'a str) -> IResult<&'a str, &'a str> {
+    for connector in connectors {
+        if let Ok((s, connector)) = words(connector)(s) {
+            return Ok((s, connector));
+        }
+    }
+
+    fail(s)
+}
+
+/// Returns the Levenshtein distance between two ASCII words.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, x) in a.bytes().enumerate() {
+        let mut diagonal = row[0];
+
+        row[0] = i + 1;
+
+        for (j, y) in b.bytes().enumerate() {
+            let above = row[j + 1];
+
+            row[j + 1] = (diagonal + usize::from(x != y))
+                .min(above + 1)
+                .min(row[j] + 1);
+            diagonal = above;
+        }
+    }
+
+    row[b.len()]
+}
+
+/// Returns the grade a position is advertised at below the full performance level of `mention`,
+/// whose trigger is at byte `offset` of `s`. That is the first grade of a ladder such as
+/// `GS-11/12/13`, or the grade just before the trigger, as in `GS-9 with promotion potential to
+/// GS-12`.
+pub fn entry_grade<'a>(s: &'a str, offset: usize, mention: &Mention<'a>) -> Option<Grade<'a>> {
+    entry_grade_with(Rules::builtin(), s, offset, mention)
+}
+
+/// Like [`entry_grade`], but with the pay plans accepted by `rules`.
+pub fn entry_grade_with<'a>(
+    rules: &Rules,
+    s: &'a str,
+    offset: usize,
+    mention: &Mention<'a>,
+) -> Option<Grade<'a>> {
+    if mention.ladder.len() > 1 {
+        return Some(mention.ladder[0]);
+    }
+
+    let before = &s[0..offset];
+
+    // Only grades with a pay plan are taken, so that e.g. the `2` in `for 2 years with promotion
+    // potential` is not.
+    (before.len().saturating_sub(ENTRY_WINDOW)..before.len())
+        .filter(|&i| before.is_char_boundary(i))
+        .filter(|&i| !before[0..i].ends_with(|c: char| c.is_alphanumeric()))
+        .find_map(|i| {
+            let (rest, ladder) = ladder_with(rules, &before[i..]).ok()?;
+            let first = ladder[0];
+
+            (first.pay_plan.is_some() && is_entry_connector(rest)).then_some(first)
+        })
+}
+
+/// Lists every trigger phrase in `s` with the grades found after it, or where parsing stopped if
+/// there were none, to show why a grade was or was not found.
+pub fn explain(s: &str) -> Vec<Attempt<'_>> {
+    explain_with(Rules::builtin(), s)
+}
+
+/// Like [`explain`], but also recognizes the trigger spellings from `rules`.
+pub fn explain_with<'a>(rules: &Rules, s: &'a str) -> Vec<Attempt<'a>> {
+    s.bytes()
+        .enumerate()
+        .filter(|(_, b)| FPL_TRIGGER_STARTS.contains(b) || TARGET_TRIGGER_STARTS.contains(b))
+        .filter_map(|(offset, _)| {
+            let rest = &s[offset..];
+
+            let (after, (trigger, rule, connectors)) = fpl_trigger(rules, rest)
+                .map(|(after, (trigger, rule))| (after, (trigger, rule, &rules.fpl_connectors)))
+                .or_else(|_| {
+                    target_trigger(rest).map(|(after, trigger)| {
+                        (after, (trigger, Rule::Target, &rules.target_connectors))
+                    })
+                })
+                .ok()?;
+
+            let (grades, connector) = connector_gap(connectors, after).ok()?;
+
+            let (ladder, end) = match ladder_span(rules, grades) {
+                Ok((rest, (ladder, _, length))) => (ladder, s.len() - rest.len() - length),
+                Err(_) => (Vec::new(), s.len() - grades.len()),
+            };
+
+            Some(Attempt {
+                rule,
+                offset,
+                trigger,
+                connector,
+                ladder,
+                end,
+            })
+        })
+        .collect()
+}
+
+/// Finds the full performance level in `s`, falling back to a target grade, and describes it.
+pub fn extract(s: &str) -> Option<Extraction<'_>> {
+    extract_with(Rules::builtin(), s)
+}
+
+/// Like [`extract`], but also recognizes the trigger spellings from `rules`.
+pub fn extract_with<'a>(rules: &Rules, s: &'a str) -> Option<Extraction<'a>> {
+    get_fpl_mention_with(rules, s).map(|(offset, m)| Extraction::new(offset, &m))
+}
+
+/// Returns the first mention `parser` finds in `s`, together with the byte offset of its trigger.
+///
+/// The parser is only tried where a trigger can start, i.e. at the ASCII letters in `starts`,
+/// which are always at character boundaries.
+fn find_mention<'a>(
+    s: &'a str,
+    starts: &[u8],
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, Mention<'a>>,
+) -> Option<(usize, Mention<'a>)> {
+    s.bytes()
+        .enumerate()
+        .filter(|(_, b)| starts.contains(b))
+        .find_map(|(i, _)| parser(&s[i..]).ok().map(|(_, mention)| (i, mention)))
+}
+
+pub fn fpl(s: &str) -> IResult<&str, &str> {
+    map(|s| fpl_trigger(Rules::builtin(), s), |(trigger, _)| trigger)(s)
+}
+
+/// Parses a full performance level trigger followed by the highest grade it refers to.
+pub fn fpl_grade(s: &str) -> IResult<&str, Grade<'_>> {
+    map(fpl_mention, |m| m.grade())(s)
+}
+
+/// Parses a full performance level trigger followed by the grades it refers to.
+pub fn fpl_mention(s: &str) -> IResult<&str, Mention<'_>> {
+    fpl_mention_with(Rules::builtin(), s)
+}
+
+fn fpl_mention_with<'a>(rules: &Rules, s: &'a str) -> IResult<&'a str, Mention<'a>> {
+    let start = s;
+    let (s, (trigger, rule)) = fpl_trigger(rules, s)?;
+    let (s, connector) = connector_gap(&rules.fpl_connectors, s)?;
+    let gap = connector.len();
+    let offset = start.len() - s.len();
+    let (s, (ladder, spans, length)) = ladder_span(rules, s)?;
+    let end = start.len() - s.len() - length;
+    let spans = spans
+        .iter()
+        .map(|&(a, b)| (offset + a, offset + b))
+        .collect();
+
+    Ok((
+        s,
+        Mention {
+            rule,
+            trigger,
+            gap,
+            end,
+            ladder,
+            spans,
+        },
+    ))
+}
+
+/// Parses a "full performance level" trigger phrase, including its common misspellings.
+fn fpl_trigger<'a>(rules: &Rules, s: &'a str) -> IResult<&'a str, (&'a str, Rule)> {
+    if let Ok((s, fpl)) = tag_no_case::<&str, &str, Error<&str>>("fpl")(s) {
+        return Ok((s, (fpl, Rule::FplAbbrev)));
+    }
+
+    if let Ok((s, trigger)) = words(&["promotion", "potential"])(s) {
+        return Ok((s, (trigger, Rule::PromotionPotential)));
+    }
+
+    if let Ok((s, trigger)) = journey(s) {
+        return Ok((s, (trigger, Rule::JourneyLevel)));
+    }
+
+    if let Ok((s, trigger)) = career_ladder(s) {
+        return Ok((s, (trigger, Rule::CareerLadder)));
+    }
+
+    if let Ok((s, trigger)) = highest_grade(s) {
+        return Ok((s, (trigger, Rule::HighestGrade)));
+    }
+
+    let start = s;
+
+    let (s, full) = alt((tag_no_case("full"), tag_no_case("poll")))(s)?;
+    let (s, _) = opt_one_of(SEPARATORS, s)?;
+
+    let (s, rule) = alt((
+        value(Rule::CareerLadder, words(&["career", "ladder", "grade"])),
+        value(Rule::FullGrade, tag_no_case("grade")),
+        value(Rule::FullPerformanceTypo, tag_no_case("peformance")),
+        value(Rule::FullPerformanceTypo, tag_no_case("perf.")),
+        value(Rule::FullPerformanceTypo, tag_no_case("perfformance")),
+        value(Rule::FullPerformance, tag_no_case("performance")),
+        value(Rule::FullPerformanceTypo, tag_no_case("performane")),
+        value(Rule::FullPerformanceTypo, tag_no_case("perfromance")),
+        value(Rule::FullPerformanceTypo, tag_no_case("perormance")),
+        value(Rule::FullPromotion, tag_no_case("promotion")),
+        value(Rule::FullWorking, tag_no_case("working")),
+        |s| variant(&rules.variants, s),
+        |s| fuzzy(rules.fuzzy, s),
+    ))(s)?;
+
+    let rule = if rule == Rule::FullPerformance && full.eq_ignore_ascii_case("poll") {
+        Rule::FullPerformanceTypo
+    } else {
+        rule
+    };
+
+    let (s, _) = space0(s)?;
+    let (s, _) = opt(tag_no_case("level"))(s)?;
+
+    Ok((s, (&start[0..start.len() - s.len()], rule)))
+}
+
+/// Parses a word within two edits of "performance" or "promotion", if `enabled`.
+fn fuzzy(enabled: bool, s: &str) -> IResult<&str, Rule> {
+    if !enabled {
+        return fail(s);
+    }
+
+    let (rest, word) = alpha1(s)?;
+    let word = word.to_ascii_lowercase();
+
+    if ["performance", "promotion"]
+        .iter()
+        .any(|w| edit_distance(&word, w) <= 2)
+    {
+        Ok((rest, Rule::FullPerformanceTypo))
+    } else {
+        fail(s)
+    }
+}
+
+/// Finds the full performance level grade in `s`, falling back to a target grade.
+pub fn get_fpl_grade(s: &str) -> Option<Grade<'_>> {
+    get_fpl_mention(s).map(|(_, m)| m.grade())
+}
+
+/// Finds the full performance level mention in `s`, falling back to a target grade mention,
+/// together with the byte offset of its trigger.
+pub fn get_fpl_mention(s: &str) -> Option<(usize, Mention<'_>)> {
+    get_fpl_mention_with(Rules::builtin(), s)
+}
+
+/// Like [`get_fpl_mention`], but also recognizes the trigger spellings from `rules`.
+pub fn get_fpl_mention_with<'a>(rules: &Rules, s: &'a str) -> Option<(usize, Mention<'a>)> {
+    find_mention(s, FPL_TRIGGER_STARTS, |s| fpl_mention_with(rules, s))
+        .or_else(|| find_mention(s, TARGET_TRIGGER_STARTS, |s| target_mention_with(rules, s)))
+}
+
+/// Finds every full performance level and target grade mention in `s`, together with the byte
+/// offset of its trigger.
+pub fn get_fpl_mentions(s: &str) -> Vec<(usize, Mention<'_>)> {
+    get_fpl_mentions_with(Rules::builtin(), s)
+}
+
+/// Like [`get_fpl_mentions`], but also recognizes the trigger spellings from `rules`.
+pub fn get_fpl_mentions_with<'a>(rules: &Rules, s: &'a str) -> Vec<(usize, Mention<'a>)> {
+    let mut mentions = Vec::new();
+    let mut offset = 0;
+
+    while let Some(i) = s[offset..]
+        .bytes()
+        .position(|b| FPL_TRIGGER_STARTS.contains(&b) || TARGET_TRIGGER_STARTS.contains(&b))
+    {
+        let start = offset + i;
+        let rest = &s[start..];
+        let found = fpl_mention_with(rules, rest).or_else(|_| target_mention_with(rules, rest));
+
+        if let Ok((next, mention)) = found {
+            mentions.push((start, mention));
+            offset = s.len() - next.len();
+        } else {
+            offset = start + 1;
+        }
+    }
+
+    mentions
+}
+
+/// Splits `s` into the parts before byte `start` and after byte `end`, e.g. around the span of
+/// a mention, or returns `None` if they are out of bounds or not on character boundaries.
+pub fn get_match_prefix_and_suffix(s: &str, start: usize, end: usize) -> Option<(&str, &str)> {
+    Some((s.get(0..start)?, s.get(end.max(start)..)?))
+}
+
+/// Parses a single grade such as `12`, `gs-13` or `gs-0510-09`.
+pub fn grade(s: &str) -> IResult<&str, Grade<'_>> {
+    grade_with(Rules::builtin(), s)
+}
+
+fn grade_with<'a>(rules: &Rules, s: &'a str) -> IResult<&'a str, Grade<'a>> {
+    if let Ok((s, grade)) = nurse(rules, s) {
+        return Ok((s, grade));
+    }
+
+    if let Ok((s, grade)) = senior(rules, s) {
+        return Ok((s, grade));
+    }
+
+    if let Ok((s, grade)) = band(s) {
+        return Ok((s, grade));
+    }
+
+    if let Ok((s, (text, grade))) = number(s) {
+        return Ok((
+            s,
+            Grade {
+                grade,
+                text,
+                pay_plan: None,
+                series: None,
+                kind: Kind::Grade,
+            },
+        ));
+    }
+
+    let (s, pay_plan) = verify(|s| alphas(2, s), |p| rules.accepts_pay_plan(p))(s)?;
+    let (s, sep) = opt_one_of(GRADE_SEPARATORS, s)?;
+    let (s, _) = opt(one_of(SPACES))(s)?;
+
+    let kind = if word_index(&DEMO_PAY_PLANS, pay_plan).is_some() {
+        Kind::Band
+    } else {
+        Kind::Grade
+    };
+
+    let pay_plan = Some(pay_plan);
+
+    match sep {
+        None | Some(' ' | '\u{a0}') => {
+            let (s, (text, grade)) = number(s)?;
+
+            Ok((
+                s,
+                Grade {
+                    grade,
+                    text,
+                    pay_plan,
+                    series: None,
+                    kind,
+                },
+            ))
+        }
+        Some(sep) => {
+            let (s, grade_or_series) = max_digits(4, s)?;
+
+            if let Ok((s, _)) = char::<&str, Error<&str>>(sep)(s) {
+                if let Ok((s, grade)) = max_digits(2, s) {
+                    let series = (grade_or_series.len() > 2).then_some(grade_or_series);
+
+                    return Ok((
+                        s,
+                        Grade {
+                            grade,
+                            text: grade,
+                            pay_plan,
+                            series,
+                            kind,
+                        },
+                    ));
+                }
+            }
+
+            if grade_or_series.len() <= 2 {
+                Ok((
+                    s,
+                    Grade {
+                        grade: grade_or_series,
+                        text: grade_or_series,
+                        pay_plan,
+                        series: None,
+                        kind,
+                    },
+                ))
+            } else {
+                fail(s)
+            }
+        }
+    }
+}
+
+/// Parses a "highest grade", "maximum grade" or "max grade" trigger, optionally followed by
+/// "level".
+fn highest_grade(s: &str) -> IResult<&str, &str> {
+    let start = s;
+
+    let (s, _) = alt((
+        tag_no_case("highest"),
+        tag_no_case("maximum"),
+        tag_no_case("max"),
+    ))(s)?;
+
+    let (s, _) = opt_one_of(SEPARATORS, s)?;
+    let (s, _) = tag_no_case("grade")(s)?;
+
+    let (s, _) = opt(|s| {
+        let (s, _) = space0(s)?;
+        tag_no_case("level")(s)
+    })(s)?;
+
+    Ok((s, &start[0..start.len() - s.len()]))
+}
+
+fn is_entry_connector(s: &str) -> bool {
+    s.split(|c: char| c.is_whitespace() || c == ',' || c == '(')
+        .filter(|w| !w.is_empty())
+        .all(|w| ENTRY_CONNECTORS.iter().any(|c| c.eq_ignore_ascii_case(w)))
+}
+
+/// Parses a "journey level" or "journeyman" trigger, optionally followed by "level" or "grade".
+fn journey(s: &str) -> IResult<&str, &str> {
+    let start = s;
+
+    let (s, _) = tag_no_case("journey")(s)?;
+    let (s, man) = opt(tag_no_case("man"))(s)?;
+    let (ls, _) = opt_one_of(SEPARATORS, s)?;
+
+    let s = match alt((
+        tag_no_case("level"),
+        tag_no_case::<&str, &str, Error<&str>>("grade"),
+    ))(ls)
+    {
+        Ok((s, _)) => s,
+        Err(_) if man.is_some() => s,
+        Err(error) => return Err(error),
+    };
+
+    Ok((s, &start[0..start.len() - s.len()]))
+}
+
+/// Parses a list of grades such as `gs-11/12/13` or `gs-11 to gs-13`, carrying the pay plan and
+/// series forward.
+pub fn ladder(s: &str) -> IResult<&str, Vec<Grade<'_>>> {
+    ladder_with(Rules::builtin(), s)
+}
+
+/// Parses what separates the grades of a ladder, returning whether it is a word like `to` that
+/// makes the grades an interval, a slash or a list separator.
+fn ladder_separator(s: &str) -> IResult<&str, Separator> {
+    let (s, _) = space0(s)?;
+
+    let interval = INTERVAL_WORDS
+        .iter()
+        .find_map(|w| terminated(words(w), one_of(SPACES))(s).ok());
+
+    let (s, separator) = match interval {
+        Some((s, _)) => (s, Separator::Interval),
+        None => match opt_one_of(",/", s)? {
+            (s, Some('/')) => (s, Separator::Slash),
+            (s, _) => (s, Separator::List),
+        },
+    };
+
+    let (s, _) = space0(s)?;
+
+    Ok((s, separator))
+}
+
+/// Parses a list of grades like [`ladder`], also returning the byte range of each grade as
+/// [`Mention::spans`] does, from the start of `s`, and the number of bytes after the end of the
+/// last grade that it consumed.
+fn ladder_span<'a>(rules: &Rules, s: &'a str) -> IResult<&'a str, LadderSpan<'a>> {
+    let input = s;
+    let (mut s, first) = grade_with(rules, s)?;
+    let mut end = s;
+    let mut ladder = vec![first];
+
+    // The offset of the pay plan of the last grade, if it is in the text.
+    let mut pay_plan = first.pay_plan.filter(|&p| input.starts_with(p)).map(|_| 0);
+
+    let offset = input.len() - s.len();
+    let mut spans = vec![(pay_plan.unwrap_or(offset - first.text.len()), offset)];
+
+    loop {
+        let separator;
+
+        (s, _) = opt(step)(s)?;
+        (s, separator) = ladder_separator(s)?;
+
+        let Ok((gs, grade)) = grade_with(rules, s) else {
+            return Ok((s, (ladder, spans, end.len() - s.len())));
+        };
+
+        let previous = last_grade(&ladder);
+
+        // Ladders go up, so a lower number after a slash is a step, as in `gs-13/5`.
+        if separator == Separator::Slash
+            && grade.pay_plan.is_none()
+            && previous.kind == Kind::Grade
+            && grade.value() < previous.value()
+        {
+            return Ok((gs, (ladder, spans, end.len() - gs.len())));
+        }
+
+        // The end of an interval is above its start, so that e.g. the `2` in `gs-12 to 2 days`
+        // is not taken for a grade.
+        if separator == Separator::Interval && grade.value() <= previous.value() {
+            return Ok((s, (ladder, spans, end.len() - s.len())));
+        }
+
+        // A grade without a pay plan continues the ladder, as does a roman numeral after a nurse
+        // level, as in `vn-ii/iii`.
+        let continues = grade.pay_plan.is_none()
+            && (grade.kind == Kind::Grade
+                || grade.kind == Kind::Band && previous.kind == Kind::Nurse);
+
+        // A band or a senior pay plan does not continue a ladder of another kind, so that e.g.
+        // the `x` in `gs-12, x` is not taken for band 10.
+        if !continues && grade.kind != Kind::Grade && grade.kind != previous.kind {
+            return Ok((s, (ladder, spans, end.len() - s.len())));
+        }
+
+        let kind = if continues { previous.kind } else { grade.kind };
+
+        if grade.pay_plan.is_some() {
+            pay_plan = grade
+                .pay_plan
+                .filter(|&p| s.starts_with(p))
+                .map(|_| input.len() - s.len());
+        }
+
+        s = gs;
+        end = s;
+
+        let offset = input.len() - s.len();
+        spans.push((pay_plan.unwrap_or(offset - grade.text.len()), offset));
+
+        ladder.push(Grade {
+            pay_plan: grade.pay_plan.or(previous.pay_plan),
+            series: grade.series.or(previous.series),
+            kind,
+            ..grade
+        });
+    }
+}
+
+fn ladder_with<'a>(rules: &Rules, s: &'a str) -> IResult<&'a str, Vec<Grade<'a>>> {
+    map(|s| ladder_span(rules, s), |(ladder, _, _)| ladder)(s)
+}
+
+fn last_grade<'a, L>(ladder: L) -> Grade<'a>
+where
+    L: AsRef<[Grade<'a>]>,
+{
+    let ladder = ladder.as_ref();
+    ladder[ladder.len() - 1]
+}
+
+fn max_digits(count: usize, s: &str) -> IResult<&str, &str> {
+    verify(digit1, |s: &str| s.len() <= count)(s)
+}
+
+/// Parses a list of grades such as `gs-11/12/13` and returns the last one.
+pub fn max_grade(s: &str) -> IResult<&str, Grade<'_>> {
+    map(ladder, last_grade)(s)
+}
+
+/// Returns whether `s` says that there is no full performance level, as in `FPL: N/A`,
+/// `Promotion potential: None` or `no promotion potential`.
+pub fn no_fpl(s: &str) -> bool {
+    no_fpl_with(Rules::builtin(), s)
+}
+
+/// Like [`no_fpl`], but also recognizes the trigger spellings and connectors from `rules`.
+pub fn no_fpl_with(rules: &Rules, s: &str) -> bool {
+    let mut offset = 0;
+
+    while let Some(i) = s[offset..]
+        .bytes()
+        .position(|b| FPL_TRIGGER_STARTS.contains(&b))
+    {
+        let start = offset + i;
+
+        offset = start + 1;
+
+        let Ok((rest, (_, rule))) = fpl_trigger(rules, &s[start..]) else {
+            continue;
+        };
+
+        let before = s[0..start].trim_end();
+        let word = before.len().checked_sub(2).and_then(|i| before.get(i..));
+
+        let negated = rule == Rule::PromotionPotential
+            && before.len() < start
+            && word.is_some_and(|w| w.eq_ignore_ascii_case("no"))
+            && !before[0..before.len() - 2].ends_with(|c: char| c.is_alphanumeric());
+
+        if negated {
+            return true;
+        }
+
+        let Ok((rest, _)) = connector_gap(&rules.fpl_connectors, rest) else {
+            continue;
+        };
+
+        let none = NO_FPL_WORDS.iter().any(|w| {
+            words(w)(rest).is_ok_and(|(rest, _)| !rest.starts_with(|c: char| c.is_alphanumeric()))
+        });
+
+        if none {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Collapses whitespace and lowercases `text`.
+pub fn normalize(text: &str) -> String {
+    normalize_whitespace(text).to_lowercase()
+}
+
+/// Replaces each run of whitespace in `text` with a single space and trims it.
+pub fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn number(s: &str) -> IResult<&str, (&str, &str)> {
+    if let Ok((s, digits)) = max_digits(2, s) {
+        return Ok((s, (digits, digits)));
+    }
+
+    let start = s;
+    let (s, number) = spelled_number(s)?;
+
+    Ok((s, (&start[0..start.len() - s.len()], NUMBERS[number])))
+}
+
+/// Parses a level of the title 38 VA nurse schedule, written as `VN-II`, `VN-0610-03` or
+/// `Nurse III`.
+fn nurse<'a>(rules: &Rules, s: &'a str) -> IResult<&'a str, Grade<'a>> {
+    let (s, pay_plan) = match tag_no_case::<&str, &str, Error<&str>>("nurse")(s) {
+        Ok((s, _)) => {
+            let (s, _) = space0(s)?;
+            let (s, _) = opt(terminated(tag_no_case("level"), space0))(s)?;
+
+            (s, "VN")
+        }
+        Err(_) => {
+            let (s, pay_plan) = verify(
+                |s| alphas(2, s),
+                |p: &str| p.eq_ignore_ascii_case("vn") && rules.accepts_pay_plan(p),
+            )(s)?;
+
+            (opt_one_of(GRADE_SEPARATORS, s)?.0, pay_plan)
+        }
+    };
+
+    let (s, series) = opt(terminated(
+        verify(digit1, |d: &str| d.len() == 4),
+        one_of(DASHES),
+    ))(s)?;
+
+    let (s, (text, grade)) = alt((roman, number))(s)?;
+
+    Ok((
+        s,
+        Grade {
+            grade,
+            text,
+            pay_plan: Some(pay_plan),
+            series,
+            kind: Kind::Nurse,
+        },
+    ))
+}
+
+fn opt_one_of<'a>(list: &str, s: &'a str) -> IResult<&'a str, Option<char>> {
+    opt(one_of(list))(s)
+}
+
+fn roman(s: &str) -> IResult<&str, (&str, &str)> {
+    let (rest, word) = alpha1(s)?;
+
+    match word_index(&ROMAN, word) {
+        Some(number) if number > 0 => Ok((rest, (word, NUMBERS[number]))),
+        _ => fail(s),
+    }
+}
+
+/// Parses a senior pay plan such as `SES`, `SL` or `ST`, optionally followed by grade `00`.
+fn senior<'a>(rules: &Rules, s: &'a str) -> IResult<&'a str, Grade<'a>> {
+    let (s, pay_plan) = alpha1(s)?;
+
+    if word_index(&SENIOR_PAY_PLANS, pay_plan).is_none() || !rules.accepts_pay_plan(pay_plan) {
+        return fail(s);
+    }
+
+    let (s, grade) = opt(|s| {
+        let (s, _) = opt_one_of(SEPARATORS, s)?;
+        tag("00")(s)
+    })(s)?;
+
+    Ok((
+        s,
+        Grade {
+            grade: "00",
+            text: grade.unwrap_or(pay_plan),
+            pay_plan: Some(pay_plan),
+            series: None,
+            kind: Kind::Senior,
+        },
+    ))
+}
+
+/// Skips whitespace, including non-breaking and zero-width spaces.
+fn space0(s: &str) -> IResult<&str, &str> {
+    take_while(|c: char| c.is_whitespace() || c == '\u{200b}')(s)
+}
+
+fn spelled_number(s: &str) -> IResult<&str, usize> {
+    let (s, word) = alpha1(s)?;
+
+    if let Some(number) = word_index(&UNITS, word) {
+        return Ok((s, number));
+    }
+
+    let Some(tens) = word_index(&TENS, word).filter(|&tens| tens > 1) else {
+        return fail(s);
+    };
+
+    let (us, _) = opt_one_of(SEPARATORS, s)?;
+
+    if let Ok((us, unit)) = alpha1::<&str, Error<&str>>(us) {
+        if let Some(unit) = word_index(&UNITS[1..10], unit) {
+            return Ok((us, tens * 10 + unit + 1));
+        }
+    }
+
+    Ok((s, tens * 10))
+}
+
+/// Parses a step designation like `, step 05`, which can follow a grade.
+fn step(s: &str) -> IResult<&str, &str> {
+    let start = s;
+
+    let (s, _) = space0(s)?;
+    let (s, _) = opt(char(','))(s)?;
+    let (s, _) = space0(s)?;
+    let (s, _) = tag_no_case("step")(s)?;
+    let (s, _) = space0(s)?;
+    let (s, _) = max_digits(2, s)?;
+
+    Ok((s, &start[0..start.len() - s.len()]))
+}
+
+/// Parses a "target grade" phrase followed by the highest grade it refers to.
+pub fn target_grade(s: &str) -> IResult<&str, Grade<'_>> {
+    map(target_mention, |m| m.grade())(s)
+}
+
+/// Parses a "target grade" phrase followed by the grades it refers to.
+pub fn target_mention(s: &str) -> IResult<&str, Mention<'_>> {
+    target_mention_with(Rules::builtin(), s)
+}
+
+fn target_mention_with<'a>(rules: &Rules, s: &'a str) -> IResult<&'a str, Mention<'a>> {
+    let start = s;
+    let (s, trigger) = target_trigger(s)?;
+    let (s, connector) = connector_gap(&rules.target_connectors, s)?;
+    let gap = connector.len();
+    let offset = start.len() - s.len();
+    let (s, (ladder, spans, length)) = ladder_span(rules, s)?;
+    let end = start.len() - s.len() - length;
+    let spans = spans
+        .iter()
+        .map(|&(a, b)| (offset + a, offset + b))
+        .collect();
+
+    Ok((
+        s,
+        Mention {
+            rule: Rule::Target,
+            trigger,
+            gap,
+            end,
+            ladder,
+            spans,
+        },
+    ))
+}
+
+/// Parses a "target" or "targeted" trigger.
+fn target_trigger(s: &str) -> IResult<&str, &str> {
+    let start = s;
+
+    let (s, _) = tag_no_case("target")(s)?;
+    let (s, _) = opt(tag_no_case("ed"))(s)?;
+
+    Ok((s, &start[0..start.len() - s.len()]))
+}
+
+fn variant<'a>(variants: &[String], s: &'a str) -> IResult<&'a str, Rule> {
+    for variant in variants {
+        if let Ok((s, _)) = tag_no_case::<&str, &str, Error<&str>>(variant.as_str())(s) {
+            return Ok((s, Rule::FullPerformanceTypo));
+        }
+    }
+
+    fail(s)
+}
+
+fn word_index(list: &[&str], word: &str) -> Option<usize> {
+    list.iter().position(|w| w.eq_ignore_ascii_case(word))
+}
+
+fn words<S: AsRef<str>>(words: &[S]) -> impl FnMut(&str) -> IResult<&str, &str> + '_ {
+    move |s| {
+        let mut i = s;
+
+        for (n, word) in words.iter().enumerate() {
+            if n > 0 {
+                i = space0(i)?.0;
+            }
+
+            i = match word.as_ref() {
+                "-" => one_of(DASHES)(i)?.0,
+                word => tag_no_case(word)(i)?.0,
+            };
+        }
+
+        Ok((i, &s[0..s.len() - i.len()]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn g<'a>(pay_plan: Option<&'a str>, series: Option<&'a str>, grade: &'a str) -> Grade<'a> {
+        Grade {
+            grade,
+            text: grade,
+            pay_plan,
+            series,
+            kind: Kind::Grade,
+        }
+    }
+
+    fn w<'a>(pay_plan: Option<&'a str>, text: &'a str, grade: &'a str) -> Grade<'a> {
+        Grade {
+            text,
+            ..g(pay_plan, None, grade)
+        }
+    }
+
+    #[test]
+    fn test_dehyphenate() {
+        assert_eq!(
+            dehyphenate("full perfor-\nmance level"),
+            "full performance level"
+        );
+        assert_eq!(dehyphenate("GS-\n13"), "GS-13");
+        assert_eq!(dehyphenate("GS \r\n- \n 13"), "GS-13");
+        assert_eq!(dehyphenate("level\n\nGS-13"), "level GS-13");
+        assert_eq!(dehyphenate("full\nperformance\n"), "full performance");
+        assert_eq!(dehyphenate("GS-13 and\nGS-14"), "GS-13 and GS-14");
+    }
+
+    #[test]
+    fn test_entry_grade() {
+        let entry = |s| {
+            let (offset, mention) = get_fpl_mentions(s).remove(0);
+            entry_grade(s, offset, &mention).map(|g| g.grade)
+        };
+
+        assert_eq!(entry("FPL: GS-11/12/13"), Some("11"));
+        assert_eq!(entry("GS-9 with promotion potential to GS-12"), Some("9"));
+        assert_eq!(
+            entry("GS-0343-7, with a promotion potential to GS-12"),
+            Some("7")
+        );
+        assert_eq!(entry("GS-7/9 (promotion potential GS-12)"), Some("7"));
+        assert_eq!(entry("For 2 years with promotion potential to GS-12"), None);
+        assert_eq!(entry("GS-9 duties with promotion potential to GS-12"), None);
+        assert_eq!(entry("The full performance level is GS-13."), None);
+    }
+
+    #[test]
+    fn test_explain() {
+        let attempts =
+            explain("targeted to gs-12; full performance level of this position is varies");
+
+        assert_eq!(
+            attempts,
+            vec![
+                Attempt {
+                    rule: Rule::Target,
+                    offset: 0,
+                    trigger: "targeted",
+                    connector: " to ",
+                    ladder: vec![g(Some("gs"), None, "12")],
+                    end: 17,
+                },
+                Attempt {
+                    rule: Rule::FullPerformance,
+                    offset: 19,
+                    trigger: "full performance level",
+                    connector: " of this position is ",
+                    ladder: Vec::new(),
+                    end: 62,
+                },
+            ]
+        );
+
+        assert!(explain("no grade here").is_empty());
+    }
+
+    #[test]
+    fn test_extract() {
+        assert_eq!(extract("no grade"), None);
+        assert_eq!(extract("FPL: GS-12").unwrap().rule.source(), "fpl");
+        assert_eq!(
+            extract("targeted to gs-11").unwrap().rule.source(),
+            "target"
+        );
+
+        assert_eq!(
+            extract("The full performance level is GS-0510-13."),
+            Some(Extraction {
+                grade: "13",
+                pay_plan: Some("GS"),
+                series: Some("0510"),
+                kind: Kind::Grade,
+                rule: Rule::FullPerformance,
+                confidence: 0.98,
+                valid: true,
+                trigger_start: 4,
+                trigger_end: 26,
+                start: 38,
+                end: 40,
+            })
+        );
+
+        let text = "full performance level of grade twelve";
+        let extraction = extract(text).unwrap();
+
+        assert_eq!(extraction.grade, "12");
+        assert_eq!(&text[extraction.start..extraction.end], "twelve");
+
+        let text = "Über uns – FPL: GS-11/12, next";
+        let extraction = extract(text).unwrap();
+
+        assert_eq!(
+            &text[extraction.trigger_start..extraction.trigger_end],
+            "FPL"
+        );
+        assert_eq!(&text[extraction.start..extraction.end], "12");
+    }
+
+    #[test]
+    fn test_fpl() {
+        assert_eq!(fpl("fpl"), Ok(("", "fpl")));
+        assert_eq!(fpl("full peformance"), Ok(("", "full peformance")));
+        assert_eq!(fpl("full perf."), Ok(("", "full perf.")));
+        assert_eq!(fpl("full perfformance"), Ok(("", "full perfformance")));
+        assert_eq!(fpl("full performance"), Ok(("", "full performance")));
+        assert_eq!(fpl("full performane"), Ok(("", "full performane")));
+        assert_eq!(fpl("full perfromance "), Ok(("", "full perfromance ")));
+        assert_eq!(fpl("full-performance"), Ok(("", "full-performance")));
+
+        assert_eq!(
+            fpl("full performance level"),
+            Ok(("", "full performance level"))
+        );
+
+        assert_eq!(
+            fpl("full perormance level"),
+            Ok(("", "full perormance level"))
+        );
+
+        assert_eq!(
+            fpl("fullperformance level"),
+            Ok(("", "fullperformance level"))
+        );
+
+        assert_eq!(fpl("full working level"), Ok(("", "full working level")));
+    }
+
+    #[test]
+    fn test_fuzzy() {
+        let mut rules = Rules::default();
+
+        assert_eq!(
+            get_fpl_mention_with(&rules, "full perfornmance level gs-13"),
+            None
+        );
+
+        rules.set_fuzzy(true);
+
+        for (text, trigger) in [
+            ("full perfornmance level gs-13", "full perfornmance level"),
+            ("Full Peformence Level: GS-13", "Full Peformence Level"),
+            ("poll preformance level is gs-13", "poll preformance level"),
+            ("full promtion level is gs-13", "full promtion level"),
+        ] {
+            let (_, mention) = get_fpl_mention_with(&rules, text).unwrap();
+
+            assert_eq!(mention.rule, Rule::FullPerformanceTypo);
+            assert_eq!(mention.trigger, trigger);
+            assert_eq!(mention.grade().grade, "13");
+        }
+
+        let (_, mention) = get_fpl_mention_with(&rules, "full performance level gs-13").unwrap();
+
+        assert_eq!(mention.rule, Rule::FullPerformance);
+        assert_eq!(get_fpl_mention_with(&rules, "full performer gs-13"), None);
+        assert_eq!(get_fpl_mention_with(&rules, "full time gs-13"), None);
+
+        let rules = Rules::parse("[fpl]\nfuzzy = true").unwrap();
+
+        assert!(get_fpl_mention_with(&rules, "full perfomanse level gs-13").is_some());
+    }
+
+    #[test]
+    fn test_get_fpl_grade() {
+        assert_eq!(
+            get_fpl_grade("fpl is gs-13"),
+            Some(g(Some("gs"), None, "13"))
+        );
+
+        assert_eq!(
+            get_fpl_grade("full performance level of grade thirteen"),
+            Some(w(None, "thirteen", "13"))
+        );
+
+        assert_eq!(get_fpl_grade("no grade here"), None);
+    }
+
+    #[test]
+    fn test_get_fpl_mention() {
+        let (_, mention) = get_fpl_mention("the fpl is gs-13").unwrap();
+
+        assert_eq!(mention.rule, Rule::FplAbbrev);
+        assert_eq!(mention.trigger, "fpl");
+        assert_eq!(mention.gap, 4);
+        assert_eq!(mention.confidence(), 0.98);
+
+        let (_, mention) =
+            get_fpl_mention("full perfromance level of this position is gs-16").unwrap();
+
+        assert_eq!(mention.rule, Rule::FullPerformanceTypo);
+        assert_eq!(mention.trigger, "full perfromance level");
+        assert!(mention.confidence() < 0.5);
+
+        let (_, mention) = get_fpl_mention("targeted to gs-12").unwrap();
+
+        assert_eq!(mention.rule, Rule::Target);
+        assert_eq!(mention.trigger, "targeted");
+        assert_eq!(mention.gap, 4);
+    }
+
+    #[test]
+    fn test_get_fpl_mention_career_ladder() {
+        let (_, mention) = get_fpl_mention("career ladder GS-7/9/11/12").unwrap();
+
+        assert_eq!(mention.rule, Rule::CareerLadder);
+        assert_eq!(mention.trigger, "career ladder");
+
+        assert_eq!(
+            mention.ladder,
+            vec![
+                g(Some("GS"), None, "7"),
+                g(Some("GS"), None, "9"),
+                g(Some("GS"), None, "11"),
+                g(Some("GS"), None, "12"),
+            ]
+        );
+
+        let (_, mention) = get_fpl_mention("This is a career ladder position: GS-5/7/9.").unwrap();
+
+        assert_eq!(mention.trigger, "career ladder position");
+        assert_eq!(mention.ladder.len(), 3);
+    }
+
+    #[test]
+    fn test_get_fpl_mention_highest_grade() {
+        for (text, trigger, grade) in [
+            (
+                "the highest grade for this position is GS-13",
+                "highest grade",
+                g(Some("GS"), None, "13"),
+            ),
+            (
+                "maximum grade level: 12",
+                "maximum grade level",
+                g(None, None, "12"),
+            ),
+            ("Max Grade 11", "Max Grade", g(None, None, "11")),
+        ] {
+            let (_, mention) = get_fpl_mention(text).unwrap();
+
+            assert_eq!(mention.rule, Rule::HighestGrade);
+            assert_eq!(mention.trigger, trigger);
+            assert_eq!(mention.grade(), grade);
+        }
+    }
+
+    #[test]
+    fn test_get_fpl_mention_journey_level() {
+        let (_, mention) = get_fpl_mention("The journey level of this position is GS-12.").unwrap();
+
+        assert_eq!(mention.rule, Rule::JourneyLevel);
+        assert_eq!(mention.trigger, "journey level");
+        assert_eq!(mention.grade(), g(Some("GS"), None, "12"));
+
+        let (_, mention) = get_fpl_mention("journeyman grade GS-11").unwrap();
+
+        assert_eq!(mention.trigger, "journeyman grade");
+        assert_eq!(mention.grade(), g(Some("GS"), None, "11"));
+
+        let (_, mention) = get_fpl_mention("Journeyman: WG-10").unwrap();
+
+        assert_eq!(mention.trigger, "Journeyman");
+        assert_eq!(mention.grade(), g(Some("WG"), None, "10"));
+
+        assert_eq!(get_fpl_mention("a journey to 12 cities"), None);
+    }
+
+    #[test]
+    fn test_get_fpl_mention_promotion_potential() {
+        let (_, mention) = get_fpl_mention("Promotion Potential: 13").unwrap();
+
+        assert_eq!(mention.rule, Rule::PromotionPotential);
+        assert_eq!(mention.trigger, "Promotion Potential");
+        assert_eq!(mention.grade(), g(None, None, "13"));
+
+        let (_, mention) = get_fpl_mention("with promotion potential to gs-12.").unwrap();
+
+        assert_eq!(mention.rule, Rule::PromotionPotential);
+        assert_eq!(mention.grade(), g(Some("gs"), None, "12"));
+    }
+
+    #[test]
+    fn test_get_fpl_mention_spans() {
+        let (offset, mention) = get_fpl_mention("The fpl is gs-11/12/13.").unwrap();
+
+        assert_eq!(offset, 4);
+        assert_eq!(mention.spans, vec![(7, 12), (7, 15), (7, 18)]);
+
+        let (offset, mention) = get_fpl_mention("fpl: Nurse II/III").unwrap();
+
+        assert_eq!(offset, 0);
+        assert_eq!(mention.spans, vec![(11, 13), (14, 17)]);
+    }
+
+    #[test]
+    fn test_get_fpl_mention_unicode() {
+        assert_eq!(
+            get_fpl_grade("full\u{a0}performance level is GS\u{2013}13"),
+            Some(g(Some("GS"), None, "13"))
+        );
+
+        assert_eq!(get_fpl_grade("FPL :\u{a0}12"), Some(g(None, None, "12")));
+
+        assert_eq!(
+            get_fpl_grade("FPL \u{2014} GS\u{2011}0510\u{2011}09"),
+            Some(g(Some("GS"), Some("0510"), "09"))
+        );
+    }
+
+    #[test]
+    fn test_get_fpl_mention_with() {
+        let mut rules = Rules::default();
+        rules.add_variants("# misspellings\npreformance\n\n  perfomance  \n");
+
+        assert_eq!(get_fpl_mention("full preformance level is gs-13"), None);
+
+        let (_, mention) = get_fpl_mention_with(&rules, "full preformance level is gs-13").unwrap();
+
+        assert_eq!(mention.rule, Rule::FullPerformanceTypo);
+        assert_eq!(mention.trigger, "full preformance level");
+        assert_eq!(mention.grade(), g(Some("gs"), None, "13"));
+
+        let (_, mention) = get_fpl_mention_with(&rules, "Full Perfomance: GS-12").unwrap();
+
+        assert_eq!(mention.trigger, "Full Perfomance");
+        assert_eq!(mention.grade(), g(Some("GS"), None, "12"));
+    }
+
+    #[test]
+    fn test_get_fpl_mention_with_pay_plans() {
+        let text = "the fpl is in 12 months";
+
+        assert_eq!(get_fpl_grade(text), Some(g(Some("in"), None, "12")));
+
+        let rules = Rules::parse("[grade]\nstrict = true").unwrap();
+
+        assert_eq!(get_fpl_mention_with(&rules, text), None);
+
+        let grade = get_fpl_mention_with(&rules, "fpl is gs-13").map(|(_, m)| m.grade());
+
+        assert_eq!(grade, Some(g(Some("gs"), None, "13")));
+
+        for (text, pay_plan) in [
+            ("full performance level GG-13", "GG"),
+            ("fpl: ad-0343-12", "ad"),
+            ("full performance level is IA-14", "IA"),
+        ] {
+            let grade = get_fpl_mention_with(&rules, text).map(|(_, m)| m.grade());
+            assert_eq!(grade.and_then(|g| g.pay_plan), Some(pay_plan));
+        }
+
+        let mut rules = Rules::default();
+
+        rules.set_pay_plans(&["WG"]);
+        rules.set_strict_pay_plans(true);
+
+        assert_eq!(get_fpl_mention_with(&rules, "fpl is gs-13"), None);
+        assert!(get_fpl_mention_with(&rules, "fpl is wg-10").is_some());
+    }
+
+    #[test]
+    fn test_get_fpl_mention_with_rules() {
+        let text = "full promotion potential of this vacancy is gs-12";
+
+        assert_eq!(get_fpl_mention(text), None);
+
+        let rules = Rules::parse(
+            "[fpl] # our templates\nconnectors = [\n  'potential of this vacancy is',\n  \"is\",\n]\n",
+        )
+        .unwrap();
+
+        let (_, mention) = get_fpl_mention_with(&rules, text).unwrap();
+
+        assert_eq!(mention.rule, Rule::FullPromotion);
+        assert_eq!(mention.grade(), g(Some("gs"), None, "12"));
+
+        assert_eq!(get_fpl_mention_with(&rules, "fpl: gs-12"), None);
+        assert!(get_fpl_mention_with(&rules, "targeted to gs-12").is_some());
+
+        let error = Rules::parse("[fpl]\nconnectors = [\"is\"]\ntriggers = []").unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "rules, line 3: unknown rule 'fpl.triggers'"
+        );
+
+        let error = Rules::parse("[target]\nconnectors = [\"to\"").unwrap_err();
+
+        assert_eq!(error.to_string(), "rules, line 2: invalid syntax");
+
+        let error = Rules::parse("[grade]\nstrict = ['yes']").unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "rules, line 2: 'grade.strict' must be a boolean"
+        );
+    }
+
+    #[test]
+    fn test_get_fpl_mentions() {
+        let text = "FPL: GS-12. Duties... promotion potential to GS-13; targeted to gs-11";
+        let mentions = get_fpl_mentions(text);
+
+        let found = mentions
+            .iter()
+            .map(|(offset, m)| (*offset, m.rule, m.grade().grade))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            found,
+            vec![
+                (0, Rule::FplAbbrev, "12"),
+                (22, Rule::PromotionPotential, "13"),
+                (52, Rule::Target, "11"),
+            ]
+        );
+
+        assert!(get_fpl_mentions("no grade here").is_empty());
+    }
+
+    #[test]
+    fn test_get_match_prefix_and_suffix() {
+        let text = "FPL: GS-12.";
+
+        assert_eq!(
+            get_match_prefix_and_suffix(text, 5, 10),
+            Some(("FPL: ", "."))
+        );
+        assert_eq!(
+            get_match_prefix_and_suffix(text, 5, 5),
+            Some(("FPL: ", "GS-12."))
+        );
+        assert_eq!(get_match_prefix_and_suffix(text, 5, 20), None);
+        assert_eq!(get_match_prefix_and_suffix("fpl: š", 6, 7), None);
+    }
+
+    #[test]
+    fn test_grade() {
+        assert_eq!(grade("1"), Ok(("", g(None, None, "1"))));
+        assert_eq!(grade("12"), Ok(("", g(None, None, "12"))));
+        assert_eq!(grade("gs 11"), Ok(("", g(Some("gs"), None, "11"))));
+
+        assert_eq!(
+            grade("gs-0510-09"),
+            Ok(("", g(Some("gs"), Some("0510"), "09")))
+        );
+
+        assert_eq!(
+            grade("gs-0998-6"),
+            Ok(("", g(Some("gs"), Some("0998"), "6")))
+        );
+
+        assert_eq!(grade("gs-13"), Ok(("", g(Some("gs"), None, "13"))));
+        assert_eq!(grade("gs- 13"), Ok(("", g(Some("gs"), None, "13"))));
+        assert_eq!(grade("gs-13.xxx"), Ok((".xxx", g(Some("gs"), None, "13"))));
+        assert_eq!(grade("gs-13-"), Ok(("-", g(Some("gs"), None, "13"))));
+
+        assert_eq!(
+            grade("gs-201-13"),
+            Ok(("", g(Some("gs"), Some("201"), "13")))
+        );
+
+        assert_eq!(grade("gs-7"), Ok(("", g(Some("gs"), None, "7"))));
+        assert_eq!(grade("gs15"), Ok(("", g(Some("gs"), None, "15"))));
+        assert_eq!(grade("gs7"), Ok(("", g(Some("gs"), None, "7"))));
+        assert_eq!(grade("wg 7"), Ok(("", g(Some("wg"), None, "7"))));
+        assert_eq!(grade("wg-08"), Ok(("", g(Some("wg"), None, "08"))));
+        assert_eq!(grade("wl-08"), Ok(("", g(Some("wl"), None, "08"))));
+        assert_eq!(grade("ws-7"), Ok(("", g(Some("ws"), None, "7"))));
+
+        assert_eq!(
+            grade("gs.0343.18"),
+            Ok(("", g(Some("gs"), Some("0343"), "18")))
+        );
+
+        assert_eq!(grade("Twelve"), Ok(("", w(None, "Twelve", "12"))));
+        assert_eq!(grade("gs nine"), Ok(("", w(Some("gs"), "nine", "9"))));
+        assert_eq!(grade("twenty-one"), Ok(("", w(None, "twenty-one", "21"))));
+        assert_eq!(grade("twenty"), Ok(("", w(None, "twenty", "20"))));
+
+        assert_eq!(
+            grade("IV"),
+            Ok((
+                "",
+                Grade {
+                    kind: Kind::Band,
+                    ..w(None, "IV", "4")
+                }
+            ))
+        );
+
+        assert_eq!(
+            grade("band iii"),
+            Ok((
+                "",
+                Grade {
+                    kind: Kind::Band,
+                    ..w(None, "iii", "3")
+                }
+            ))
+        );
+
+        assert_eq!(
+            grade("Band 2"),
+            Ok((
+                "",
+                Grade {
+                    kind: Kind::Band,
+                    ..g(None, None, "2")
+                }
+            ))
+        );
+
+        assert_eq!(
+            grade("ES-00"),
+            Ok((
+                "",
+                Grade {
+                    kind: Kind::Senior,
+                    ..w(Some("ES"), "00", "00")
+                }
+            ))
+        );
+
+        assert_eq!(
+            grade("SL."),
+            Ok((
+                ".",
+                Grade {
+                    kind: Kind::Senior,
+                    ..w(Some("SL"), "SL", "00")
+                }
+            ))
+        );
+
+        assert_eq!(
+            grade("ses 00"),
+            Ok((
+                "",
+                Grade {
+                    kind: Kind::Senior,
+                    ..w(Some("ses"), "00", "00")
+                }
+            ))
+        );
+
+        assert_eq!(
+            grade("NH-03"),
+            Ok((
+                "",
+                Grade {
+                    kind: Kind::Band,
+                    ..g(Some("NH"), None, "03")
+                }
+            ))
+        );
+
+        let nurse = |pay_plan, text, grade| Grade {
+            kind: Kind::Nurse,
+            ..w(Some(pay_plan), text, grade)
+        };
+
+        assert_eq!(grade("VN-II"), Ok(("", nurse("VN", "II", "2"))));
+        assert_eq!(grade("Nurse III"), Ok(("", nurse("VN", "III", "3"))));
+        assert_eq!(grade("nurse level 4"), Ok(("", nurse("VN", "4", "4"))));
+
+        assert_eq!(
+            grade("vn-0610-03"),
+            Ok((
+                "",
+                Grade {
+                    series: Some("0610"),
+                    ..nurse("vn", "03", "03")
+                }
+            ))
+        );
+
+        assert!(grade("123").is_err());
+        assert!(grade("gs 123").is_err());
+        assert!(grade("gs-123").is_err());
+        assert!(grade("gs-1234-").is_err());
+        assert!(grade("gs-1234-123").is_err());
+        assert!(grade("gs-12345-12").is_err());
+        assert!(grade("gs123").is_err());
+        assert!(grade("tenure").is_err());
+        assert!(grade("vix").is_err());
+    }
+
+    #[test]
+    fn test_grade_display() {
+        assert_eq!(g(None, None, "12").to_string(), "12");
+        assert_eq!(g(Some("gs"), None, "12").to_string(), "GS-12");
+        assert_eq!(g(Some("gs"), Some("0343"), "12").to_string(), "GS-0343-12");
+        assert_eq!(g(None, Some("0343"), "12").to_string(), "12");
+    }
+
+    #[test]
+    fn test_is_valid() {
+        assert!(g(None, None, "12").is_valid());
+        assert!(g(Some("GS"), None, "15").is_valid());
+        assert!(g(Some("ws"), None, "19").is_valid());
+        assert!(g(Some("es"), None, "00").is_valid());
+        assert!(w(None, "iv", "4").is_valid());
+
+        assert!(Grade {
+            kind: Kind::Senior,
+            ..w(Some("st"), "st", "00")
+        }
+        .is_valid());
+
+        assert!(Grade {
+            kind: Kind::Nurse,
+            ..w(Some("VN"), "V", "5")
+        }
+        .is_valid());
+
+        assert!(!Grade {
+            kind: Kind::Nurse,
+            ..w(Some("VN"), "VI", "6")
+        }
+        .is_valid());
+
+        assert!(!g(None, None, "0").is_valid());
+        assert!(!g(None, None, "42").is_valid());
+        assert!(!g(Some("gs"), None, "16").is_valid());
+        assert!(!g(Some("gs"), None, "00").is_valid());
+        assert!(!g(Some("wg"), None, "16").is_valid());
+    }
+
+    #[test]
+    fn test_ladder() {
+        assert_eq!(
+            ladder("gs-7/9/11/13"),
+            Ok((
+                "",
+                vec![
+                    g(Some("gs"), None, "7"),
+                    g(Some("gs"), None, "9"),
+                    g(Some("gs"), None, "11"),
+                    g(Some("gs"), None, "13"),
+                ]
+            ))
+        );
+
+        assert_eq!(
+            ladder("gs-0510-07, 09"),
+            Ok((
+                "",
+                vec![
+                    g(Some("gs"), Some("0510"), "07"),
+                    g(Some("gs"), Some("0510"), "09")
+                ]
+            ))
+        );
+
+        let band = |pay_plan, grade| Grade {
+            kind: Kind::Band,
+            ..g(pay_plan, None, grade)
+        };
+
+        assert_eq!(
+            ladder("nh-02/03"),
+            Ok(("", vec![band(Some("nh"), "02"), band(Some("nh"), "03")]))
+        );
+
+        let nurse = |text, grade| Grade {
+            kind: Kind::Nurse,
+            ..w(Some("VN"), text, grade)
+        };
+
+        assert_eq!(
+            ladder("VN-I/II/III"),
+            Ok((
+                "",
+                vec![nurse("I", "1"), nurse("II", "2"), nurse("III", "3")]
+            ))
+        );
+
+        assert_eq!(
+            ladder("gs-12, x"),
+            Ok(("x", vec![g(Some("gs"), None, "12")]))
+        );
+
+        assert_eq!(
+            ladder("gs-12 step 05/13, step 1"),
+            Ok((
+                "",
+                vec![g(Some("gs"), None, "12"), g(Some("gs"), None, "13")]
+            ))
+        );
+
+        assert_eq!(
+            ladder("gs-13/5 only"),
+            Ok((" only", vec![g(Some("gs"), None, "13")]))
+        );
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(g(Some("gs"), None, "13").matches("13"));
+        assert!(g(Some("gs"), None, "13").matches("GS-13"));
+        assert!(g(Some("gs"), None, "09").matches("gs-9"));
+        assert!(g(None, None, "12").matches("12"));
+
+        assert!(!g(Some("gs"), None, "13").matches("12"));
+        assert!(!g(Some("gs"), None, "13").matches("WG-13"));
+        assert!(!g(None, None, "12").matches("GS-12"));
+        assert!(!g(None, None, "12").matches(""));
+    }
+
+    #[test]
+    fn test_max_grade() {
+        assert_eq!(
+            max_grade("gs-11/12/13"),
+            Ok(("", g(Some("gs"), None, "13")))
+        );
+
+        assert_eq!(
+            max_grade("gs-5 / gs-6 / gs-7"),
+            Ok(("", g(Some("gs"), None, "7")))
+        );
+
+        assert_eq!(
+            max_grade("gs-11 to gs-13"),
+            Ok(("", g(Some("gs"), None, "13")))
+        );
+
+        assert_eq!(
+            max_grade("gs-9 through gs-11 up to gs-12"),
+            Ok(("", g(Some("gs"), None, "12")))
+        );
+
+        assert_eq!(
+            max_grade("gs-12 to 2 days"),
+            Ok(("2 days", g(Some("gs"), None, "12")))
+        );
+    }
+
+    #[test]
+    fn test_no_fpl() {
+        assert!(no_fpl("Promotion potential: None"));
+        assert!(no_fpl("FPL N/A"));
+        assert!(no_fpl("The full performance level is not applicable."));
+        assert!(no_fpl("There is no promotion potential."));
+
+        assert!(!no_fpl("FPL: GS-12"));
+        assert!(!no_fpl("FPL: nation"));
+        assert!(!no_fpl("casino promotion potential"));
+        assert!(!no_fpl("no grade here"));
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize(""), "");
+        assert_eq!(normalize("\n\nabc   \t  DEF 1\n2\t3\n  "), "abc def 1 2 3");
+        assert_eq!(normalize_whitespace(" GS-\t13\n FPL "), "GS- 13 FPL");
+    }
+
+    #[test]
+    fn test_series_title() {
+        assert_eq!(series_title("0510"), Some("Accounting"));
+        assert_eq!(
+            series_title("2210"),
+            Some("Information Technology Management")
+        );
+        assert_eq!(series_title("83"), Some("Police"));
+        assert_eq!(series_title("0000"), None);
+        assert_eq!(series_title("x"), None);
+    }
+
+    #[test]
+    fn test_strip_html() {
+        assert_eq!(
+            strip_html("FPL&nbsp;is&nbsp;<b>GS-13</b>"),
+            "FPL\u{a0}is\u{a0}GS-13"
+        );
+        assert_eq!(strip_html("<p>FPL</p><p>GS-13</p>"), " FPL  GS-13 ");
+        assert_eq!(strip_html("a<!-- b -->c"), "ac");
+        assert_eq!(strip_html("a<script>if (x < y) {}</script>b"), "a b");
+        assert_eq!(strip_html("1 < 2 &amp; 3 <4"), "1 < 2 & 3 <4");
+        assert_eq!(strip_html("&lt;b&gt;"), "<b>");
+    }
+}
@@ -1,402 +1,1089 @@
+mod check;
+mod completions;
+mod compression;
+mod config;
+mod diff;
+mod encoding;
+mod explain;
+mod fetch;
+mod glob;
+mod input;
+mod mmap;
+mod output;
+mod parquet;
+mod print;
+mod progress;
+mod review;
+mod serve;
+mod sqlite;
+mod state;
+mod stats;
+mod unzip;
+mod watch;
+mod xlsx;
+
 use std::{
-    io::{stdout, Result},
+    cmp::Ordering,
+    collections::HashMap,
+    fs,
+    io::Result,
+    num::NonZeroUsize,
     path::{Path, PathBuf},
+    process::ExitCode,
 };
 
-use clap::Parser;
-use csv::{ReaderBuilder, WriterBuilder};
-use html_escape::encode_text;
-
-use nom::{
-    branch::alt,
-    bytes::complete::{tag, tag_no_case},
-    character::complete::{alpha1, anychar, char, digit1, multispace0, one_of},
-    combinator::{fail, opt, verify},
-    error::Error,
-    multi::many_till,
-    IResult,
+use clap::{ArgGroup, Parser, Subcommand, ValueEnum};
+use csv::WriterBuilder;
+use encoding::Encoding;
+use fpl::{
+    dehyphenate, entry_grade_with, get_fpl_mention_with, get_fpl_mentions_with, no_fpl_with,
+    normalize, normalize_whitespace, strip_html, Grade, Mention, Rules,
+};
+use input::{Reader, Record};
+use output::Output;
+use print::Printer;
+use progress::Progress;
+use rayon::prelude::*;
+use state::State;
+use stats::Stats;
+
+const CHUNK_SIZE: usize = 1024;
+
+/// The exit code used when reading, parsing or writing fails.
+const EXIT_ERROR: u8 = 2;
+
+/// The exit code used when a grade was not found in some of the records.
+const EXIT_UNMATCHED: u8 = 1;
+
+/// The extraction options of the subcommands that do not print records, such as `stats`.
+static NO_EXTRACT_ARGS: ExtractArgs = ExtractArgs {
+    all_mentions: false,
+    annotations: false,
+    color: Color::Auto,
+    columns: Vec::new(),
+    conflicts: false,
+    crlf: false,
+    css: None,
+    descending: false,
+    entry_grade: false,
+    html: false,
+    json: false,
+    jsonl: false,
+    keep: Keep::First,
+    ladder: false,
+    markdown: false,
+    matched_only: false,
+    page_size: None,
+    parquet: None,
+    pretty: false,
+    provenance: None,
+    series_titles: false,
+    snippet: false,
+    sort_by: None,
+    source_file: false,
+    sqlite: None,
+    state: None,
+    state_by: UniqueBy::Text,
+    template: None,
+    unmatched_only: false,
+    xlsx: None,
 };
-
-use serde::Deserialize;
 
 #[derive(Parser)]
-struct Args {
-    input: PathBuf,
+pub struct Args {
+    #[command(subcommand)]
+    command: Command,
+
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    #[arg(long, global = true)]
+    dehyphenate: bool,
+
+    #[arg(long, default_value = ",", value_parser = parse_delimiter, global = true)]
+    delimiter: u8,
+
+    #[arg(long, value_enum, default_value_t = Encoding::Auto, global = true)]
+    encoding: Encoding,
+
+    #[arg(long, global = true)]
+    fuzzy: bool,
+
+    #[arg(long, global = true)]
+    headers: bool,
+
+    #[arg(long, global = true)]
+    id_column: Option<String>,
+
+    #[arg(long, global = true)]
+    max_grade: Option<u32>,
+
+    #[arg(long, global = true)]
+    min_grade: Option<u32>,
+
+    #[arg(long, global = true)]
+    mmap: bool,
+
+    #[arg(long, global = true)]
+    no_progress: bool,
+
+    #[arg(long, value_enum, default_value_t = Normalization::None, global = true)]
+    normalize: Normalization,
+
+    #[arg(long, global = true)]
+    normalize_output: bool,
+
+    #[arg(long, default_value = ",", value_parser = parse_delimiter, global = true)]
+    out_delimiter: u8,
+
+    /// Escapes quotes in quoted fields with this character instead of doubling them.
+    #[arg(long, value_parser = parse_delimiter, global = true)]
+    out_escape: Option<u8>,
+
+    #[arg(long, default_value = "\"", value_parser = parse_delimiter, global = true)]
+    out_quote: u8,
+
+    #[arg(long, global = true)]
+    output: Option<PathBuf>,
+
+    #[arg(long, global = true, value_delimiter = ',')]
+    pay_plans: Vec<String>,
+
+    #[arg(long, value_enum, default_value_t = QuoteStyle::Necessary, global = true)]
+    quote_style: QuoteStyle,
+
+    #[arg(long, global = true)]
+    recursive: bool,
+
+    #[arg(long, global = true)]
+    rules: Option<PathBuf>,
+
+    /// Reads this sheet of XLSX inputs instead of the first one.
+    #[arg(long, global = true)]
+    sheet: Option<String>,
 
+    #[arg(long, global = true)]
+    skip_bad_rows: bool,
+
+    #[arg(long, global = true)]
+    strict_pay_plans: bool,
+
+    #[arg(long, global = true)]
+    strip_html: bool,
+
+    #[arg(long, global = true)]
+    text_column: Option<String>,
+
+    #[arg(long, global = true)]
+    unique: bool,
+
+    #[arg(long, value_enum, global = true, conflicts_with = "unique")]
+    unique_by: Option<UniqueBy>,
+
+    #[arg(long, global = true)]
+    variants: Option<PathBuf>,
+}
+
+/// The options of the subcommands that print the extracted records.
+#[derive(clap::Args)]
+#[command(group(ArgGroup::new("format")))]
+pub struct ExtractArgs {
     #[arg(long)]
+    all_mentions: bool,
+
+    #[arg(long, group = "format")]
+    annotations: bool,
+
+    #[arg(long, value_enum, default_value_t = Color::Auto, requires = "pretty")]
+    color: Color,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        conflicts_with_all = ["annotations", "html", "markdown", "parquet", "pretty", "sqlite", "xlsx"]
+    )]
+    columns: Vec<String>,
+
+    #[arg(long)]
+    conflicts: bool,
+
+    #[arg(long)]
+    crlf: bool,
+
+    #[arg(long, requires = "html")]
+    css: Option<PathBuf>,
+
+    #[arg(long, requires = "sort_by")]
+    descending: bool,
+
+    #[arg(long)]
+    entry_grade: bool,
+
+    #[arg(long, group = "format")]
     html: bool,
 
+    #[arg(long, group = "format")]
+    json: bool,
+
+    #[arg(long, group = "format")]
+    jsonl: bool,
+
+    #[arg(long, value_enum, default_value_t = Keep::First, requires = "unique_by")]
+    keep: Keep,
+
     #[arg(long)]
-    unique: bool,
-}
+    ladder: bool,
 
-#[derive(Deserialize)]
-struct Record {
-    id: usize,
-    text: String,
-}
+    #[arg(long, group = "format")]
+    markdown: bool,
+
+    #[arg(long)]
+    matched_only: bool,
+
+    #[arg(long, requires = "html")]
+    page_size: Option<NonZeroUsize>,
+
+    #[arg(long, group = "format", conflicts_with = "output")]
+    parquet: Option<PathBuf>,
+
+    #[arg(long, group = "format")]
+    pretty: bool,
+
+    #[arg(long, requires = "unique")]
+    provenance: Option<PathBuf>,
+
+    #[arg(long)]
+    series_titles: bool,
+
+    #[arg(long)]
+    snippet: bool,
+
+    #[arg(long, value_enum)]
+    sort_by: Option<SortBy>,
 
-fn alphas(count: usize, s: &str) -> IResult<&str, &str> {
-    verify(alpha1, |s: &str| s.len() == count)(s)
+    #[arg(long)]
+    source_file: bool,
+
+    #[arg(long, group = "format", conflicts_with = "output")]
+    sqlite: Option<PathBuf>,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["html", "json", "markdown", "parquet", "pretty", "sqlite", "xlsx"]
+    )]
+    state: Option<PathBuf>,
+
+    #[arg(long, value_enum, default_value_t = UniqueBy::Text, requires = "state")]
+    state_by: UniqueBy,
+
+    #[arg(long, requires = "html")]
+    template: Option<PathBuf>,
+
+    #[arg(long, conflicts_with_all = ["matched_only", "max_grade", "min_grade"])]
+    unmatched_only: bool,
+
+    #[arg(long, group = "format", conflicts_with = "output")]
+    xlsx: Option<PathBuf>,
 }
 
-fn dedup_records(records: &mut Vec<Record>) {
-    records.iter_mut().for_each(|r| r.text = normalize(&r.text));
-    records.sort_by(|a, b| a.text.cmp(&b.text));
-    records.dedup_by(|a, b| a.text == b.text);
+#[derive(Subcommand)]
+enum Command {
+    /// Checks the extraction against a CSV corpus with `text` and expected `grade` columns.
+    Check { corpus: PathBuf },
+    /// Prints the completion script of a shell.
+    #[command(hide = true)]
+    Completions {
+        #[arg(value_enum)]
+        shell: completions::Shell,
+    },
+    /// Compares the CSV outputs of two runs and lists the records whose grade differs.
+    Diff { old: PathBuf, new: PathBuf },
+    /// Shows the trigger phrases found in a text, the grades after each and the grade extracted,
+    /// or where parsing stopped.
+    Explain { text: String },
+    /// Extracts the full performance level of each record of the input files.
+    Extract {
+        #[arg(required_unless_present = "watch")]
+        input: Vec<PathBuf>,
+        /// Processes the files appearing in a directory as they arrive, appending to the output.
+        #[arg(
+            long,
+            conflicts_with_all = [
+                "input", "html", "json", "markdown", "parquet", "pretty", "sqlite", "state", "unique", "xlsx"
+            ]
+        )]
+        watch: Option<PathBuf>,
+        #[command(flatten)]
+        options: ExtractArgs,
+    },
+    /// Searches USAJOBS for job announcements and extracts the grades from their duties and
+    /// qualifications.
+    Fetch {
+        #[arg(long)]
+        keyword: String,
+        #[arg(long)]
+        api_key: String,
+        /// The email address the API key was requested with.
+        #[arg(long)]
+        email: String,
+        #[command(flatten)]
+        options: ExtractArgs,
+    },
+    /// Steps through the records of the input files to accept, correct or reject the grade
+    /// extracted from each, writing the decisions to a CSV file.
+    Review {
+        #[arg(required = true)]
+        input: Vec<PathBuf>,
+        /// The file the decisions are appended to, `<input>.review.csv` by default.
+        #[arg(long)]
+        decisions: Option<PathBuf>,
+    },
+    /// Serves the extraction over HTTP, with `POST /extract` for JSON texts and `POST /batch` for
+    /// CSV uploads.
+    Serve {
+        /// The address to listen on, `0.0.0.0` to accept connections from other machines.
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+    },
+    /// Prints grade, rule and pay plan statistics instead of the records.
+    Stats {
+        #[arg(required = true)]
+        input: Vec<PathBuf>,
+    },
 }
 
-fn fpl(s: &str) -> IResult<&str, &str> {
-    if let Ok((s, fpl)) = tag_no_case::<&str, &str, Error<&str>>("fpl")(s) {
-        return Ok((s, fpl));
-    }
+/// Whether `--pretty` colors the grades, by default only when writing to a terminal.
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Color {
+    Always,
+    Auto,
+    Never,
+}
 
-    let start = s;
+/// Which of the records with the same ID `--unique-by id` keeps.
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Keep {
+    First,
+    Longest,
+}
 
-    let (s, _) = alt((tag_no_case("full"), tag_no_case("poll")))(s)?;
-    let (s, _) = opt_one_of(" -", s)?;
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Normalization {
+    None,
+    /// Collapses runs of whitespace into single spaces.
+    Whitespace,
+    /// Collapses whitespace and lowercases the text.
+    Full,
+}
 
-    let (s, _) = alt((
-        words(&["career", "ladder", "grade"]),
-        tag_no_case("grade"),
-        tag_no_case("peformance"),
-        tag_no_case("perf."),
-        tag_no_case("perfformance"),
-        tag_no_case("performance"),
-        tag_no_case("performane"),
-        tag_no_case("perfromance"),
-        tag_no_case("perormance"),
-        tag_no_case("promotion"),
-    ))(s)?;
+/// Which fields of the CSV output are quoted.
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum QuoteStyle {
+    Always,
+    /// Quotes only the fields with a delimiter, a quote or a line break.
+    Necessary,
+    Never,
+}
 
-    let (s, _) = multispace0(s)?;
-    let (s, _) = opt(tag_no_case("level"))(s)?;
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum SortBy {
+    Id,
+    /// Sorts by the grade number, with the records without a grade last.
+    Grade,
+    Text,
+    /// Sorts the records with a grade before those without.
+    Match,
+}
 
-    Ok((s, &start[0..start.len() - s.len()]))
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum UniqueBy {
+    /// Keeps one of the records with the same ID.
+    Id,
+    /// Keeps one of the records with the same normalized text, like `--unique`.
+    Text,
 }
 
-fn fpl_grade(s: &str) -> IResult<&str, &str> {
-    let (s, _) = fpl(s)?;
-    let (s, _) = multispace0(s)?;
-
-    let (s, _) = opt(alt((
-        alt((
-            tag("-"),
-            tag(","),
-            tag(":"),
-            tag("(fpl)"),
-            tag("("),
-            tag("="),
-        )),
-        words(&["at", "grade", "level"]),
-        tag_no_case("at"),
-        words(&["for", "this", "pd", "is"]),
-        words(&["for", "this", "position", "is"]),
-        words(&["is", "at", "the"]),
-        words(&["is", "at"]),
-        words(&["is", "level", ":"]),
-        words(&["is", "the"]),
-        words(&["management", "analyst"]),
-        tag_no_case("is"),
-        words(&["of", "a", "career", "ladder", "position"]),
-        words(&["of", "a"]),
-        words(&["of", "position", "is"]),
-        words(&["of", "position", ":"]),
-        words(&["of", "the", "position", "is"]),
-        words(&["of", "this", "pd", "is"]),
-        words(&["of", "this", "position", "is"]),
-    )))(s)?;
-
-    let (s, _) = multispace0(s)?;
-
-    max_grade(s)
+pub struct Row<'a> {
+    record: &'a Record,
+    mention: Option<Mention<'a>>,
+    /// The byte offset of the trigger of `mention` in the input text.
+    offset: usize,
+    /// The grade below the full performance level, only looked for with `--entry-grade`.
+    entry: Option<Grade<'a>>,
+    /// All mentions with their offsets, only collected with `--all-mentions` or `--conflicts`.
+    mentions: Vec<(usize, Mention<'a>)>,
+    /// Whether the text says that there is no full performance level, when no grade was found.
+    no_fpl: bool,
 }
 
-fn get_fpl_grade(s: &str) -> Option<&str> {
-    if let Ok((_, (_, grade))) = many_till(anychar, fpl_grade)(s) {
-        Some(grade)
-    } else if let Ok((_, (_, grade))) = many_till(anychar, target_grade)(s) {
-        Some(grade)
-    } else {
-        None
+impl Args {
+    /// Returns a builder of CSV writers with the delimiter, quoting and escaping of the output.
+    fn csv_writer(&self) -> WriterBuilder {
+        let mut builder = WriterBuilder::new();
+
+        builder
+            .delimiter(self.out_delimiter)
+            .quote(self.out_quote)
+            .quote_style(match self.quote_style {
+                QuoteStyle::Always => csv::QuoteStyle::Always,
+                QuoteStyle::Necessary => csv::QuoteStyle::Necessary,
+                QuoteStyle::Never => csv::QuoteStyle::Never,
+            });
+
+        if let Some(escape) = self.out_escape {
+            builder.double_quote(false).escape(escape);
+        }
+
+        builder
+    }
+
+    /// Returns the extraction options, which are all off for the subcommands without them.
+    fn extract(&self) -> &ExtractArgs {
+        match &self.command {
+            Command::Extract { options, .. } | Command::Fetch { options, .. } => options,
+            _ => &NO_EXTRACT_ARGS,
+        }
+    }
+
+    fn inputs(&self) -> &[PathBuf] {
+        match &self.command {
+            Command::Extract { input, .. }
+            | Command::Review { input, .. }
+            | Command::Stats { input } => input,
+            _ => &[],
+        }
+    }
+
+    fn watch(&self) -> Option<&Path> {
+        match &self.command {
+            Command::Extract { watch, .. } => watch.as_deref(),
+            _ => None,
+        }
     }
 }
 
-fn get_match_prefix_and_suffix<'a>(s: &'a str, m: &'a str) -> (&'a str, &'a str) {
-    unsafe {
-        let start = s.as_ptr();
-        let match_start = m.as_ptr();
-        let offset = match_start.offset_from(start).unsigned_abs();
+impl<'a> Row<'a> {
+    fn bounds(&self) -> Option<(Grade<'a>, Grade<'a>)> {
+        self.ladder().and_then(ladder_bounds)
+    }
+
+    fn grade(&self) -> Option<Grade<'a>> {
+        self.mention.as_ref().map(Mention::grade)
+    }
+
+    /// Returns the byte range of the grade in the input text, including its pay plan when it is
+    /// in the text, as in `GS-13` and `gs-11/12/13`, but not the `VN` of `Nurse III`.
+    fn grade_span(&self) -> Option<(usize, usize)> {
+        Some(mention_span(self.offset, self.mention.as_ref()?))
+    }
 
-        let prefix = &s[0..offset];
-        let suffix = &s[offset + m.len()..];
+    /// Returns the byte range of the grade as it is written in the input text, without its pay
+    /// plan.
+    fn grade_text_span(&self) -> Option<(usize, usize)> {
+        let (_, end) = self.grade_span()?;
 
-        (prefix, suffix)
+        Some((end - self.grade()?.text.len(), end))
+    }
+
+    fn is_selected(&self, args: &Args) -> bool {
+        if args.min_grade.is_some() || args.max_grade.is_some() {
+            return self.grade().is_some_and(|g| {
+                let value = g.value();
+
+                args.min_grade.is_none_or(|min| value >= min)
+                    && args.max_grade.is_none_or(|max| value <= max)
+            });
+        }
+
+        if args.extract().matched_only {
+            self.mention.is_some()
+        } else if args.extract().unmatched_only {
+            self.mention.is_none()
+        } else {
+            true
+        }
+    }
+
+    fn ladder(&self) -> Option<&[Grade<'a>]> {
+        self.mention.as_ref().map(|m| m.ladder.as_slice())
     }
 }
 
-fn grade(s: &str) -> IResult<&str, &str> {
-    if let Ok((s, grade)) = max_digits(2, s) {
-        return Ok((s, grade));
+/// Compares two rows by `key`, in descending order if `descending` is set. Rows without a grade
+/// stay last when sorting by grade in either order.
+fn compare_rows(a: &Row, b: &Row, key: SortBy, descending: bool) -> Ordering {
+    let order = |ordering: Ordering| {
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    };
+
+    match key {
+        SortBy::Id => order(a.record.id.cmp(&b.record.id)),
+        SortBy::Grade => match (a.grade(), b.grade()) {
+            (Some(a), Some(b)) => order(a.value().cmp(&b.value())),
+            (a, b) => b.is_some().cmp(&a.is_some()),
+        },
+        SortBy::Text => order(a.record.text.cmp(&b.record.text)),
+        SortBy::Match => order(b.mention.is_some().cmp(&a.mention.is_some())),
     }
+}
+
+/// Removes the records whose normalized text repeats an earlier one and returns the IDs of the
+/// remaining records with the IDs of the records they absorbed and their text.
+fn dedup_records(records: &mut Vec<Record>) -> Vec<(usize, Vec<usize>, String)> {
+    normalize_records(records);
+    records.sort_by(|a, b| a.text.cmp(&b.text));
 
-    let (s, _) = alphas(2, s)?;
-    let (s, sep) = opt_one_of(" -.", s)?;
-    let (s, _) = opt(tag(" "))(s)?;
+    let provenance = records
+        .chunk_by(|a, b| a.text == b.text)
+        .map(|group| {
+            let absorbed = group[1..].iter().map(|r| r.id).collect();
+            (group[0].id, absorbed, group[0].text.clone())
+        })
+        .collect();
+
+    records.dedup_by(|a, b| a.text == b.text);
+    provenance
+}
 
-    match sep {
-        None | Some(' ') => max_digits(2, s),
-        Some(sep) => {
-            let (s, grade_or_series) = max_digits(4, s)?;
+/// Removes the records whose ID repeats an earlier one, keeping the first or the longest text in
+/// the place of the first.
+fn dedup_records_by_id(records: Vec<Record>, keep: Keep) -> Vec<Record> {
+    let mut indices = HashMap::<usize, usize>::new();
+    let mut unique: Vec<Record> = Vec::new();
 
-            if let Ok((s, _)) = char::<&str, Error<&str>>(sep)(s) {
-                if let Ok((s, grade)) = max_digits(2, s) {
-                    return Ok((s, grade));
+    for record in records {
+        match indices.get(&record.id) {
+            Some(&index) => {
+                if keep == Keep::Longest && record.text.len() > unique[index].text.len() {
+                    unique[index] = record;
                 }
             }
-
-            if grade_or_series.len() <= 2 {
-                Ok((s, grade_or_series))
-            } else {
-                fail(s)
+            None => {
+                indices.insert(record.id, unique.len());
+                unique.push(record);
             }
         }
     }
-}
 
-fn main() -> Result<()> {
-    let args = Args::parse();
-    let mut records = read_records(args.input)?;
+    unique
+}
 
-    if args.unique {
-        dedup_records(&mut records);
-    }
+fn extract_rows<'a>(records: &'a [Record], rules: &Rules, args: &Args) -> Vec<Row<'a>> {
+    records
+        .par_iter()
+        .map(|record| {
+            let (offset, mention) = match get_fpl_mention_with(rules, record.input()) {
+                Some((offset, mention)) => (offset, Some(mention)),
+                None => (0, None),
+            };
+
+            let entry = mention
+                .as_ref()
+                .filter(|_| args.extract().entry_grade)
+                .and_then(|m| entry_grade_with(rules, record.input(), offset, m));
+
+            Row {
+                record,
+                entry,
+                mentions: if args.extract().all_mentions || args.extract().conflicts {
+                    get_fpl_mentions_with(rules, record.input())
+                } else {
+                    Vec::new()
+                },
+                no_fpl: mention.is_none() && no_fpl_with(rules, record.input()),
+                mention,
+                offset,
+            }
+        })
+        .collect()
+}
 
-    if args.html {
-        print_html(&records, !args.unique);
-    } else {
-        print_csv(&records, !args.unique)?;
-    }
+fn ladder_bounds<'a>(ladder: &[Grade<'a>]) -> Option<(Grade<'a>, Grade<'a>)> {
+    let min = ladder.iter().min_by_key(|g| g.value())?;
+    let max = ladder.iter().max_by_key(|g| g.value())?;
 
-    Ok(())
+    Some((*min, *max))
 }
 
-fn max_digits(count: usize, s: &str) -> IResult<&str, &str> {
-    verify(digit1, |s: &str| s.len() <= count)(s)
+/// Reads the `--state` of earlier runs, if any.
+fn load_state(args: &Args) -> Result<Option<State>> {
+    (args.extract().state.as_deref())
+        .map(|path| State::load(path, args.extract().state_by))
+        .transpose()
 }
 
-fn max_grade(s: &str) -> IResult<&str, &str> {
-    let (mut s, mut max_grade) = grade(s)?;
+fn main() -> ExitCode {
+    let mut args = match config::parse() {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            return ExitCode::from(EXIT_ERROR);
+        }
+    };
 
-    loop {
-        (s, _) = multispace0(s)?;
-        (s, _) = opt_one_of(",/", s)?;
-        (s, _) = multispace0(s)?;
+    if args.unique_by == Some(UniqueBy::Text) {
+        args.unique = true;
+    }
 
-        if let Ok((gs, grade)) = grade(s) {
-            (s, max_grade) = (gs, grade);
-        } else {
-            return Ok((s, max_grade));
+    match run(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(EXIT_UNMATCHED),
+        Err(error) => {
+            eprintln!("Error: {error}");
+            ExitCode::from(EXIT_ERROR)
         }
     }
 }
 
-fn normalize(text: &str) -> String {
-    text.split_whitespace()
-        .collect::<Vec<_>>()
-        .join(" ")
-        .to_lowercase()
+/// Returns the byte range of the grade of `mention`, whose trigger starts at byte `offset` of the
+/// input, as [`Row::grade_span`] does.
+fn mention_span(offset: usize, mention: &Mention) -> (usize, usize) {
+    let (start, end) = mention.spans[mention.spans.len() - 1];
+
+    (offset + start, offset + end)
 }
 
-fn opt_one_of<'a>(list: &str, s: &'a str) -> IResult<&'a str, Option<char>> {
-    opt(one_of(list))(s)
+fn normalize_records(records: &mut [Record]) {
+    records.iter_mut().for_each(|r| r.text = normalize(&r.text));
 }
 
-fn print_csv(records: &[Record], print_ids: bool) -> Result<()> {
-    let mut writer = WriterBuilder::new().from_writer(stdout());
+fn parse_delimiter(s: &str) -> std::result::Result<u8, String> {
+    match s {
+        "\\t" | "tab" => Ok(b'\t'),
+        _ if s.len() == 1 && s.is_ascii() => Ok(s.as_bytes()[0]),
+        _ => Err(format!(
+            "'{s}' is not a single ASCII character, '\\t' or 'tab'"
+        )),
+    }
+}
 
-    for record in records {
-        let grade = get_fpl_grade(&record.text).unwrap_or_default();
+fn preprocess_records(records: &mut [Record], args: &Args) {
+    if args.strip_html {
+        records
+            .iter_mut()
+            .for_each(|r| r.text = strip_html(&r.text));
+    }
 
-        if print_ids {
-            writer.write_record([record.id.to_string().as_str(), grade, &record.text])?;
+    if args.dehyphenate {
+        records
+            .iter_mut()
+            .for_each(|r| r.text = dehyphenate(&r.text));
+    }
+
+    let normalize = match args.normalize {
+        Normalization::None => return,
+        Normalization::Whitespace => normalize_whitespace,
+        Normalization::Full => normalize,
+    };
+
+    for record in records {
+        if args.normalize_output {
+            record.text = normalize(&record.text);
         } else {
-            writer.write_record([grade, &record.text])?;
+            record.normalized = Some(normalize(&record.text));
         }
     }
+}
 
-    Ok(())
+/// Prints the records that earlier runs of the `--state` did not process, returning whether a
+/// grade was found in every record, and saves the state.
+fn print_records(
+    records: impl Iterator<Item = Result<Record>>,
+    rules: &Rules,
+    args: &Args,
+    progress: &mut Progress,
+) -> Result<bool> {
+    let mut state = load_state(args)?;
+    let mut printer = Printer::new(args)?;
+    let records = skip_processed(records, state.as_mut());
+    let matched = process(records, rules, args, progress, |rows| printer.print(rows))?;
+
+    printer.finish()?;
+    state.as_ref().map(State::save).transpose()?;
+
+    Ok(matched)
 }
 
-fn print_html(records: &[Record], print_ids: bool) {
-    println!("<!doctype html>");
-    println!("<html lang='en'>");
-    println!("\t<body>");
-    println!("\t<style>");
-    println!("\t.fpl {{color: red}}");
-    println!("\ttable, td, th {{border: 1px solid; border-collapse: collapse}}");
-    println!("\t</style>");
-    println!("\t\t<table>");
-    println!("\t\t\t<thead>");
-    println!("\t\t\t\t<tr>");
-
-    println!(
-        "\t\t\t\t\t<th scope='col'>{}</th>",
-        if print_ids { "ID" } else { "Line" }
-    );
-
-    println!("\t\t\t\t\t<th scope='col'>Grade</th>");
-    println!("\t\t\t\t\t<th scope='col'>Text</th>");
-    println!("\t\t\t\t</tr>");
-    println!("\t\t\t</thead>");
-    println!("\t\t\t<tbody>");
-
-    for (i, record) in records.iter().enumerate() {
-        println!("\t\t\t\t<tr>");
-
-        println!(
-            "\t\t\t\t\t<td>{}</td>",
-            if print_ids { record.id } else { i + 1 }
-        );
+/// Extracts the grades of all records, passing the selected ones to `f` in chunks, and returns
+/// whether a grade was found in every record. Deduplicating or sorting the records reads them all
+/// into a single chunk.
+fn process(
+    mut reader: impl Iterator<Item = Result<Record>>,
+    rules: &Rules,
+    args: &Args,
+    progress: &mut Progress,
+    mut f: impl FnMut(&[Row]) -> Result<()>,
+) -> Result<bool> {
+    let mut matched = true;
+
+    let mut select = |records: &[Record]| -> Result<()> {
+        let mut rows = extract_rows(records, rules, args);
+        let found = rows.iter().filter(|r| r.mention.is_some()).count();
+
+        matched &= found == rows.len();
+        rows.retain(|r| r.is_selected(args));
+
+        if let Some(key) = args.extract().sort_by {
+            rows.sort_by(|a, b| compare_rows(a, b, key, args.extract().descending));
+        }
 
-        if let Some(grade) = get_fpl_grade(&record.text) {
-            println!("\t\t\t\t\t<td>{grade}</td>");
+        progress.clear();
+        f(&rows)?;
+        progress.update(records.len(), found);
 
-            let (prefix, suffix) = get_match_prefix_and_suffix(&record.text, grade);
+        Ok(())
+    };
 
-            println!(
-                "\t\t\t\t\t<td>{}<span class='fpl'>{}</span>{}</td>",
-                encode_text(prefix),
-                encode_text(grade),
-                encode_text(suffix)
-            );
-        } else {
-            println!("\t\t\t\t\t<td></td>");
-            println!("\t\t\t\t\t<td>{}</td>", encode_text(&record.text));
+    if args.unique || args.unique_by.is_some() || args.extract().sort_by.is_some() {
+        let mut records = reader.collect::<Result<Vec<_>>>()?;
+
+        if args.unique_by == Some(UniqueBy::Id) {
+            records = dedup_records_by_id(records, args.extract().keep);
         }
 
-        println!("\t\t\t\t</tr>");
-    }
+        preprocess_records(&mut records, args);
 
-    println!("\t\t\t</tbody>");
-    println!("\t\t</table>");
-    println!("\t</body>");
-    println!("</html>");
-}
+        if args.unique {
+            let provenance = dedup_records(&mut records);
+
+            if let Some(path) = &args.extract().provenance {
+                write_provenance(path, &provenance, args)?;
+            }
+        } else if args.extract().unmatched_only {
+            normalize_records(&mut records);
+        }
 
-fn read_records<P>(path: P) -> Result<Vec<Record>>
-where
-    P: AsRef<Path>,
-{
-    let mut csv = ReaderBuilder::new().has_headers(false).from_path(path)?;
-    let mut records = Vec::new();
+        select(&records)?;
+        progress.clear();
 
-    for record in csv.deserialize() {
-        records.push(record?);
+        return Ok(matched);
     }
 
-    Ok(records)
+    loop {
+        let mut records = reader
+            .by_ref()
+            .take(CHUNK_SIZE)
+            .collect::<Result<Vec<_>>>()?;
+
+        if records.is_empty() {
+            progress.clear();
+            return Ok(matched);
+        }
+
+        preprocess_records(&mut records, args);
+
+        if args.extract().unmatched_only {
+            normalize_records(&mut records);
+        }
+
+        select(&records)?;
+    }
 }
 
-fn target_grade(s: &str) -> IResult<&str, &str> {
-    let (s, _) = tag_no_case("target")(s)?;
-    let (s, _) = opt(tag_no_case("ed"))(s)?;
-    let (s, _) = multispace0(s)?;
+/// Reads the records of the input files of `extract`, `review` or `stats`, returning whether a
+/// grade was found in every record.
+fn read_inputs(rules: &Rules, args: &Args) -> Result<bool> {
+    let inputs = glob::expand(args.inputs(), args.recursive)?;
+    let mut progress = Progress::new(&inputs, args.no_progress);
+    let reader = Reader::new(args, &inputs).track(progress.bytes());
 
-    let (s, _) = opt(alt((
-        tag_no_case("to"),
-        words(&["position", ","]),
-        words(&["position", "posted", "as", "at", "a"]),
-    )))(s)?;
+    match &args.command {
+        Command::Review { decisions, .. } => {
+            review::review(reader, &inputs, decisions.as_deref(), rules, args)
+        }
+        Command::Stats { .. } => {
+            let mut stats = Stats::default();
+            let mut out = Output::new(args.output.as_deref())?;
 
-    let (s, _) = multispace0(s)?;
+            let matched = process(reader, rules, args, &mut progress, |rows| {
+                stats.add(rows);
+                Ok(())
+            })?;
 
-    max_grade(s)
+            stats.print(&mut out)?;
+            out.commit()?;
+
+            Ok(matched)
+        }
+        _ => print_records(reader, rules, args, &mut progress),
+    }
 }
 
-fn words(words: &'static [&str]) -> impl FnMut(&str) -> IResult<&str, &str> {
-    move |s| {
-        let mut i = s;
+/// Returns whether a grade was found in every record, including those filtered out.
+fn run(args: &Args) -> Result<bool> {
+    let mut rules = match &args.rules {
+        Some(path) => Rules::parse(&fs::read_to_string(path)?)?,
+        None => Rules::default(),
+    };
+
+    if let Some(path) = &args.variants {
+        rules.add_variants(&fs::read_to_string(path)?);
+    }
+
+    if args.fuzzy {
+        rules.set_fuzzy(true);
+    }
+
+    if !args.pay_plans.is_empty() {
+        rules.set_pay_plans(&args.pay_plans);
+    }
 
-        for word in words {
-            i = multispace0(i)?.0;
-            i = tag_no_case(*word)(i)?.0;
+    if args.strict_pay_plans || !args.pay_plans.is_empty() {
+        rules.set_strict_pay_plans(true);
+    }
+
+    if let Some(dir) = args.watch() {
+        return watch::watch(dir, &rules, args);
+    }
+
+    match &args.command {
+        Command::Check { corpus } => check::check(corpus, &rules, args),
+        Command::Completions { shell } => completions::completions(*shell, args),
+        Command::Diff { old, new } => diff::diff(old, new, args),
+        Command::Explain { text } => explain::explain(text, &rules, args),
+        Command::Extract { .. } | Command::Review { .. } | Command::Stats { .. } => {
+            read_inputs(&rules, args)
+        }
+        Command::Serve { host, port } => serve::serve(host, *port, &rules, args),
+        Command::Fetch {
+            keyword,
+            api_key,
+            email,
+            ..
+        } => {
+            let records = fetch::fetch(keyword, api_key, email)?;
+            let mut progress = Progress::new(&[], args.no_progress);
+
+            print_records(records.into_iter().map(Ok), &rules, args, &mut progress)
         }
+    }
+}
+
+/// Drops the records processed by earlier runs, remembering the others in `state`.
+fn skip_processed<'a>(
+    records: impl Iterator<Item = Result<Record>> + 'a,
+    mut state: Option<&'a mut State>,
+) -> impl Iterator<Item = Result<Record>> + 'a {
+    records.filter(move |record| match (record, &mut state) {
+        (Ok(record), Some(state)) => state.insert(record),
+        _ => true,
+    })
+}
 
-        Ok((i, &s[0..s.len() - s.len()]))
+/// Writes which records each row of the `--unique` output stands for, as its text is all the
+/// output identifies it by.
+fn write_provenance(
+    path: &Path,
+    provenance: &[(usize, Vec<usize>, String)],
+    args: &Args,
+) -> Result<()> {
+    let mut out = Output::new(Some(path))?;
+    let mut writer = args.csv_writer().from_writer(&mut out);
+
+    writer.write_record(["id", "absorbed_ids", "text"])?;
+
+    for (id, absorbed, text) in provenance {
+        let absorbed = absorbed.iter().map(usize::to_string).collect::<Vec<_>>();
+        writer.write_record([&id.to_string(), &absorbed.join(";"), text])?;
     }
+
+    writer.flush()?;
+    drop(writer);
+    out.commit()
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{env, process, sync::Arc};
+
     use crate::*;
 
+    fn r(id: usize, source: &str, text: &str) -> Record {
+        Record {
+            id,
+            normalized: None,
+            source: Arc::from(source),
+            text: text.to_string(),
+        }
+    }
+
+    fn temp(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("fpl-{}-{name}", process::id()));
+
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_compare_rows() {
+        let args = Args::parse_from(["fpl", "extract", "input.csv"]);
+        let records = [
+            r(1, "a.csv", "Full performance level: GS-13"),
+            r(2, "a.csv", "Full performance level: GS-12"),
+            r(3, "a.csv", "No grade here"),
+            r(4, "a.csv", "The FPL is GS-12"),
+        ];
+
+        let rows = extract_rows(&records, &Rules::default(), &args);
+        let sorted = |key, descending| {
+            let mut rows = rows.iter().collect::<Vec<_>>();
+
+            rows.sort_by(|a, b| compare_rows(a, b, key, descending));
+            rows.iter().map(|r| r.record.id).collect::<Vec<_>>()
+        };
+
+        // Ties keep their order, and the record without a grade stays last.
+        assert_eq!(sorted(SortBy::Grade, false), [2, 4, 1, 3]);
+        assert_eq!(sorted(SortBy::Grade, true), [1, 2, 4, 3]);
+        assert_eq!(sorted(SortBy::Id, true), [4, 3, 2, 1]);
+        assert_eq!(sorted(SortBy::Match, false), [1, 2, 4, 3]);
+        assert_eq!(sorted(SortBy::Match, true), [3, 1, 2, 4]);
+        assert_eq!(sorted(SortBy::Text, false), [2, 1, 3, 4]);
+    }
+
     #[test]
-    fn test_fpl() {
-        assert_eq!(fpl("fpl"), Ok(("", "fpl")));
-        assert_eq!(fpl("full peformance"), Ok(("", "full peformance")));
-        assert_eq!(fpl("full perf."), Ok(("", "full perf.")));
-        assert_eq!(fpl("full perfformance"), Ok(("", "full perfformance")));
-        assert_eq!(fpl("full performance"), Ok(("", "full performance")));
-        assert_eq!(fpl("full performane"), Ok(("", "full performane")));
-        assert_eq!(fpl("full perfromance "), Ok(("", "full perfromance ")));
-        assert_eq!(fpl("full-performance"), Ok(("", "full-performance")));
+    fn test_dedup_records_by_id() {
+        let records = || {
+            vec![
+                r(1, "a.csv", "GS-12"),
+                r(2, "a.csv", "GS-13"),
+                r(1, "a.csv", "Grade: GS-12"),
+                r(2, "b.csv", "GS-14"),
+                r(3, "b.csv", "GS-15"),
+                r(1, "b.csv", "Grade: GS-11"),
+            ]
+        };
+
+        let unique = |keep| {
+            dedup_records_by_id(records(), keep)
+                .into_iter()
+                .map(|r| (r.id, r.source.to_string(), r.text))
+                .collect::<Vec<_>>()
+        };
+
+        let row = |id, source: &str, text: &str| (id, source.to_string(), text.to_string());
 
         assert_eq!(
-            fpl("full performance level"),
-            Ok(("", "full performance level"))
+            unique(Keep::First),
+            [
+                row(1, "a.csv", "GS-12"),
+                row(2, "a.csv", "GS-13"),
+                row(3, "b.csv", "GS-15"),
+            ]
         );
 
+        // The longest text takes the place of the first, and of equally long ones the first stays.
         assert_eq!(
-            fpl("full perormance level"),
-            Ok(("", "full perormance level"))
+            unique(Keep::Longest),
+            [
+                row(1, "a.csv", "Grade: GS-12"),
+                row(2, "a.csv", "GS-13"),
+                row(3, "b.csv", "GS-15"),
+            ]
         );
+    }
 
+    #[test]
+    fn test_parse_delimiter() {
+        assert_eq!(parse_delimiter(","), Ok(b','));
+        assert_eq!(parse_delimiter(";"), Ok(b';'));
+        assert_eq!(parse_delimiter("\\t"), Ok(b'\t'));
+        assert_eq!(parse_delimiter("tab"), Ok(b'\t'));
+        assert_eq!(parse_delimiter("\t"), Ok(b'\t'));
+        assert_eq!(
+            parse_delimiter(""),
+            Err("'' is not a single ASCII character, '\\t' or 'tab'".to_string())
+        );
         assert_eq!(
-            fpl("fullperformance level"),
-            Ok(("", "fullperformance level"))
+            parse_delimiter(",;"),
+            Err("',;' is not a single ASCII character, '\\t' or 'tab'".to_string())
         );
+        assert_eq!(
+            parse_delimiter("\u{a7}"),
+            Err("'\u{a7}' is not a single ASCII character, '\\t' or 'tab'".to_string())
+        );
+        assert!(parse_delimiter("TAB").is_err());
     }
 
     #[test]
-    fn test_grade() {
-        assert_eq!(grade("1"), Ok(("", "1")));
-        assert_eq!(grade("12"), Ok(("", "12")));
-        assert_eq!(grade("gs 11"), Ok(("", "11")));
-        assert_eq!(grade("gs-0510-09"), Ok(("", "09")));
-        assert_eq!(grade("gs-0998-6"), Ok(("", "6")));
-        assert_eq!(grade("gs-13"), Ok(("", "13")));
-        assert_eq!(grade("gs- 13"), Ok(("", "13")));
-        assert_eq!(grade("gs-13.xxx"), Ok((".xxx", "13")));
-        assert_eq!(grade("gs-13-"), Ok(("-", "13")));
-        assert_eq!(grade("gs-201-13"), Ok(("", "13")));
-        assert_eq!(grade("gs-7"), Ok(("", "7")));
-        assert_eq!(grade("gs15"), Ok(("", "15")));
-        assert_eq!(grade("gs7"), Ok(("", "7")));
-        assert_eq!(grade("wg 7"), Ok(("", "7")));
-        assert_eq!(grade("wg-08"), Ok(("", "08")));
-        assert_eq!(grade("wl-08"), Ok(("", "08")));
-        assert_eq!(grade("ws-7"), Ok(("", "7")));
-        assert_eq!(grade("gs.0343.18"), Ok(("", "18")));
-
-        assert!(grade("123").is_err());
-        assert!(grade("gs 123").is_err());
-        assert!(grade("gs-123").is_err());
-        assert!(grade("gs-1234-").is_err());
-        assert!(grade("gs-1234-123").is_err());
-        assert!(grade("gs-12345-12").is_err());
-        assert!(grade("gs123").is_err());
+    fn test_printer_sqlite_compressed() {
+        let args = Args::parse_from(["fpl", "extract", "--sqlite", "results.db.gz", "input.csv"]);
+        let error = Printer::new(&args).err().unwrap();
+
+        assert_eq!(
+            error.to_string(),
+            "--sqlite cannot write compressed results.db.gz"
+        );
+        assert!(!Path::new("results.db.gz.tmp").exists());
     }
 
     #[test]
-    fn test_max_grade() {
-        assert_eq!(max_grade("gs-11/12/13"), Ok(("", "13")));
-        assert_eq!(max_grade("gs-5 / gs-6 / gs-7"), Ok(("", "7")));
+    fn test_skip_processed() {
+        let input = temp("resumed.csv");
+        let output = temp("resumed.out.csv");
+        let state = temp("resumed.json");
+
+        let args = Args::parse_from([
+            "fpl",
+            "--output",
+            output.to_str().unwrap(),
+            "extract",
+            "--state",
+            state.to_str().unwrap(),
+            input.to_str().unwrap(),
+        ]);
+
+        fs::write(&input, "1,Grade: GS-12\n2,Grade: GS-13\n").unwrap();
+        run(&args).unwrap();
+
+        fs::write(&input, "1,Grade: GS-12\n2,Grade: GS-13\n3,Grade: GS-14\n").unwrap();
+        run(&args).unwrap();
+
+        let ids = fs::read_to_string(&output)
+            .unwrap()
+            .lines()
+            .map(|l| l.split(',').next().unwrap().to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(ids, ["1", "2", "3"]);
+
+        for path in [input, output, state] {
+            fs::remove_file(path).unwrap();
+        }
     }
 
     #[test]
-    fn test_normalize() {
-        assert_eq!(normalize(""), "");
-        assert_eq!(normalize("\n\nabc   \t  DEF 1\n2\t3\n  "), "abc def 1 2 3");
+    fn test_write_provenance() {
+        let args = Args::parse_from(["fpl", "extract", "--unique", "input.csv"]);
+        let path = temp("provenance.csv");
+        let mut records = vec![
+            r(1, "a.csv", "GS-12"),
+            r(2, "a.csv", "GS-13"),
+            r(3, "a.csv", "GS-12"),
+            r(4, "b.csv", "GS-12, or \"13\""),
+            r(5, "b.csv", "gs-12"),
+        ];
+
+        let provenance = dedup_records(&mut records);
+
+        write_provenance(&path, &provenance, &args).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            concat!(
+                "id,absorbed_ids,text\n",
+                "1,3;5,gs-12\n",
+                "4,,\"gs-12, or \"\"13\"\"\"\n",
+                "2,,gs-13\n"
+            )
+        );
+
+        fs::remove_file(path).unwrap();
     }
 }
@@ -0,0 +1,67 @@
+use html_escape::decode_html_entities;
+
+/// Tags that format text within a word, e.g. `GS-<b>13</b>`, and are removed without a space.
+const INLINE_TAGS: [&str; 12] = [
+    "a", "abbr", "b", "em", "font", "i", "mark", "small", "span", "strong", "sub", "sup",
+];
+
+/// Removes HTML tags, comments, scripts and styles from `text` and decodes its entities.
+///
+/// Block tags such as `<p>` or `<br>` are replaced by a space, so that the words around them stay
+/// apart, while inline tags are removed.
+pub fn strip_html(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('<') {
+        stripped.push_str(&rest[0..start]);
+        rest = &rest[start..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+
+        let name = tag_name(rest);
+
+        let Some(end) = rest.find('>').filter(|_| !name.is_empty()) else {
+            stripped.push('<');
+            rest = &rest[1..];
+            continue;
+        };
+
+        let closing = rest.starts_with("</");
+        rest = &rest[end + 1..];
+
+        if !closing && (name == "script" || name == "style") {
+            let lowercase = rest.to_ascii_lowercase();
+
+            rest = lowercase
+                .find(&format!("</{name}"))
+                .and_then(|end| rest[end..].find('>').map(|e| &rest[end + e + 1..]))
+                .unwrap_or("");
+        }
+
+        if !INLINE_TAGS.contains(&name.as_str()) {
+            stripped.push(' ');
+        }
+    }
+
+    stripped.push_str(rest);
+    decode_html_entities(&stripped).into_owned()
+}
+
+/// Returns the lowercase name of the tag `s` starts with, or nothing if it does not start with
+/// a tag.
+fn tag_name(s: &str) -> String {
+    let s = s
+        .strip_prefix("</")
+        .or_else(|| s.strip_prefix('<'))
+        .unwrap_or(s);
+    let s = s.strip_prefix('!').unwrap_or(s);
+
+    s.chars()
+        .take_while(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
@@ -0,0 +1,136 @@
+use std::{
+    fs::File,
+    io::{Error, ErrorKind, Result},
+    path::Path,
+};
+
+/// A file mapped read-only into memory, so that it is paged in as it is parsed instead of being
+/// read into buffers.
+pub struct Map {
+    len: usize,
+    ptr: *const u8,
+}
+
+#[cfg(unix)]
+mod sys {
+    use std::ffi::{c_int, c_void};
+
+    pub const MADV_SEQUENTIAL: c_int = 2;
+    pub const MAP_FAILED: *mut c_void = !0 as *mut c_void;
+    pub const MAP_PRIVATE: c_int = 2;
+    pub const PROT_READ: c_int = 1;
+
+    extern "C" {
+        pub fn madvise(addr: *mut c_void, len: usize, advice: c_int) -> c_int;
+
+        pub fn mmap(
+            addr: *mut c_void,
+            len: usize,
+            prot: c_int,
+            flags: c_int,
+            fd: c_int,
+            offset: i64,
+        ) -> *mut c_void;
+
+        pub fn munmap(addr: *mut c_void, len: usize) -> c_int;
+    }
+}
+
+impl Map {
+    #[cfg(unix)]
+    pub fn open(path: &Path) -> Result<Self> {
+        use std::{os::fd::AsRawFd, ptr};
+
+        let file = File::open(path)?;
+        let len = usize::try_from(file.metadata()?.len())
+            .map_err(|_| Error::new(ErrorKind::OutOfMemory, "file too large to map"))?;
+
+        // An empty mapping is an error, so an empty file is not mapped at all.
+        if len == 0 {
+            return Ok(Self {
+                len,
+                ptr: ptr::NonNull::dangling().as_ptr(),
+            });
+        }
+
+        // SAFETY: the arguments describe a new private read-only mapping of the whole file, which
+        // is checked for failure. As with any mapping, the file must not be truncated while it
+        // is being read.
+        let ptr = unsafe {
+            sys::mmap(
+                ptr::null_mut(),
+                len,
+                sys::PROT_READ,
+                sys::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+
+        if ptr == sys::MAP_FAILED {
+            return Err(Error::last_os_error());
+        }
+
+        // SAFETY: the range was just mapped. The advice only affects read-ahead, so its failure
+        // is ignored.
+        unsafe {
+            sys::madvise(ptr, len, sys::MADV_SEQUENTIAL);
+        }
+
+        Ok(Self {
+            len,
+            ptr: ptr as *const u8,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn open(_path: &Path) -> Result<Self> {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "memory-mapped input is only supported on Unix",
+        ))
+    }
+}
+
+impl AsRef<[u8]> for Map {
+    fn as_ref(&self) -> &[u8] {
+        // SAFETY: `ptr` points to `len` bytes that stay mapped until the map is dropped, or is
+        // dangling for an empty file.
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+impl Drop for Map {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if self.len > 0 {
+            // SAFETY: the range was mapped by `open` and is not used after this.
+            unsafe {
+                sys::munmap(self.ptr as *mut _, self.len);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::{env, fs, process};
+
+    use crate::mmap::*;
+
+    #[test]
+    fn test_open() {
+        let path = env::temp_dir().join(format!("fpl-{}-map.csv", process::id()));
+
+        fs::write(&path, "1,fpl is gs-13\n").unwrap();
+        assert_eq!(Map::open(&path).unwrap().as_ref(), b"1,fpl is gs-13\n");
+
+        fs::write(&path, "").unwrap();
+        assert!(Map::open(&path).unwrap().as_ref().is_empty());
+
+        fs::remove_file(&path).unwrap();
+
+        let error = Map::open(&path).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,206 @@
+use std::{
+    ffi::OsString,
+    fs::{remove_file, rename, File, OpenOptions},
+    io::{stdout, BufWriter, Error, ErrorKind, Result, Seek, SeekFrom, StdoutLock, Write},
+    path::{Path, PathBuf},
+    process::{Child, ChildStdin},
+};
+
+use crate::compression::Compression;
+
+pub enum Output {
+    /// A file written in place, keeping what it already contains.
+    Append(BufWriter<File>),
+    /// A file written through a compressing process, in place if `temp_path` is not set.
+    Compressed {
+        child: Child,
+        compression: Compression,
+        path: PathBuf,
+        temp_path: Option<PathBuf>,
+        writer: Option<BufWriter<ChildStdin>>,
+    },
+    File {
+        path: PathBuf,
+        temp_path: PathBuf,
+        writer: Option<BufWriter<File>>,
+    },
+    Stdout(StdoutLock<'static>),
+}
+
+impl Output {
+    /// Opens `path` for appending, creating it if needed, or standard output without a path.
+    pub fn append(path: Option<&Path>) -> Result<Self> {
+        let Some(path) = path else {
+            return Ok(Self::Stdout(stdout().lock()));
+        };
+
+        let file = OpenOptions::new().append(true).create(true).open(path)?;
+
+        // Compressed streams can be concatenated, so appending to them is appending a new one.
+        if let Some(compression) = Compression::of(path) {
+            return Self::compressed(compression, file, path, None);
+        }
+
+        Ok(Self::Append(BufWriter::new(file)))
+    }
+
+    pub fn commit(mut self) -> Result<()> {
+        match &mut self {
+            Self::Append(writer) => writer.flush(),
+            Self::Compressed {
+                child,
+                compression,
+                path,
+                temp_path,
+                writer,
+            } => {
+                if let Some(writer) = writer.take() {
+                    drop(writer.into_inner().map_err(|e| e.into_error())?);
+                    compression.finish(child)?;
+
+                    if let Some(temp_path) = temp_path {
+                        File::open(&temp_path)?.sync_all()?;
+                        rename(temp_path, path)?;
+                    }
+                }
+
+                Ok(())
+            }
+            Self::File {
+                path,
+                temp_path,
+                writer,
+            } => {
+                if let Some(writer) = writer.take() {
+                    writer.into_inner()?.sync_all()?;
+                    rename(temp_path, path)?;
+                }
+
+                Ok(())
+            }
+            Self::Stdout(stdout) => stdout.flush(),
+        }
+    }
+
+    pub fn new(path: Option<&Path>) -> Result<Self> {
+        let Some(path) = path else {
+            return Ok(Self::Stdout(stdout().lock()));
+        };
+
+        let mut temp_path = OsString::from(path);
+        temp_path.push(".tmp");
+
+        let temp_path = PathBuf::from(temp_path);
+        let file = File::create(&temp_path)?;
+
+        if let Some(compression) = Compression::of(path) {
+            return Self::compressed(compression, file, path, Some(temp_path));
+        }
+
+        let writer = BufWriter::new(file);
+
+        Ok(Self::File {
+            path: path.to_path_buf(),
+            temp_path,
+            writer: Some(writer),
+        })
+    }
+
+    fn compressed(
+        compression: Compression,
+        file: File,
+        path: &Path,
+        temp_path: Option<PathBuf>,
+    ) -> Result<Self> {
+        let mut child = match compression.compress(file) {
+            Ok(child) => child,
+            Err(error) => {
+                if let Some(temp_path) = &temp_path {
+                    let _ = remove_file(temp_path);
+                }
+
+                return Err(error);
+            }
+        };
+
+        let stdin = child.stdin.take().expect("stdin is piped");
+
+        Ok(Self::Compressed {
+            child,
+            compression,
+            path: path.to_path_buf(),
+            temp_path,
+            writer: Some(BufWriter::new(stdin)),
+        })
+    }
+}
+
+impl Drop for Output {
+    fn drop(&mut self) {
+        if let Self::Compressed {
+            child,
+            temp_path,
+            writer: writer @ Some(_),
+            ..
+        } = self
+        {
+            drop(writer.take());
+            let _ = child.kill();
+            let _ = child.wait();
+
+            if let Some(temp_path) = temp_path {
+                let _ = remove_file(temp_path);
+            }
+        }
+
+        if let Self::File {
+            temp_path,
+            writer: writer @ Some(_),
+            ..
+        } = self
+        {
+            drop(writer.take());
+            let _ = remove_file(temp_path);
+        }
+    }
+}
+
+impl Seek for Output {
+    fn seek(&mut self, position: SeekFrom) -> Result<u64> {
+        match self {
+            Self::Append(_) => Err(Error::new(
+                ErrorKind::Unsupported,
+                "cannot seek in a file being appended to",
+            )),
+            Self::Compressed { .. } => Err(Error::new(
+                ErrorKind::Unsupported,
+                "cannot seek in a compressed file",
+            )),
+            Self::File { writer, .. } => writer.as_mut().map_or(Ok(0), |w| w.seek(position)),
+            Self::Stdout(_) => Err(Error::new(
+                ErrorKind::Unsupported,
+                "cannot seek in standard output",
+            )),
+        }
+    }
+}
+
+impl Write for Output {
+    fn flush(&mut self) -> Result<()> {
+        match self {
+            Self::Append(writer) => writer.flush(),
+            Self::Compressed { writer, .. } => writer.as_mut().map_or(Ok(()), Write::flush),
+            Self::File { writer, .. } => writer.as_mut().map_or(Ok(()), Write::flush),
+            Self::Stdout(stdout) => stdout.flush(),
+        }
+    }
+
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        match self {
+            Self::Append(writer) => writer.write(buf),
+            Self::Compressed { writer, .. } => writer.as_mut().map_or(Ok(0), |w| w.write(buf)),
+            Self::File { writer, .. } => writer.as_mut().map_or(Ok(0), |w| w.write(buf)),
+            Self::Stdout(stdout) => stdout.write(buf),
+        }
+    }
+}
@@ -0,0 +1,442 @@
+use std::io::{Result, Write};
+
+const BINARY: u8 = 8;
+const I32: u8 = 5;
+const I64: u8 = 6;
+const LIST: u8 = 9;
+const STRUCT: u8 = 12;
+
+const ENCODING_PLAIN: i32 = 0;
+const ENCODING_RLE: i32 = 3;
+
+/// The number of rows buffered before they are written out as a row group.
+const ROW_GROUP_SIZE: usize = 65536;
+
+#[derive(Clone, Copy)]
+pub enum Kind {
+    Boolean,
+    Double,
+    Int64,
+    String,
+}
+
+pub enum Value<'a> {
+    Boolean(bool),
+    Double(f64),
+    Int64(i64),
+    Null,
+    Text(&'a str),
+}
+
+pub struct Column {
+    pub kind: Kind,
+    pub name: &'static str,
+    pub optional: bool,
+}
+
+/// Writes an uncompressed Parquet file, buffering rows into row groups.
+pub struct File {
+    buffers: Vec<Buffer>,
+    columns: Vec<Column>,
+    offset: i64,
+    row_groups: Vec<RowGroup>,
+    rows: usize,
+}
+
+struct Buffer {
+    booleans: usize,
+    definitions: Vec<bool>,
+    values: Vec<u8>,
+}
+
+struct ChunkInfo {
+    offset: i64,
+    size: i64,
+    values: i64,
+}
+
+struct Compact {
+    bytes: Vec<u8>,
+    fields: Vec<i16>,
+    last: i16,
+}
+
+struct RowGroup {
+    chunks: Vec<ChunkInfo>,
+    rows: i64,
+}
+
+impl Column {
+    fn physical_type(&self) -> i32 {
+        match self.kind {
+            Kind::Boolean => 0,
+            Kind::Double => 5,
+            Kind::Int64 => 2,
+            Kind::String => 6,
+        }
+    }
+}
+
+impl Compact {
+    fn binary(&mut self, id: i16, value: &[u8]) {
+        self.field(id, BINARY);
+        self.varint(value.len() as u64);
+        self.bytes.extend(value);
+    }
+
+    fn end(&mut self) {
+        self.bytes.push(0);
+        self.last = self.fields.pop().unwrap_or_default();
+    }
+
+    fn field(&mut self, id: i16, kind: u8) {
+        let delta = id - self.last;
+
+        if (1..=15).contains(&delta) {
+            self.bytes.push((delta as u8) << 4 | kind);
+        } else {
+            self.bytes.push(kind);
+            self.varint(zigzag(id as i64));
+        }
+
+        self.last = id;
+    }
+
+    fn i32(&mut self, id: i16, value: i32) {
+        self.field(id, I32);
+        self.varint(zigzag(value as i64));
+    }
+
+    fn i64(&mut self, id: i16, value: i64) {
+        self.field(id, I64);
+        self.varint(zigzag(value));
+    }
+
+    fn list(&mut self, id: i16, kind: u8, size: usize) {
+        self.field(id, LIST);
+
+        if size < 15 {
+            self.bytes.push((size as u8) << 4 | kind);
+        } else {
+            self.bytes.push(0xf0 | kind);
+            self.varint(size as u64);
+        }
+    }
+
+    fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+            fields: Vec::new(),
+            last: 0,
+        }
+    }
+
+    /// Starts a struct, either as field `id` or, without an id, as a list element.
+    fn start(&mut self, id: Option<i16>) {
+        if let Some(id) = id {
+            self.field(id, STRUCT);
+        }
+
+        self.fields.push(self.last);
+        self.last = 0;
+    }
+
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.bytes.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+
+        self.bytes.push(value as u8);
+    }
+}
+
+impl File {
+    pub fn finish(mut self, out: &mut impl Write) -> Result<()> {
+        if self.rows > 0 {
+            self.flush(out)?;
+        }
+
+        let mut thrift = Compact::new();
+        let rows = self.row_groups.iter().map(|g| g.rows).sum();
+
+        thrift.i32(1, 1);
+        thrift.list(2, STRUCT, self.columns.len() + 1);
+        thrift.start(None);
+        thrift.binary(4, b"schema");
+        thrift.i32(5, self.columns.len() as i32);
+        thrift.end();
+
+        for column in &self.columns {
+            thrift.start(None);
+            thrift.i32(1, column.physical_type());
+            thrift.i32(3, column.optional as i32);
+            thrift.binary(4, column.name.as_bytes());
+
+            if let Kind::String = column.kind {
+                thrift.i32(6, 0);
+                thrift.start(Some(10));
+                thrift.start(Some(1));
+                thrift.end();
+                thrift.end();
+            }
+
+            thrift.end();
+        }
+
+        thrift.i64(3, rows);
+        thrift.list(4, STRUCT, self.row_groups.len());
+
+        for group in &self.row_groups {
+            thrift.start(None);
+            thrift.list(1, STRUCT, group.chunks.len());
+
+            for (chunk, column) in group.chunks.iter().zip(&self.columns) {
+                let encodings = if column.optional {
+                    vec![ENCODING_PLAIN, ENCODING_RLE]
+                } else {
+                    vec![ENCODING_PLAIN]
+                };
+
+                thrift.start(None);
+                thrift.i64(2, chunk.offset);
+                thrift.start(Some(3));
+                thrift.i32(1, column.physical_type());
+                thrift.list(2, I32, encodings.len());
+
+                for encoding in encodings {
+                    thrift.varint(zigzag(encoding as i64));
+                }
+
+                thrift.list(3, BINARY, 1);
+                thrift.varint(column.name.len() as u64);
+                thrift.bytes.extend(column.name.as_bytes());
+                thrift.i32(4, 0);
+                thrift.i64(5, chunk.values);
+                thrift.i64(6, chunk.size);
+                thrift.i64(7, chunk.size);
+                thrift.i64(9, chunk.offset);
+                thrift.end();
+                thrift.end();
+            }
+
+            thrift.i64(2, group.chunks.iter().map(|c| c.size).sum());
+            thrift.i64(3, group.rows);
+            thrift.end();
+        }
+
+        thrift.binary(
+            6,
+            concat!("fpl version ", env!("CARGO_PKG_VERSION")).as_bytes(),
+        );
+
+        thrift.bytes.push(0);
+
+        out.write_all(&thrift.bytes)?;
+        out.write_all(&(thrift.bytes.len() as u32).to_le_bytes())?;
+        out.write_all(b"PAR1")
+    }
+
+    pub fn new(out: &mut impl Write, columns: Vec<Column>) -> Result<Self> {
+        out.write_all(b"PAR1")?;
+
+        Ok(Self {
+            buffers: columns.iter().map(|_| Buffer::new()).collect(),
+            columns,
+            offset: 4,
+            row_groups: Vec::new(),
+            rows: 0,
+        })
+    }
+
+    pub fn write_row(&mut self, out: &mut impl Write, values: &[Value]) -> Result<()> {
+        for (buffer, value) in self.buffers.iter_mut().zip(values) {
+            buffer.push(value);
+        }
+
+        self.rows += 1;
+
+        if self.rows == ROW_GROUP_SIZE {
+            self.flush(out)?;
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self, out: &mut impl Write) -> Result<()> {
+        let mut chunks = Vec::new();
+
+        for (buffer, column) in self.buffers.iter_mut().zip(&self.columns) {
+            let mut data = Vec::new();
+
+            if column.optional {
+                let levels = bit_packed(&buffer.definitions);
+
+                data.extend((levels.len() as u32).to_le_bytes());
+                data.extend(levels);
+            }
+
+            data.append(&mut buffer.values);
+
+            let mut header = Compact::new();
+
+            header.i32(1, 0);
+            header.i32(2, data.len() as i32);
+            header.i32(3, data.len() as i32);
+            header.start(Some(5));
+            header.i32(1, self.rows as i32);
+            header.i32(2, ENCODING_PLAIN);
+            header.i32(3, ENCODING_RLE);
+            header.i32(4, ENCODING_RLE);
+            header.end();
+            header.bytes.push(0);
+
+            out.write_all(&header.bytes)?;
+            out.write_all(&data)?;
+
+            let size = (header.bytes.len() + data.len()) as i64;
+
+            chunks.push(ChunkInfo {
+                offset: self.offset,
+                size,
+                values: self.rows as i64,
+            });
+
+            self.offset += size;
+            *buffer = Buffer::new();
+        }
+
+        self.row_groups.push(RowGroup {
+            chunks,
+            rows: self.rows as i64,
+        });
+
+        self.rows = 0;
+
+        Ok(())
+    }
+}
+
+impl Buffer {
+    fn new() -> Self {
+        Self {
+            booleans: 0,
+            definitions: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, value: &Value) {
+        self.definitions.push(!matches!(value, Value::Null));
+
+        match value {
+            Value::Boolean(value) => {
+                if self.booleans.is_multiple_of(8) {
+                    self.values.push(0);
+                }
+
+                if *value {
+                    *self.values.last_mut().unwrap() |= 1 << (self.booleans % 8);
+                }
+
+                self.booleans += 1;
+            }
+            Value::Double(value) => self.values.extend(value.to_le_bytes()),
+            Value::Int64(value) => self.values.extend(value.to_le_bytes()),
+            Value::Null => {}
+            Value::Text(text) => {
+                self.values.extend((text.len() as u32).to_le_bytes());
+                self.values.extend(text.as_bytes());
+            }
+        }
+    }
+}
+
+/// Encodes definition levels of bit width 1 as a single bit-packed run of the RLE/bit-packing
+/// hybrid encoding.
+fn bit_packed(levels: &[bool]) -> Vec<u8> {
+    let groups = levels.len().div_ceil(8);
+    let mut header = Compact::new();
+
+    header.varint((groups as u64) << 1 | 1);
+
+    let mut bytes = header.bytes;
+
+    for group in levels.chunks(8) {
+        bytes.push(
+            group
+                .iter()
+                .enumerate()
+                .fold(0, |byte, (i, &level)| byte | (level as u8) << i),
+        );
+    }
+
+    bytes
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parquet::*;
+
+    #[test]
+    fn test_bit_packed() {
+        assert_eq!(bit_packed(&[true, false, true]), [0x03, 0x05]);
+        assert_eq!(bit_packed(&[true; 9]), [0x05, 0xff, 0x01]);
+    }
+
+    #[test]
+    fn test_file() {
+        let mut out = Vec::new();
+        let columns = vec![Column {
+            kind: Kind::Int64,
+            name: "id",
+            optional: false,
+        }];
+
+        let mut file = File::new(&mut out, columns).unwrap();
+
+        file.write_row(&mut out, &[Value::Int64(7)]).unwrap();
+        file.finish(&mut out).unwrap();
+
+        let version = concat!("fpl version ", env!("CARGO_PKG_VERSION"));
+
+        #[rustfmt::skip]
+        let mut metadata = vec![
+            // FileMetaData: version 1, and the schema of the root and its column.
+            0x15, 0x02, 0x19, 0x2c,
+            0x48, 0x06, b's', b'c', b'h', b'e', b'm', b'a', 0x15, 0x02, 0x00,
+            0x15, 0x04, 0x25, 0x00, 0x18, 0x02, b'i', b'd', 0x00,
+            // num_rows 1, and a row group of a column chunk at offset 4.
+            0x16, 0x02, 0x19, 0x1c, 0x19, 0x1c, 0x26, 0x08,
+            // ColumnMetaData: INT64, PLAIN, path `id`, UNCOMPRESSED, 1 value, 25 bytes at 4.
+            0x1c, 0x15, 0x04, 0x19, 0x15, 0x00, 0x19, 0x18, 0x02, b'i', b'd', 0x15, 0x00,
+            0x16, 0x02, 0x16, 0x32, 0x16, 0x32, 0x26, 0x08, 0x00, 0x00,
+            // The size and rows of the row group.
+            0x16, 0x32, 0x16, 0x02, 0x00,
+            // created_by
+            0x28, version.len() as u8,
+        ];
+
+        metadata.extend(version.as_bytes());
+        metadata.push(0x00);
+
+        #[rustfmt::skip]
+        let mut expected = vec![
+            b'P', b'A', b'R', b'1',
+            // PageHeader: DATA_PAGE of 8 bytes, with a DataPageHeader of 1 PLAIN value.
+            0x15, 0x00, 0x15, 0x10, 0x15, 0x10,
+            0x2c, 0x15, 0x02, 0x15, 0x00, 0x15, 0x06, 0x15, 0x06, 0x00, 0x00,
+            0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+
+        expected.extend(&metadata);
+        expected.extend((metadata.len() as u32).to_le_bytes());
+        expected.extend(b"PAR1");
+
+        assert_eq!(out, expected);
+    }
+}
@@ -0,0 +1,40 @@
+use crate::DASHES;
+
+/// Undoes the line breaking of text extracted from PDF documents.
+///
+/// A word hyphenated across lines, e.g. `perfor-\nmance`, is rejoined, a break next to a dash, e.g.
+/// `GS-\n13`, is removed, and any other break becomes a space.
+pub fn dehyphenate(text: &str) -> String {
+    let mut lines = text.split('\n');
+    let mut joined = lines.next().unwrap_or_default().to_string();
+
+    for line in lines {
+        let line = line.trim_start_matches([' ', '\t']);
+        let end = joined.trim_end_matches([' ', '\t', '\r']).len();
+
+        joined.truncate(end);
+
+        let mut before = joined.chars().rev();
+        let last = before.next();
+        let next = line.chars().next();
+
+        match (last, before.next(), next) {
+            (Some(dash), Some(letter), Some(next))
+                if is_hyphen(dash) && letter.is_alphabetic() && next.is_lowercase() =>
+            {
+                joined.truncate(end - dash.len_utf8());
+            }
+            (Some(last), _, Some(next)) if is_hyphen(last) || DASHES.contains(next) => {}
+            (Some(_), _, Some(_)) => joined.push(' '),
+            _ => {}
+        }
+
+        joined.push_str(line);
+    }
+
+    joined
+}
+
+fn is_hyphen(c: char) -> bool {
+    c == '\u{ad}' || DASHES.contains(c)
+}
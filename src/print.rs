@@ -0,0 +1,1795 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    env, fs,
+    io::{stdout, Error, ErrorKind, IsTerminal, Result, Write},
+    mem,
+    path::{Path, PathBuf},
+};
+
+use csv::Terminator;
+use fpl::{get_match_prefix_and_suffix, series_title, Grade};
+use html_escape::{encode_single_quoted_attribute, encode_text};
+use serde::Serialize;
+
+use crate::{
+    compression::Compression,
+    input::Record,
+    mention_span,
+    output::Output,
+    parquet,
+    sqlite::{Database, Value},
+    xlsx::{Cell, Workbook},
+    Args, Color, Row,
+};
+
+/// The label of the grade spans of `--annotations`.
+const ANNOTATION_LABEL: &str = "FPL_GRADE";
+
+/// The grade of the records that say there is no full performance level, as in `FPL: N/A`.
+const NO_FPL: &str = "none";
+
+const BOLD_GREEN: &str = "\x1b[1;32m";
+const DIM: &str = "\x1b[2m";
+const GREEN: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+const YELLOW: &str = "\x1b[33m";
+
+/// The width of the `--pretty` table when the terminal does not tell it in `COLUMNS`.
+const PRETTY_WIDTH: usize = 100;
+
+const REPORT_SCRIPT: &str = include_str!("report.js");
+const REPORT_TEMPLATE: &str = include_str!("report.html");
+
+enum Format {
+    Annotations,
+    Csv,
+    Html,
+    Json,
+    Jsonl,
+    Markdown,
+    Parquet,
+    Pretty,
+    Sqlite,
+    Xlsx,
+}
+
+/// A record of `--annotations` in the JSONL format of spaCy and Prodigy, with character offsets.
+#[derive(Serialize)]
+struct Annotation<'a> {
+    text: &'a str,
+    spans: Vec<AnnotationSpan>,
+    meta: AnnotationMeta<'a>,
+}
+
+#[derive(Serialize)]
+struct AnnotationMeta<'a> {
+    id: usize,
+    rule: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_file: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct AnnotationSpan {
+    start: usize,
+    end: usize,
+    label: &'static str,
+}
+
+#[derive(Serialize)]
+struct JsonRecord<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<usize>,
+    grade: Option<&'a str>,
+    pay_plan: Option<String>,
+    series: Option<&'a str>,
+    kind: Option<&'static str>,
+    valid: Option<bool>,
+    confidence: Option<f64>,
+    rule: Option<&'static str>,
+    source: Option<&'static str>,
+    /// The character offsets of the grade highlighted in HTML, in the text the grade was extracted
+    /// from, which is the normalized text with `--normalize`.
+    match_start: Option<usize>,
+    match_end: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_grade: Option<Option<&'a str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_grade: Option<Option<&'a str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ladder: Option<Vec<&'a str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mentions: Option<Vec<JsonMention<'a>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    conflict: Option<Option<Vec<String>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    series_title: Option<Option<&'static str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snippet: Option<Option<&'a str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entry_grade: Option<Option<&'a str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_file: Option<&'a str>,
+    text: &'a str,
+}
+
+#[derive(Serialize)]
+struct JsonMention<'a> {
+    offset: usize,
+    grade: &'a str,
+    pay_plan: Option<String>,
+    rule: &'static str,
+}
+
+/// A row of the `--pretty` table, which is buffered to align the columns.
+struct PrettyRow {
+    id: String,
+    grade: String,
+    valid: bool,
+    rule: &'static str,
+    /// The text before the grade, the grade and the text after it.
+    text: (String, String, String),
+}
+
+pub struct Printer<'a> {
+    /// How many rows of each ID the HTML anchors were written for.
+    anchors: HashMap<usize, usize>,
+    args: &'a Args,
+    /// The positions of the `--columns` among the fields of a CSV record.
+    columns: Vec<usize>,
+    count: usize,
+    database: Option<Database>,
+    format: Format,
+    html_footer: String,
+    html_header: String,
+    /// The HTML rows of the current page, which are written after the summary of the page.
+    html_rows: Vec<u8>,
+    out: Output,
+    page: usize,
+    parquet: Option<parquet::File>,
+    pretty: Vec<PrettyRow>,
+    summary: Summary,
+    workbook: Option<Workbook>,
+}
+
+/// The statistics of the rows of an HTML report, from its first page to the current one.
+#[derive(Default)]
+struct Summary {
+    grades: BTreeMap<(String, u32), usize>,
+    matched: usize,
+    records: usize,
+}
+
+impl<'a> Printer<'a> {
+    pub fn finish(mut self) -> Result<()> {
+        match self.format {
+            Format::Annotations | Format::Csv | Format::Jsonl | Format::Markdown => {}
+            Format::Html => self.print_html_page(false)?,
+            Format::Json => {
+                if self.count == 0 {
+                    write!(self.out, "[")?;
+                }
+
+                writeln!(self.out, "]")?;
+            }
+            Format::Parquet => {
+                if let Some(file) = self.parquet.take() {
+                    file.finish(&mut self.out)?;
+                }
+            }
+            Format::Pretty => print_pretty(&mut self.out, &self.pretty, self.args)?,
+            Format::Sqlite => {
+                if let Some(database) = self.database.take() {
+                    database.finish(&mut self.out)?;
+                }
+            }
+            Format::Xlsx => {
+                if let Some(workbook) = self.workbook.take() {
+                    workbook.finish(&mut self.out)?;
+                }
+            }
+        }
+
+        self.out.commit()
+    }
+
+    pub fn new(args: &'a Args) -> Result<Self> {
+        let format = if args.extract().annotations {
+            Format::Annotations
+        } else if args.extract().html {
+            Format::Html
+        } else if args.extract().json {
+            Format::Json
+        } else if args.extract().jsonl {
+            Format::Jsonl
+        } else if args.extract().markdown {
+            Format::Markdown
+        } else if args.extract().parquet.is_some() {
+            Format::Parquet
+        } else if args.extract().pretty {
+            Format::Pretty
+        } else if args.extract().sqlite.is_some() {
+            Format::Sqlite
+        } else if args.extract().xlsx.is_some() {
+            Format::Xlsx
+        } else {
+            Format::Csv
+        };
+
+        let mut columns = Vec::new();
+
+        if !args.extract().columns.is_empty() {
+            let keys = column_keys(args, matches!(format, Format::Json | Format::Jsonl));
+
+            for column in &args.extract().columns {
+                let Some(index) = keys.iter().position(|k| k == column) else {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "unknown column '{column}', expected one of {}",
+                            keys.join(", ")
+                        ),
+                    ));
+                };
+
+                columns.push(index);
+            }
+        }
+
+        if args.extract().page_size.is_some() && args.output.is_none() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "--page-size requires --output",
+            ));
+        }
+
+        // A database is finished by rewriting its first page, which a compressed file cannot do.
+        if let Some(path) = args.extract().sqlite.as_deref() {
+            if Compression::of(path).is_some() {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("--sqlite cannot write compressed {}", path.display()),
+                ));
+            }
+        }
+
+        let path = (args.extract().parquet.as_deref())
+            .or(args.extract().sqlite.as_deref())
+            .or(args.extract().xlsx.as_deref());
+        let path = path.or(args.output.as_deref());
+
+        let resumed = args.extract().state.as_deref().is_some_and(Path::exists);
+
+        let out = if args.watch().is_some() || resumed {
+            Output::append(path)?
+        } else {
+            Output::new(path)?
+        };
+
+        let mut html_header = String::new();
+        let mut html_footer = String::new();
+
+        if let Format::Html = format {
+            let template = match &args.extract().template {
+                Some(path) => fs::read_to_string(path)?,
+                None => REPORT_TEMPLATE.to_string(),
+            };
+
+            let css = match &args.extract().css {
+                Some(path) => format!(
+                    "<style>\n{}\n\t</style>",
+                    fs::read_to_string(path)?.trim_end()
+                ),
+                None => String::new(),
+            };
+
+            // A template without a place for the style sheet would silently drop it.
+            if !css.is_empty() && !template.contains("{{css}}") {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "--css requires a template with a {{css}} placeholder",
+                ));
+            }
+
+            let template = expand_template(&template, &[("{{css}}", &css)]);
+
+            (html_header, html_footer) = html_template(&template)?;
+        }
+
+        let mut printer = Self {
+            anchors: HashMap::new(),
+            args,
+            columns,
+            count: 0,
+            database: None,
+            format,
+            html_footer,
+            html_header,
+            html_rows: Vec::new(),
+            out,
+            page: 1,
+            parquet: None,
+            pretty: Vec::new(),
+            summary: Summary::default(),
+            workbook: None,
+        };
+
+        match printer.format {
+            Format::Markdown => print_markdown_header(&mut printer.out, args)?,
+            Format::Parquet => {
+                printer.parquet =
+                    Some(parquet::File::new(&mut printer.out, parquet_columns(args))?);
+            }
+            Format::Sqlite => {
+                printer.database = Some(Database::new(
+                    &mut printer.out,
+                    "results",
+                    &sqlite_schema(args),
+                )?);
+            }
+            Format::Xlsx => {
+                let mut workbook = Workbook::new(&mut printer.out)?;
+                let mut columns = column_names(args);
+
+                if !args.unique {
+                    columns.insert(0, "ID");
+                }
+
+                workbook.write_header(&mut printer.out, &columns)?;
+                printer.workbook = Some(workbook);
+            }
+            _ => {}
+        }
+
+        Ok(printer)
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.out.flush()
+    }
+
+    pub fn print(&mut self, mut rows: &[Row]) -> Result<()> {
+        while !rows.is_empty() {
+            let len = match self.args.extract().page_size {
+                Some(size) => {
+                    let size = size.get();
+
+                    if self.count > 0 && self.count.is_multiple_of(size) {
+                        self.next_page()?;
+                    }
+
+                    rows.len().min(size - self.count % size)
+                }
+                None => rows.len(),
+            };
+
+            let (page, rest) = rows.split_at(len);
+
+            match self.format {
+                Format::Annotations => print_annotations(&mut self.out, page, self.args)?,
+                Format::Csv => print_csv(&mut self.out, page, &self.columns, self.args)?,
+                Format::Html => {
+                    self.summary.add(page);
+                    print_html(
+                        &mut self.html_rows,
+                        page,
+                        self.count,
+                        &mut self.anchors,
+                        self.args,
+                    )?
+                }
+                Format::Json => print_json(&mut self.out, page, self.count, self.args)?,
+                Format::Jsonl => print_jsonl(&mut self.out, page, self.args)?,
+                Format::Markdown => print_markdown(&mut self.out, page, self.count, self.args)?,
+                Format::Parquet => {
+                    if let Some(file) = &mut self.parquet {
+                        print_parquet(&mut self.out, file, page, self.args)?;
+                    }
+                }
+                Format::Pretty => {
+                    let rows = page.iter().enumerate();
+
+                    self.pretty
+                        .extend(rows.map(|(i, row)| pretty_row(row, self.count + i, self.args)));
+                }
+                Format::Sqlite => {
+                    if let Some(database) = &mut self.database {
+                        print_sqlite(&mut self.out, database, page, self.args)?;
+                    }
+                }
+                Format::Xlsx => {
+                    if let Some(workbook) = &mut self.workbook {
+                        print_xlsx(&mut self.out, workbook, page, self.args)?;
+                    }
+                }
+            }
+
+            self.count += len;
+            rows = rest;
+        }
+
+        Ok(())
+    }
+
+    fn next_page(&mut self) -> Result<()> {
+        self.print_html_page(true)?;
+
+        let path = page_path(self.output_path(), self.page + 1);
+        let out = Output::new(Some(&path))?;
+
+        mem::replace(&mut self.out, out).commit()?;
+        self.page += 1;
+
+        Ok(())
+    }
+
+    fn output_path(&self) -> &'a Path {
+        self.args.output.as_deref().unwrap_or(Path::new(""))
+    }
+
+    /// Writes the current page of the HTML report, whose summary comes before its rows.
+    fn print_html_page(&mut self, has_next: bool) -> Result<()> {
+        let navigation = if self.args.extract().page_size.is_some() {
+            html_navigation(self.output_path(), self.page, has_next)
+        } else {
+            String::new()
+        };
+
+        let columns = html_columns(self.args);
+        let summary = self.summary.to_html();
+
+        let values = [
+            ("{{columns}}", columns.as_str()),
+            ("{{navigation}}", &navigation),
+            ("{{script}}", REPORT_SCRIPT.trim_end()),
+            ("{{summary}}", &summary),
+        ];
+
+        write!(self.out, "{}", expand_template(&self.html_header, &values))?;
+        self.out.write_all(&mem::take(&mut self.html_rows))?;
+        write!(self.out, "{}", expand_template(&self.html_footer, &values))
+    }
+}
+
+impl Summary {
+    fn add(&mut self, rows: &[Row]) {
+        self.records += rows.len();
+
+        for grade in rows.iter().filter_map(Row::grade) {
+            let pay_plan = pay_plan(grade).unwrap_or_default();
+
+            self.matched += 1;
+            *self.grades.entry((pay_plan, grade.value())).or_default() += 1;
+        }
+    }
+
+    fn to_html(&self) -> String {
+        let rate = if self.records == 0 {
+            0.0
+        } else {
+            self.matched as f64 * 100.0 / self.records as f64
+        };
+
+        let mut html = vec![
+            "<section class='summary'>".to_string(),
+            format!(
+                "\t<p>Records: {}, matched: {} ({rate:.1}%)</p>",
+                self.records, self.matched
+            ),
+            "\t<table>".to_string(),
+            "\t\t<thead>".to_string(),
+            "\t\t\t<tr>".to_string(),
+            "\t\t\t\t<th scope='col'>Grade</th>".to_string(),
+            "\t\t\t\t<th scope='col'>Count</th>".to_string(),
+            "\t\t\t</tr>".to_string(),
+            "\t\t</thead>".to_string(),
+            "\t\t<tbody>".to_string(),
+        ];
+
+        for ((pay_plan, value), count) in &self.grades {
+            let grade = if pay_plan.is_empty() {
+                value.to_string()
+            } else {
+                format!("{pay_plan}-{value:02}")
+            };
+
+            html.push(format!(
+                "\t\t\t<tr><td>{}</td><td>{count}</td></tr>",
+                encode_text(&grade)
+            ));
+        }
+
+        html.extend([
+            "\t\t</tbody>".to_string(),
+            "\t</table>".to_string(),
+            "</section>".to_string(),
+        ]);
+
+        html.join("\n\t\t")
+    }
+}
+
+/// Returns the distinct grades of the mentions of a row if there is more than one, e.g.
+/// `["GS-12", "GS-13"]`, or nothing if the mentions agree.
+fn conflict_grades(row: &Row) -> Vec<String> {
+    let mut grades = Vec::new();
+
+    for (_, mention) in &row.mentions {
+        let grade = mention.grade();
+        let key = (pay_plan(grade), grade.value());
+
+        if !grades.iter().any(|(k, _)| *k == key) {
+            grades.push((key, grade_label(grade)));
+        }
+    }
+
+    if grades.len() < 2 {
+        return Vec::new();
+    }
+
+    grades.into_iter().map(|(_, label)| label).collect()
+}
+
+fn expand_template(part: &str, values: &[(&str, &str)]) -> String {
+    let mut expanded = String::new();
+
+    for line in part.split_inclusive('\n') {
+        let placeholder = values.iter().find(|(p, _)| line.trim() == *p);
+
+        if let Some((_, "")) = placeholder {
+            continue;
+        }
+
+        let mut line = line.to_string();
+
+        for (placeholder, value) in values {
+            line = line.replace(placeholder, value);
+        }
+
+        expanded.push_str(&line);
+    }
+
+    expanded
+}
+
+/// Returns the names `--columns` selects the fields of a CSV record or the members of a JSON
+/// record by, such as `pay_plan`, in the order they are written without it.
+fn column_keys(args: &Args, json: bool) -> Vec<String> {
+    let mut keys = Vec::new();
+
+    if !args.unique {
+        keys.push("id".to_string());
+    }
+
+    for name in column_names(args) {
+        keys.push(name.to_lowercase().replace(' ', "_"));
+
+        if json && name == "Source" {
+            keys.extend(["match_start".to_string(), "match_end".to_string()]);
+        }
+    }
+
+    keys
+}
+
+fn column_names(args: &Args) -> Vec<&'static str> {
+    let mut columns = vec![
+        "Grade",
+        "Pay Plan",
+        "Series",
+        "Kind",
+        "Valid",
+        "Confidence",
+        "Rule",
+        "Source",
+    ];
+
+    if args.extract().ladder {
+        columns.extend(["Min Grade", "Max Grade", "Ladder"]);
+    }
+
+    if args.extract().all_mentions {
+        columns.push("Mentions");
+    }
+
+    if args.extract().conflicts {
+        columns.push("Conflict");
+    }
+
+    if args.extract().series_titles {
+        columns.push("Series Title");
+    }
+
+    if args.extract().snippet {
+        columns.push("Snippet");
+    }
+
+    if args.extract().entry_grade {
+        columns.push("Entry Grade");
+    }
+
+    if args.extract().source_file {
+        columns.push("Source File");
+    }
+
+    columns.push("Text");
+    columns
+}
+
+fn grade_fields(row: &Row, args: &Args) -> Vec<String> {
+    let mut fields = Vec::new();
+
+    match &row.mention {
+        Some(mention) => {
+            let grade = mention.grade();
+
+            fields.extend([
+                grade.grade.to_string(),
+                pay_plan(grade).unwrap_or_default(),
+                grade.series.unwrap_or_default().to_string(),
+                grade.kind.as_str().to_string(),
+                grade.is_valid().to_string(),
+                format!("{:.2}", mention.confidence()),
+                mention.rule.as_str().to_string(),
+                mention.rule.source().to_string(),
+            ]);
+        }
+        None => {
+            if row.no_fpl {
+                fields.push(NO_FPL.to_string());
+            }
+
+            fields.resize(8, String::new());
+        }
+    }
+
+    if args.extract().ladder {
+        match row.ladder().zip(row.bounds()) {
+            Some((ladder, (min, max))) => fields.extend([
+                min.grade.to_string(),
+                max.grade.to_string(),
+                ladder_grades(ladder),
+            ]),
+            None => fields.resize(fields.len() + 3, String::new()),
+        }
+    }
+
+    if args.extract().all_mentions {
+        fields.push(mention_list(row));
+    }
+
+    if args.extract().conflicts {
+        fields.push(conflict_grades(row).join(";"));
+    }
+
+    if args.extract().series_titles {
+        fields.push(row_series_title(row).unwrap_or_default().to_string());
+    }
+
+    if args.extract().snippet {
+        fields.push(row_snippet(row).unwrap_or_default().to_string());
+    }
+
+    if args.extract().entry_grade {
+        fields.push(row.entry.map(|g| g.grade).unwrap_or_default().to_string());
+    }
+
+    fields
+}
+
+fn grade_label(grade: Grade) -> String {
+    match pay_plan(grade) {
+        Some(pay_plan) => format!("{pay_plan}-{}", grade.grade),
+        None => grade.grade.to_string(),
+    }
+}
+
+fn html_columns(args: &Args) -> String {
+    let mut columns = vec![if args.unique { "Line" } else { "ID" }];
+
+    columns.extend(column_names(args));
+
+    columns
+        .iter()
+        .map(|c| format!("<th scope='col'>{c}</th>"))
+        .collect::<Vec<_>>()
+        .join("\n\t\t\t\t\t")
+}
+
+fn html_navigation(path: &Path, page: usize, has_next: bool) -> String {
+    let link = |page, label| {
+        let path = page_path(path, page);
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+        format!(
+            "<a href='{}'>{label}</a>",
+            encode_single_quoted_attribute(&name)
+        )
+    };
+
+    let mut navigation = vec!["<nav>".to_string()];
+
+    if page > 1 {
+        navigation.push(format!("\t{}", link(page - 1, "Previous")));
+    }
+
+    navigation.push(format!("\tPage {page}"));
+
+    if has_next {
+        navigation.push(format!("\t{}", link(page + 1, "Next")));
+    }
+
+    navigation.push("</nav>".to_string());
+    navigation.join("\n\t\t")
+}
+
+fn html_template(template: &str) -> Result<(String, String)> {
+    let (header, footer) = template.split_once("{{rows}}").ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            "template has no {{rows}} placeholder",
+        )
+    })?;
+
+    let header = header.trim_end_matches([' ', '\t']);
+    let footer = footer.strip_prefix('\n').unwrap_or(footer);
+
+    Ok((header.to_string(), footer.to_string()))
+}
+
+fn json_record<'a>(row: &'a Row, args: &Args) -> JsonRecord<'a> {
+    let grade = row.grade();
+    let bounds = row.bounds();
+
+    let span = row.grade_text_span().map(|(start, end)| {
+        let input = row.record.input();
+        let chars = input[0..start].chars().count();
+
+        (chars, chars + input[start..end].chars().count())
+    });
+
+    JsonRecord {
+        id: (!args.unique).then_some(row.record.id),
+        grade: grade.map(|g| g.grade).or(row.no_fpl.then_some(NO_FPL)),
+        pay_plan: grade.and_then(pay_plan),
+        series: grade.and_then(|g| g.series),
+        kind: grade.map(|g| g.kind.as_str()),
+        valid: grade.map(|g| g.is_valid()),
+        confidence: row.mention.as_ref().map(|m| round(m.confidence())),
+        rule: row.mention.as_ref().map(|m| m.rule.as_str()),
+        source: row.mention.as_ref().map(|m| m.rule.source()),
+        match_start: span.map(|(start, _)| start),
+        match_end: span.map(|(_, end)| end),
+        min_grade: args
+            .extract()
+            .ladder
+            .then(|| bounds.map(|(min, _)| min.grade)),
+        max_grade: args
+            .extract()
+            .ladder
+            .then(|| bounds.map(|(_, max)| max.grade)),
+        ladder: args.extract().ladder.then(|| {
+            row.ladder()
+                .into_iter()
+                .flatten()
+                .map(|g| g.grade)
+                .collect::<Vec<_>>()
+        }),
+        mentions: args.extract().all_mentions.then(|| {
+            row.mentions
+                .iter()
+                .map(|(offset, mention)| {
+                    let grade = mention.grade();
+
+                    JsonMention {
+                        offset: *offset,
+                        grade: grade.grade,
+                        pay_plan: pay_plan(grade),
+                        rule: mention.rule.as_str(),
+                    }
+                })
+                .collect()
+        }),
+        conflict: args.extract().conflicts.then(|| {
+            let grades = conflict_grades(row);
+            (!grades.is_empty()).then_some(grades)
+        }),
+        series_title: args.extract().series_titles.then(|| row_series_title(row)),
+        snippet: args.extract().snippet.then(|| row_snippet(row)),
+        entry_grade: args
+            .extract()
+            .entry_grade
+            .then(|| row.entry.map(|g| g.grade)),
+        source_file: args.extract().source_file.then_some(&*row.record.source),
+        text: &row.record.text,
+    }
+}
+
+fn ladder_grades(ladder: &[Grade]) -> String {
+    ladder.iter().map(|g| g.grade).collect::<Vec<_>>().join(";")
+}
+
+fn markdown_escape(text: &str) -> String {
+    encode_text(text)
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace(['\r', '\n'], "<br>")
+}
+
+/// Lists all mentions of a row as `GS-12@0;GS-13@40`, where the numbers are the byte offsets of
+/// the triggers.
+fn mention_list(row: &Row) -> String {
+    row.mentions
+        .iter()
+        .map(|(offset, mention)| format!("{}@{offset}", grade_label(mention.grade())))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+fn page_path(path: &Path, page: usize) -> PathBuf {
+    if page == 1 {
+        return path.to_path_buf();
+    }
+
+    let mut name = path.file_stem().unwrap_or_default().to_os_string();
+    name.push(format!("-{page}"));
+
+    if let Some(extension) = path.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+
+    path.with_file_name(name)
+}
+
+fn parquet_columns(args: &Args) -> Vec<parquet::Column> {
+    let column = |name, kind, optional| parquet::Column {
+        kind,
+        name,
+        optional,
+    };
+
+    let mut columns = Vec::new();
+
+    if !args.unique {
+        columns.push(column("id", parquet::Kind::Int64, false));
+    }
+
+    columns.extend([
+        column("grade", parquet::Kind::String, true),
+        column("pay_plan", parquet::Kind::String, true),
+        column("series", parquet::Kind::String, true),
+        column("kind", parquet::Kind::String, true),
+        column("valid", parquet::Kind::Boolean, true),
+        column("confidence", parquet::Kind::Double, true),
+        column("rule", parquet::Kind::String, true),
+        column("source", parquet::Kind::String, true),
+    ]);
+
+    if args.extract().ladder {
+        columns.extend([
+            column("min_grade", parquet::Kind::String, true),
+            column("max_grade", parquet::Kind::String, true),
+            column("ladder", parquet::Kind::String, true),
+        ]);
+    }
+
+    if args.extract().series_titles {
+        columns.push(column("series_title", parquet::Kind::String, true));
+    }
+
+    if args.extract().snippet {
+        columns.push(column("snippet", parquet::Kind::String, true));
+    }
+
+    if args.extract().entry_grade {
+        columns.push(column("entry_grade", parquet::Kind::String, true));
+    }
+
+    if args.extract().source_file {
+        columns.push(column("source_file", parquet::Kind::String, false));
+    }
+
+    columns.push(column("text", parquet::Kind::String, false));
+    columns
+}
+
+fn pay_plan(grade: Grade) -> Option<String> {
+    grade.pay_plan.map(str::to_uppercase)
+}
+
+fn pretty_row(row: &Row, index: usize, args: &Args) -> PrettyRow {
+    let id = if args.unique {
+        index + 1
+    } else {
+        row.record.id
+    };
+
+    let input = row.record.input();
+
+    let Some(mention) = &row.mention else {
+        return PrettyRow {
+            id: id.to_string(),
+            grade: if row.no_fpl { NO_FPL } else { "-" }.to_string(),
+            valid: true,
+            rule: "",
+            text: (input.to_string(), String::new(), String::new()),
+        };
+    };
+
+    let grade = mention.grade();
+    let (start, end) = row.grade_span().unwrap_or_default();
+
+    PrettyRow {
+        id: id.to_string(),
+        grade: grade_label(grade),
+        valid: grade.is_valid(),
+        rule: mention.rule.as_str(),
+        text: (
+            input[0..start].to_string(),
+            input[start..end].to_string(),
+            input[end..].to_string(),
+        ),
+    }
+}
+
+/// Fits the text before a grade, the grade and the text after it into `width` characters,
+/// keeping the grade in view and marking cut text with `…`. Whitespace is shown as spaces.
+fn pretty_text(text: &(String, String, String), width: usize) -> (String, String, String) {
+    let chars = |s: &str| {
+        s.chars()
+            .map(|c| if c.is_whitespace() { ' ' } else { c })
+            .collect::<Vec<_>>()
+    };
+
+    let (before, grade, after) = (chars(&text.0), chars(&text.1), chars(&text.2));
+
+    if before.len() + grade.len() + after.len() <= width {
+        return (
+            before.iter().collect(),
+            grade.iter().collect(),
+            after.iter().collect(),
+        );
+    }
+
+    let skip = before.len().saturating_sub(width / 3);
+    let mut room = width.saturating_sub(1);
+    let mut parts = [String::new(), String::new(), String::new()];
+
+    if skip > 0 {
+        parts[0].push('…');
+        room = room.saturating_sub(1);
+    }
+
+    let mut cut = false;
+
+    for (part, chars) in parts.iter_mut().zip([&before[skip..], &grade, &after]) {
+        let taken = chars.len().min(room);
+
+        part.extend(&chars[0..taken]);
+        room -= taken;
+        cut |= taken < chars.len();
+    }
+
+    if cut {
+        parts[2].push('…');
+    }
+
+    let [before, grade, after] = parts;
+
+    (before, grade, after)
+}
+
+/// Writes a JSONL line per row with the spans of its grades in the text they were extracted from,
+/// which is the normalized text with `--normalize`. All mentions are annotated with
+/// `--all-mentions`, and a row without a grade has no spans.
+fn print_annotations(out: &mut impl Write, rows: &[Row], args: &Args) -> Result<()> {
+    for row in rows {
+        let text = row.record.input();
+
+        let mut spans = if args.extract().all_mentions {
+            row.mentions
+                .iter()
+                .map(|(offset, mention)| mention_span(*offset, mention))
+                .collect()
+        } else {
+            row.grade_span().into_iter().collect::<Vec<_>>()
+        };
+
+        spans.dedup();
+
+        let chars = |offset: usize| text[..offset].chars().count();
+
+        let annotation = Annotation {
+            text,
+            spans: spans
+                .into_iter()
+                .map(|(start, end)| AnnotationSpan {
+                    start: chars(start),
+                    end: chars(end),
+                    label: ANNOTATION_LABEL,
+                })
+                .collect(),
+            meta: AnnotationMeta {
+                id: row.record.id,
+                rule: row.mention.as_ref().map(|m| m.rule.as_str()),
+                source_file: args.extract().source_file.then_some(&*row.record.source),
+            },
+        };
+
+        serde_json::to_writer(&mut *out, &annotation)?;
+        writeln!(out)?;
+    }
+
+    Ok(())
+}
+
+/// Prints `rows` as CSV records of the fields at `columns`, or of all fields without them.
+pub fn print_csv(out: &mut impl Write, rows: &[Row], columns: &[usize], args: &Args) -> Result<()> {
+    let terminator = if args.extract().crlf {
+        Terminator::CRLF
+    } else {
+        Terminator::Any(b'\n')
+    };
+
+    let mut writer = args.csv_writer().terminator(terminator).from_writer(out);
+
+    for row in rows {
+        let mut fields = Vec::new();
+
+        if !args.unique {
+            fields.push(row.record.id.to_string());
+        }
+
+        fields.extend(grade_fields(row, args));
+
+        if args.extract().source_file {
+            fields.push(row.record.source.to_string());
+        }
+
+        fields.push(row.record.text.clone());
+
+        if !columns.is_empty() {
+            fields = columns.iter().map(|&i| mem::take(&mut fields[i])).collect();
+        }
+
+        writer.write_record(&fields)?;
+    }
+
+    writer.flush()
+}
+
+fn print_html(
+    out: &mut impl Write,
+    rows: &[Row],
+    offset: usize,
+    anchors: &mut HashMap<usize, usize>,
+    args: &Args,
+) -> Result<()> {
+    let print_ids = !args.unique;
+
+    for (i, row) in rows.iter().enumerate() {
+        let record = row.record;
+        let id = if print_ids { record.id } else { offset + i + 1 };
+
+        // IDs can repeat across inputs, so a repeated one gets a suffix to keep anchors unique.
+        let anchor = match anchors.entry(id).and_modify(|n| *n += 1).or_insert(1) {
+            1 => format!("record-{id}"),
+            n => format!("record-{id}-{n}"),
+        };
+
+        writeln!(out, "\t\t\t\t<tr id='{anchor}'>")?;
+        writeln!(out, "\t\t\t\t\t<td><a href='#{anchor}'>{id}</a></td>")?;
+
+        if let Some(mention) = &row.mention {
+            let grade = mention.grade();
+            let pay_plan = pay_plan(grade).unwrap_or_default();
+            let series = grade.series.unwrap_or_default();
+
+            writeln!(out, "\t\t\t\t\t<td>{}</td>", grade.grade)?;
+            writeln!(out, "\t\t\t\t\t<td>{}</td>", encode_text(&pay_plan))?;
+            writeln!(out, "\t\t\t\t\t<td>{}</td>", encode_text(series))?;
+            writeln!(out, "\t\t\t\t\t<td>{}</td>", grade.kind.as_str())?;
+            writeln!(out, "\t\t\t\t\t<td>{}</td>", grade.is_valid())?;
+            writeln!(out, "\t\t\t\t\t<td>{:.2}</td>", mention.confidence())?;
+            writeln!(out, "\t\t\t\t\t<td>{}</td>", mention.rule.as_str())?;
+            writeln!(out, "\t\t\t\t\t<td>{}</td>", mention.rule.source())?;
+
+            if args.extract().ladder {
+                let (min, max) = row.bounds().unwrap_or((grade, grade));
+
+                writeln!(out, "\t\t\t\t\t<td>{}</td>", min.grade)?;
+                writeln!(out, "\t\t\t\t\t<td>{}</td>", max.grade)?;
+                writeln!(out, "\t\t\t\t\t<td>{}</td>", ladder_grades(&mention.ladder))?;
+            }
+
+            if args.extract().all_mentions {
+                writeln!(
+                    out,
+                    "\t\t\t\t\t<td>{}</td>",
+                    encode_text(&mention_list(row))
+                )?;
+            }
+
+            if args.extract().conflicts {
+                writeln!(
+                    out,
+                    "\t\t\t\t\t<td>{}</td>",
+                    encode_text(&conflict_grades(row).join(";"))
+                )?;
+            }
+
+            if args.extract().series_titles {
+                let title = row_series_title(row).unwrap_or_default();
+                writeln!(out, "\t\t\t\t\t<td>{}</td>", encode_text(title))?;
+            }
+
+            if args.extract().snippet {
+                let snippet = row_snippet(row).unwrap_or_default();
+                writeln!(out, "\t\t\t\t\t<td>{}</td>", encode_text(snippet))?;
+            }
+
+            if args.extract().entry_grade {
+                let entry = row.entry.map(|g| g.grade).unwrap_or_default();
+                writeln!(out, "\t\t\t\t\t<td>{}</td>", encode_text(entry))?;
+            }
+
+            if args.extract().source_file {
+                print_html_source(out, record)?;
+            }
+
+            let input = record.input();
+            let (start, end) = row.grade_text_span().unwrap_or_default();
+            let (prefix, suffix) =
+                get_match_prefix_and_suffix(input, row.offset, end).unwrap_or_default();
+            let trigger = &input[row.offset..start];
+
+            writeln!(
+                out,
+                "\t\t\t\t\t<td>{}<span class='trigger fpl-trigger'>{}</span><span class='fpl fpl-grade'>{}</span>{}</td>",
+                encode_text(prefix),
+                encode_text(trigger),
+                encode_text(grade.text),
+                encode_text(suffix)
+            )?;
+        } else {
+            let columns = if args.extract().ladder { 11 } else { 8 }
+                + args.extract().all_mentions as usize
+                + args.extract().conflicts as usize
+                + args.extract().series_titles as usize
+                + args.extract().snippet as usize
+                + args.extract().entry_grade as usize;
+
+            if row.no_fpl {
+                writeln!(out, "\t\t\t\t\t<td>{NO_FPL}</td>")?;
+            }
+
+            for _ in row.no_fpl as usize..columns {
+                writeln!(out, "\t\t\t\t\t<td></td>")?;
+            }
+
+            if args.extract().source_file {
+                print_html_source(out, record)?;
+            }
+
+            writeln!(out, "\t\t\t\t\t<td>{}</td>", encode_text(&record.text))?;
+        }
+
+        writeln!(out, "\t\t\t\t</tr>")?;
+    }
+
+    Ok(())
+}
+
+fn print_html_source(out: &mut impl Write, record: &Record) -> Result<()> {
+    writeln!(out, "\t\t\t\t\t<td>{}</td>", encode_text(&record.source))
+}
+
+fn print_json(out: &mut impl Write, rows: &[Row], offset: usize, args: &Args) -> Result<()> {
+    for (i, row) in rows.iter().enumerate() {
+        write!(out, "{}", if offset + i == 0 { "[" } else { "," })?;
+        write_json(out, row, args)?;
+    }
+
+    Ok(())
+}
+
+fn print_jsonl(out: &mut impl Write, rows: &[Row], args: &Args) -> Result<()> {
+    for row in rows {
+        write_json(out, row, args)?;
+        writeln!(out)?;
+    }
+
+    Ok(())
+}
+
+fn print_markdown(out: &mut impl Write, rows: &[Row], offset: usize, args: &Args) -> Result<()> {
+    for (i, row) in rows.iter().enumerate() {
+        let id = if args.unique {
+            offset + i + 1
+        } else {
+            row.record.id
+        };
+
+        let grade = match row.grade() {
+            Some(grade) => grade_label(grade),
+            None if row.no_fpl => NO_FPL.to_string(),
+            None => String::new(),
+        };
+
+        write!(out, "| {id} | {} |", markdown_escape(&grade))?;
+
+        if args.extract().source_file {
+            write!(out, " {} |", markdown_escape(&row.record.source))?;
+        }
+
+        writeln!(out, " {} |", markdown_escape(&row.record.text))?;
+    }
+
+    Ok(())
+}
+
+fn print_markdown_header(out: &mut impl Write, args: &Args) -> Result<()> {
+    let id = if args.unique { "Line" } else { "ID" };
+
+    if args.extract().source_file {
+        writeln!(out, "| {id} | Grade | Source File | Text |")?;
+        writeln!(out, "| ---: | --- | --- | --- |")?;
+    } else {
+        writeln!(out, "| {id} | Grade | Text |")?;
+        writeln!(out, "| ---: | --- | --- |")?;
+    }
+
+    Ok(())
+}
+
+fn print_parquet(
+    out: &mut impl Write,
+    file: &mut parquet::File,
+    rows: &[Row],
+    args: &Args,
+) -> Result<()> {
+    use parquet::Value;
+
+    fn text(value: Option<&str>) -> Value<'_> {
+        value.map_or(Value::Null, Value::Text)
+    }
+
+    for row in rows {
+        let record = json_record(row, args);
+        let ladder = row.ladder().map(ladder_grades);
+        let mut values = Vec::new();
+
+        if let Some(id) = record.id {
+            values.push(Value::Int64(id as i64));
+        }
+
+        values.extend([
+            text(record.grade),
+            text(record.pay_plan.as_deref()),
+            text(record.series),
+            text(record.kind),
+            record.valid.map_or(Value::Null, Value::Boolean),
+            record.confidence.map_or(Value::Null, Value::Double),
+            text(record.rule),
+            text(record.source),
+        ]);
+
+        if args.extract().ladder {
+            values.extend([
+                text(record.min_grade.flatten()),
+                text(record.max_grade.flatten()),
+                text(ladder.as_deref()),
+            ]);
+        }
+
+        if let Some(series_title) = record.series_title {
+            values.push(text(series_title));
+        }
+
+        if let Some(snippet) = record.snippet {
+            values.push(text(snippet));
+        }
+
+        if let Some(entry_grade) = record.entry_grade {
+            values.push(text(entry_grade));
+        }
+
+        if let Some(source_file) = record.source_file {
+            values.push(Value::Text(source_file));
+        }
+
+        values.push(Value::Text(record.text));
+        file.write_row(out, &values)?;
+    }
+
+    Ok(())
+}
+
+/// Writes the `--pretty` table, with the grades in color unless `--color` or the output rules it
+/// out.
+fn print_pretty(out: &mut impl Write, rows: &[PrettyRow], args: &Args) -> Result<()> {
+    let color = match args.extract().color {
+        Color::Always => true,
+        Color::Auto => {
+            args.output.is_none() && stdout().is_terminal() && env::var_os("NO_COLOR").is_none()
+        }
+        Color::Never => false,
+    };
+
+    let paint = |code: &'static str| if color { code } else { "" };
+    let reset = paint(RESET);
+
+    let id = if args.unique { "Line" } else { "ID" };
+    let width = |header: &str, f: fn(&PrettyRow) -> usize| {
+        rows.iter().map(f).max().unwrap_or(0).max(header.len())
+    };
+
+    let id_width = width(id, |r| r.id.len());
+    let grade_width = width("Grade", |r| r.grade.chars().count());
+    let rule_width = width("Rule", |r| r.rule.len());
+
+    let total = env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse().ok())
+        .unwrap_or(PRETTY_WIDTH);
+
+    let text_width = total
+        .saturating_sub(id_width + grade_width + rule_width + 6)
+        .max(20);
+
+    writeln!(
+        out,
+        "{}{id:>id_width$}  {:grade_width$}  {:rule_width$}  Text{reset}",
+        paint(DIM),
+        "Grade",
+        "Rule"
+    )?;
+
+    for row in rows {
+        let (before, grade, after) = pretty_text(&row.text, text_width);
+
+        let grade_color = match (row.rule.is_empty(), row.valid) {
+            (true, _) => paint(DIM),
+            (false, true) => paint(GREEN),
+            (false, false) => paint(YELLOW),
+        };
+
+        let grade = if grade.is_empty() {
+            grade
+        } else {
+            format!("{}{grade}{reset}", paint(BOLD_GREEN))
+        };
+
+        writeln!(
+            out,
+            "{:>id_width$}  {grade_color}{:grade_width$}{reset}  {:rule_width$}  {before}{grade}{after}",
+            row.id, row.grade, row.rule
+        )?;
+    }
+
+    Ok(())
+}
+
+fn print_sqlite(
+    out: &mut impl Write,
+    database: &mut Database,
+    rows: &[Row],
+    args: &Args,
+) -> Result<()> {
+    for row in rows {
+        let grade = row.grade();
+        let pay_plan = grade.and_then(pay_plan);
+
+        let mut values = vec![
+            if args.unique {
+                Value::Null
+            } else {
+                Value::Integer(row.record.id as i64)
+            },
+            match grade {
+                Some(grade) => Value::Text(grade.grade),
+                None if row.no_fpl => Value::Text(NO_FPL),
+                None => Value::Null,
+            },
+            pay_plan.as_deref().map_or(Value::Null, Value::Text),
+            row.mention
+                .as_ref()
+                .map_or(Value::Null, |m| Value::Text(m.rule.as_str())),
+            row.mention
+                .as_ref()
+                .map_or(Value::Null, |m| Value::Text(m.rule.source())),
+        ];
+
+        if args.extract().source_file {
+            values.push(Value::Text(&row.record.source));
+        }
+
+        values.push(Value::Text(&row.record.text));
+        database.insert(out, &values)?;
+    }
+
+    Ok(())
+}
+
+fn print_xlsx(
+    out: &mut impl Write,
+    workbook: &mut Workbook,
+    rows: &[Row],
+    args: &Args,
+) -> Result<()> {
+    for row in rows {
+        let fields = grade_fields(row, args);
+        let mut cells = Vec::new();
+
+        if !args.unique {
+            cells.push(Cell::Number(row.record.id));
+        }
+
+        cells.extend(fields.iter().map(|f| {
+            if f.is_empty() {
+                Cell::Empty
+            } else {
+                Cell::Text(f)
+            }
+        }));
+
+        if args.extract().source_file {
+            cells.push(Cell::Text(&row.record.source));
+        }
+
+        cells.push(match row.grade() {
+            Some(grade) => {
+                let (start, end) = row.grade_text_span().unwrap_or_default();
+                let input = row.record.input();
+                let (prefix, suffix) =
+                    get_match_prefix_and_suffix(input, start, end).unwrap_or_default();
+
+                Cell::Highlighted(prefix, grade.text, suffix)
+            }
+            None => Cell::Text(&row.record.text),
+        });
+
+        workbook.write_row(out, &cells)?;
+    }
+
+    Ok(())
+}
+
+fn round(confidence: f64) -> f64 {
+    (confidence * 100.0).round() / 100.0
+}
+
+/// Returns the title of the occupational series of the grade found in a row.
+fn row_series_title(row: &Row) -> Option<&'static str> {
+    row.grade().and_then(|g| g.series).and_then(series_title)
+}
+
+/// Returns the text of the mention found in a row, from its trigger to its last grade.
+fn row_snippet<'a>(row: &Row<'a>) -> Option<&'a str> {
+    let mention = row.mention.as_ref()?;
+
+    row.record.input().get(row.offset..row.offset + mention.end)
+}
+
+fn sqlite_schema(args: &Args) -> String {
+    let source_file = if args.extract().source_file {
+        "source_file TEXT, "
+    } else {
+        ""
+    };
+
+    format!(
+        "CREATE TABLE results (id INTEGER, grade TEXT, pay_plan TEXT, rule TEXT, source TEXT, {source_file}text TEXT)"
+    )
+}
+
+/// Writes the JSON record of a row with the members selected by `--columns`, or all of them.
+fn write_json(out: &mut impl Write, row: &Row, args: &Args) -> Result<()> {
+    let record = json_record(row, args);
+
+    if args.extract().columns.is_empty() {
+        return Ok(serde_json::to_writer(&mut *out, &record)?);
+    }
+
+    let record = serde_json::to_value(record)?;
+
+    for (i, column) in args.extract().columns.iter().enumerate() {
+        write!(out, "{}", if i == 0 { "{" } else { "," })?;
+        serde_json::to_writer(&mut *out, column)?;
+        write!(out, ":")?;
+        serde_json::to_writer(
+            &mut *out,
+            &record.get(column).unwrap_or(&serde_json::Value::Null),
+        )?;
+    }
+
+    write!(out, "}}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{process, sync::Arc};
+
+    use clap::Parser;
+    use fpl::Rules;
+
+    use crate::{extract_rows, print::*};
+
+    type Print = fn(&mut Vec<u8>, &[Row], &Args) -> Result<()>;
+
+    fn args(options: &[&str]) -> Args {
+        Args::parse_from(
+            ["fpl", "extract"]
+                .iter()
+                .chain(options)
+                .chain(&["input.csv"]),
+        )
+    }
+
+    fn print(options: &[&str], print: Print) -> String {
+        let args = args(options);
+        let records = records();
+        let rows = extract_rows(&records, &Rules::default(), &args);
+        let mut out = Vec::new();
+
+        print(&mut out, &rows, &args).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    /// Prints the records with a printer writing to a temporary file, and returns what it wrote.
+    fn printed(name: &str, options: &[&str]) -> Result<String> {
+        let path = temp(name);
+        let path = path.to_str().unwrap();
+        let args = args(&[options, &["--output", path]].concat());
+        let records = records();
+        let rows = extract_rows(&records, &Rules::default(), &args);
+        let mut printer = Printer::new(&args)?;
+
+        printer.print(&rows)?;
+        printer.finish()?;
+
+        let output = fs::read_to_string(path)?;
+
+        fs::remove_file(path)?;
+        Ok(output)
+    }
+
+    fn records() -> Vec<Record> {
+        [
+            (1, "The FPL is gs-11/12/13."),
+            (2, "No grade, FPL: N/A"),
+            (1, "Full performance level: WG-10"),
+        ]
+        .into_iter()
+        .map(|(id, text)| Record {
+            id,
+            normalized: None,
+            source: Arc::from("input.csv"),
+            text: text.to_string(),
+        })
+        .collect()
+    }
+
+    fn temp(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("fpl-{}-{name}", process::id()))
+    }
+
+    #[test]
+    fn test_print_annotations() {
+        assert_eq!(
+            print(&[], print_annotations),
+            concat!(
+                r#"{"text":"The FPL is gs-11/12/13.","spans":[{"start":11,"end":22,"#,
+                r#""label":"FPL_GRADE"}],"meta":{"id":1,"rule":"fpl_abbrev"}}"#,
+                "\n",
+                r#"{"text":"No grade, FPL: N/A","spans":[],"meta":{"id":2,"rule":null}}"#,
+                "\n",
+                r#"{"text":"Full performance level: WG-10","spans":[{"start":24,"end":29,"#,
+                r#""label":"FPL_GRADE"}],"meta":{"id":1,"rule":"full_performance"}}"#,
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_print_csv() {
+        assert_eq!(
+            print(&[], |out, rows, args| print_csv(out, rows, &[], args)),
+            concat!(
+                "1,13,GS,,grade,true,0.98,fpl_abbrev,fpl,The FPL is gs-11/12/13.\n",
+                "2,none,,,,,,,,\"No grade, FPL: N/A\"\n",
+                "1,10,WG,,grade,true,0.99,full_performance,fpl,Full performance level: WG-10\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_print_html() {
+        let print_html = |out: &mut Vec<u8>, rows: &[Row], args: &Args| {
+            print_html(out, rows, 0, &mut HashMap::new(), args)
+        };
+
+        let cells = |cells: &[&str]| {
+            cells
+                .iter()
+                .map(|c| format!("\t\t\t\t\t<td>{c}</td>\n"))
+                .collect::<String>()
+        };
+
+        let expected = [
+            "\t\t\t\t<tr id='record-1'>\n".to_string(),
+            "\t\t\t\t\t<td><a href='#record-1'>1</a></td>\n".to_string(),
+            cells(&["13", "GS", "", "grade", "true", "0.98", "fpl_abbrev", "fpl"]),
+            cells(&[concat!(
+                "The <span class='trigger fpl-trigger'>FPL is gs-11/12/</span>",
+                "<span class='fpl fpl-grade'>13</span>."
+            )]),
+            "\t\t\t\t</tr>\n".to_string(),
+            "\t\t\t\t<tr id='record-2'>\n".to_string(),
+            "\t\t\t\t\t<td><a href='#record-2'>2</a></td>\n".to_string(),
+            cells(&["none", "", "", "", "", "", "", ""]),
+            cells(&["No grade, FPL: N/A"]),
+            "\t\t\t\t</tr>\n".to_string(),
+            "\t\t\t\t<tr id='record-1-2'>\n".to_string(),
+            "\t\t\t\t\t<td><a href='#record-1-2'>1</a></td>\n".to_string(),
+            cells(&[
+                "10",
+                "WG",
+                "",
+                "grade",
+                "true",
+                "0.99",
+                "full_performance",
+                "fpl",
+            ]),
+            cells(&[concat!(
+                "<span class='trigger fpl-trigger'>Full performance level: WG-</span>",
+                "<span class='fpl fpl-grade'>10</span>"
+            )]),
+            "\t\t\t\t</tr>\n".to_string(),
+        ];
+
+        assert_eq!(print(&["--html"], print_html), expected.concat());
+    }
+
+    #[test]
+    fn test_print_json() {
+        assert_eq!(
+            print(&[], |out, rows, args| print_json(out, rows, 0, args)),
+            concat!(
+                r#"[{"id":1,"grade":"13","pay_plan":"GS","series":null,"kind":"grade","#,
+                r#""valid":true,"confidence":0.98,"rule":"fpl_abbrev","source":"fpl","#,
+                r#""match_start":20,"match_end":22,"text":"The FPL is gs-11/12/13."},"#,
+                r#"{"id":2,"grade":"none","pay_plan":null,"series":null,"kind":null,"#,
+                r#""valid":null,"confidence":null,"rule":null,"source":null,"#,
+                r#""match_start":null,"match_end":null,"text":"No grade, FPL: N/A"},"#,
+                r#"{"id":1,"grade":"10","pay_plan":"WG","series":null,"kind":"grade","#,
+                r#""valid":true,"confidence":0.99,"rule":"full_performance","source":"fpl","#,
+                r#""match_start":27,"match_end":29,"text":"Full performance level: WG-10"}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_print_jsonl() {
+        let output = print(&[], print_jsonl);
+        let lines = output.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[1],
+            concat!(
+                r#"{"id":2,"grade":"none","pay_plan":null,"series":null,"kind":null,"#,
+                r#""valid":null,"confidence":null,"rule":null,"source":null,"#,
+                r#""match_start":null,"match_end":null,"text":"No grade, FPL: N/A"}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_print_markdown() {
+        let print_markdown = |out: &mut Vec<u8>, rows: &[Row], args: &Args| {
+            print_markdown_header(out, args)?;
+            print_markdown(out, rows, 0, args)
+        };
+
+        assert_eq!(
+            print(&["--markdown"], print_markdown),
+            concat!(
+                "| ID | Grade | Text |\n",
+                "| ---: | --- | --- |\n",
+                "| 1 | GS-13 | The FPL is gs-11/12/13. |\n",
+                "| 2 | none | No grade, FPL: N/A |\n",
+                "| 1 | WG-10 | Full performance level: WG-10 |\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_print_pretty() {
+        let print_pretty = |out: &mut Vec<u8>, rows: &[Row], args: &Args| {
+            let rows = rows.iter().enumerate();
+            let rows = rows
+                .map(|(i, row)| pretty_row(row, i, args))
+                .collect::<Vec<_>>();
+
+            print_pretty(out, &rows, args)
+        };
+
+        assert_eq!(
+            print(&["--pretty", "--color", "never"], print_pretty),
+            concat!(
+                "ID  Grade  Rule              Text\n",
+                " 1  GS-13  fpl_abbrev        The FPL is gs-11/12/13.\n",
+                " 2  none                     No grade, FPL: N/A\n",
+                " 1  WG-10  full_performance  Full performance level: WG-10\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_printer_columns() {
+        assert_eq!(
+            printed("columns.csv", &["--columns", "text,grade,id"]).unwrap(),
+            concat!(
+                "The FPL is gs-11/12/13.,13,1\n",
+                "\"No grade, FPL: N/A\",none,2\n",
+                "Full performance level: WG-10,10,1\n"
+            )
+        );
+
+        assert_eq!(
+            printed("columns.json", &["--json", "--columns", "grade,match_end"]).unwrap(),
+            concat!(
+                r#"[{"grade":"13","match_end":22},{"grade":"none","match_end":null},"#,
+                r#"{"grade":"10","match_end":29}]"#,
+                "\n"
+            )
+        );
+
+        let error = printed("unknown.csv", &["--columns", "grade,score"]).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(
+            error.to_string(),
+            "unknown column 'score', expected one of id, grade, pay_plan, series, kind, valid, \
+             confidence, rule, source, text"
+        );
+
+        let error = printed("unknown.json", &["--json", "--columns", "match"]).unwrap_err();
+
+        assert!(error
+            .to_string()
+            .starts_with("unknown column 'match', expected one of id, grade,"));
+    }
+
+    #[test]
+    fn test_printer_css() {
+        let css = temp("report.css");
+        let template = temp("template.html");
+        let css_path = css.to_str().unwrap();
+        let template_path = template.to_str().unwrap();
+
+        fs::write(&css, "td {color: blue}\n").unwrap();
+        fs::write(&template, "<table>\n{{rows}}\n</table>\n").unwrap();
+
+        let options = ["--html", "--css", css_path, "--template", template_path];
+        let error = printed("css.html", &options).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(
+            error.to_string(),
+            "--css requires a template with a {{css}} placeholder"
+        );
+
+        fs::write(&template, "{{css}}\n<table>\n{{rows}}\n</table>\n").unwrap();
+
+        let output = printed("css.html", &options).unwrap();
+
+        assert!(output.starts_with("<style>\ntd {color: blue}\n\t</style>\n<table>\n"));
+
+        fs::remove_file(css).unwrap();
+        fs::remove_file(template).unwrap();
+    }
+}
@@ -0,0 +1,119 @@
+use std::{
+    fs,
+    io::{stderr, IsTerminal, Write},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::compression::Compression;
+
+/// How often the progress line is redrawn at most.
+const DRAW_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Reports the records processed, the match rate and the estimated time left on a line of
+/// standard error that is redrawn as the records are processed.
+pub struct Progress {
+    bytes: Arc<AtomicU64>,
+    drawn: Option<Instant>,
+    enabled: bool,
+    matched: usize,
+    records: usize,
+    start: Instant,
+    /// The size of all input files, unless some of them are compressed.
+    total: Option<u64>,
+    visible: bool,
+}
+
+impl Progress {
+    /// Returns a progress that is never shown.
+    pub fn hidden() -> Self {
+        Self {
+            bytes: Arc::default(),
+            drawn: None,
+            enabled: false,
+            matched: 0,
+            records: 0,
+            start: Instant::now(),
+            total: None,
+            visible: false,
+        }
+    }
+
+    /// Returns a progress of reading `inputs`, which is shown if standard error is a terminal.
+    pub fn new(inputs: &[PathBuf], hide: bool) -> Self {
+        let total = inputs
+            .iter()
+            .map(|path| match Compression::of(path) {
+                Some(_) => None,
+                None => fs::metadata(path).ok().map(|m| m.len()),
+            })
+            .sum();
+
+        Self {
+            enabled: !hide && stderr().is_terminal(),
+            total,
+            ..Self::hidden()
+        }
+    }
+
+    /// Returns the counter of the input bytes read, which the reader of the inputs updates.
+    pub fn bytes(&self) -> Arc<AtomicU64> {
+        self.bytes.clone()
+    }
+
+    /// Erases the progress line, so that it is not mixed with the output.
+    pub fn clear(&mut self) {
+        if self.visible {
+            eprint!("\r\x1b[K");
+            self.visible = false;
+        }
+    }
+
+    pub fn update(&mut self, records: usize, matched: usize) {
+        self.records += records;
+        self.matched += matched;
+
+        if !self.enabled || self.drawn.is_some_and(|d| d.elapsed() < DRAW_INTERVAL) {
+            return;
+        }
+
+        let rate = self.matched as f64 * 100.0 / self.records.max(1) as f64;
+        let mut line = format!("{} records, {rate:.1}% matched", self.records);
+
+        if let Some(eta) = self.eta() {
+            let seconds = eta.as_secs();
+            line += &format!(
+                ", ETA {}:{:02}:{:02}",
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            );
+        }
+
+        let mut err = stderr().lock();
+        let _ = write!(err, "\r\x1b[K{line}");
+        let _ = err.flush();
+
+        self.drawn = Some(Instant::now());
+        self.visible = true;
+    }
+
+    fn eta(&self) -> Option<Duration> {
+        let total = self.total?;
+        let bytes = self.bytes.load(Ordering::Relaxed).min(total);
+
+        if bytes == 0 {
+            return None;
+        }
+
+        let elapsed = self.start.elapsed().as_secs_f64();
+
+        Some(Duration::from_secs_f64(
+            elapsed * (total - bytes) as f64 / bytes as f64,
+        ))
+    }
+}
@@ -0,0 +1,80 @@
+(function () {
+	const table = document.getElementById('report');
+	const headers = Array.from(table.tHead.rows[0].cells);
+	const body = table.tBodies[0];
+	const rows = Array.from(body.rows);
+	const search = document.getElementById('search');
+	const grade = document.getElementById('grade');
+	const exportButton = document.getElementById('export');
+	const gradeColumn = headers.findIndex(th => th.textContent === 'Grade');
+	const textColumn = headers.findIndex(th => th.textContent === 'Text');
+	const collator = new Intl.Collator(undefined, {numeric: true, sensitivity: 'base'});
+
+	// The lowercased ID and text searched, computed once since reports can have many rows.
+	const searched = new Map(rows.map(row => [
+		row,
+		(row.cells[0].textContent + '\n' + row.cells[textColumn].textContent).toLowerCase()
+	]));
+
+	let searchTimer;
+
+	const grades = new Set(rows.map(row => row.cells[gradeColumn].textContent));
+
+	Array.from(grades)
+		.sort(collator.compare)
+		.forEach(value => grade.add(value === '' ? new Option('(none)', '-') : new Option(value, value)));
+
+	function csvField(text) {
+		return /[",\r\n]/.test(text) ? '"' + text.replace(/"/g, '""') + '"' : text;
+	}
+
+	function exportRows() {
+		const visible = Array.from(body.rows).filter(row => !row.hidden);
+
+		const lines = [headers, ...visible.map(row => Array.from(row.cells))]
+			.map(cells => cells.map(cell => csvField(cell.textContent)).join(','));
+
+		const link = document.createElement('a');
+
+		link.href = URL.createObjectURL(new Blob([lines.join('\r\n') + '\r\n'], {type: 'text/csv'}));
+		link.download = 'report.csv';
+		link.click();
+
+		setTimeout(() => URL.revokeObjectURL(link.href));
+	}
+
+	function filter() {
+		const query = search.value.toLowerCase();
+		const wanted = grade.value;
+
+		rows.forEach(row => {
+			const matches = (wanted === '' || row.cells[gradeColumn].textContent === (wanted === '-' ? '' : wanted))
+				&& (query === '' || searched.get(row).includes(query));
+
+			row.hidden = !matches;
+		});
+	}
+
+	headers.forEach((th, column) => {
+		th.addEventListener('click', () => {
+			const ascending = th.getAttribute('aria-sort') !== 'ascending';
+
+			headers.forEach(other => other.removeAttribute('aria-sort'));
+			th.setAttribute('aria-sort', ascending ? 'ascending' : 'descending');
+
+			rows.sort((a, b) => {
+				const order = collator.compare(a.cells[column].textContent, b.cells[column].textContent);
+				return ascending ? order : -order;
+			});
+
+			body.append(...rows);
+		});
+	});
+
+	search.addEventListener('input', () => {
+		clearTimeout(searchTimer);
+		searchTimer = setTimeout(filter, 150);
+	});
+	grade.addEventListener('change', filter);
+	exportButton.addEventListener('click', exportRows);
+})();
@@ -0,0 +1,526 @@
+//! The review screen drives the terminal with `stty` and ANSI escape sequences instead of ratatui
+//! and crossterm. It is a single page redrawn on each key, which needs neither widgets nor a
+//! layout engine, and like the Parquet, SQLite and XLSX writers it keeps the dependencies of the
+//! package down. The price is that it runs in Unix terminals only.
+
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Error, ErrorKind, Read, Result, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::Arc,
+};
+
+use csv::{ReaderBuilder, WriterBuilder};
+use fpl::Rules;
+
+use crate::{
+    extract_rows,
+    input::{Reader, Record},
+    preprocess_records, Args, Row,
+};
+
+const BOLD: &str = "\x1b[1m";
+const CLEAR: &str = "\x1b[2J\x1b[H";
+const DIM: &str = "\x1b[2m";
+const HIGHLIGHT: &str = "\x1b[1;30;42m";
+const RESET: &str = "\x1b[0m";
+
+/// The screen lines taken by everything but the text of the record.
+const CHROME_LINES: usize = 8;
+
+const HELP: &str = "[a]ccept  [c]orrect  [r]eject  [n]ext  [p]revious  [q]uit";
+
+/// A reviewer's decision on the extracted grade of a record.
+#[derive(Clone)]
+struct Decision {
+    decision: &'static str,
+    grade: String,
+}
+
+enum Key {
+    Char(char),
+    Enter,
+    Left,
+    Right,
+    Other,
+}
+
+/// The terminal in raw mode on the alternate screen, which is restored when dropped.
+struct Terminal {
+    saved: String,
+    tty: File,
+}
+
+impl Terminal {
+    fn new() -> Result<Self> {
+        if cfg!(not(unix)) {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "review needs a Unix terminal",
+            ));
+        }
+
+        let saved = stty(&["-g"])?;
+
+        stty(&["-icanon", "-echo", "min", "1"])?;
+
+        let mut tty = OpenOptions::new().read(true).write(true).open("/dev/tty")?;
+        write!(tty, "\x1b[?1049h\x1b[?25l")?;
+
+        Ok(Self {
+            saved: saved.trim().to_string(),
+            tty,
+        })
+    }
+
+    /// Reads a line with the terminal back in its usual mode.
+    fn prompt(&mut self, prompt: &str) -> Result<String> {
+        write!(self.tty, "{prompt}\x1b[?25h")?;
+        stty(&[&self.saved])?;
+
+        let mut line = String::new();
+        let result = BufReader::new(&self.tty).read_line(&mut line);
+
+        stty(&["-icanon", "-echo", "min", "1"])?;
+        write!(self.tty, "\x1b[?25l")?;
+        result?;
+
+        Ok(line.trim().to_string())
+    }
+
+    fn read_key(&mut self) -> Result<Key> {
+        let mut byte = [0];
+
+        self.tty.read_exact(&mut byte)?;
+
+        Ok(match byte[0] {
+            b'\n' | b'\r' => Key::Enter,
+            0x1b => {
+                let mut sequence = [0; 2];
+
+                self.tty.read_exact(&mut sequence)?;
+
+                match sequence {
+                    [b'[', b'C'] => Key::Right,
+                    [b'[', b'D'] => Key::Left,
+                    _ => Key::Other,
+                }
+            }
+            byte if byte.is_ascii() => Key::Char(byte.to_ascii_lowercase() as char),
+            _ => Key::Other,
+        })
+    }
+
+    /// Returns the number of lines and columns of the terminal.
+    fn size(&self) -> (usize, usize) {
+        let size = stty(&["size"]).unwrap_or_default();
+        let mut numbers = size.split_whitespace().filter_map(|n| n.parse().ok());
+
+        match (numbers.next(), numbers.next()) {
+            (Some(lines), Some(columns)) if lines > 0 && columns > 0 => (lines, columns),
+            _ => (24, 80),
+        }
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = write!(self.tty, "\x1b[?25h\x1b[?1049l");
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+/// Steps through the records of the inputs, showing each text with the grade highlighted, and
+/// appends the reviewer's decisions to `decisions`, by default `<input>.review.csv` next to the
+/// first input. Records decided in an earlier session are skipped to; the last decision on a
+/// record counts.
+pub fn review(
+    reader: Reader,
+    inputs: &[PathBuf],
+    decisions: Option<&Path>,
+    rules: &Rules,
+    args: &Args,
+) -> Result<bool> {
+    let mut records = reader.collect::<Result<Vec<Record>>>()?;
+
+    preprocess_records(&mut records, args);
+
+    let rows = extract_rows(&records, rules, args);
+
+    if rows.is_empty() {
+        return Err(Error::new(ErrorKind::InvalidInput, "no records to review"));
+    }
+
+    // There are records, so there is an input.
+    let path = match decisions {
+        Some(path) => path.to_path_buf(),
+        None => default_path(&inputs[0]),
+    };
+
+    let mut decided = load(&path)?;
+    let exists = path.exists();
+    let file = OpenOptions::new().create(true).append(true).open(&path)?;
+    let mut writer = WriterBuilder::new().has_headers(false).from_writer(file);
+
+    if !exists {
+        writer.write_record(["source", "id", "extracted", "decision", "grade"])?;
+        writer.flush()?;
+    }
+
+    let mut index = rows
+        .iter()
+        .position(|r| !decided.contains_key(&key(r.record)))
+        .unwrap_or(rows.len() - 1);
+
+    let mut terminal = Terminal::new()?;
+    let mut status = String::new();
+
+    loop {
+        let row = &rows[index];
+        let decision = decided.get(&key(row.record)).cloned();
+
+        draw(&mut terminal, &rows, index, decision.as_ref(), &status)?;
+        status.clear();
+
+        let extracted = row.grade().map(|g| g.grade.to_string()).unwrap_or_default();
+
+        let decision = match terminal.read_key()? {
+            Key::Char('a') | Key::Enter => Some(Decision {
+                decision: "accepted",
+                grade: extracted.clone(),
+            }),
+            Key::Char('c') => {
+                let grade = terminal.prompt("Grade: ")?;
+
+                (!grade.is_empty()).then_some(Decision {
+                    decision: "corrected",
+                    grade,
+                })
+            }
+            Key::Char('r') => Some(Decision {
+                decision: "rejected",
+                grade: String::new(),
+            }),
+            Key::Char('n' | ' ') | Key::Right => {
+                index = next(index, rows.len(), &mut status);
+                None
+            }
+            Key::Char('p') | Key::Left => {
+                index = index.saturating_sub(1);
+                None
+            }
+            Key::Char('q') => break,
+            _ => None,
+        };
+
+        if let Some(decision) = decision {
+            writer.write_record([
+                &*row.record.source,
+                &row.record.id.to_string(),
+                &extracted,
+                decision.decision,
+                &decision.grade,
+            ])?;
+            writer.flush()?;
+
+            decided.insert(key(row.record), decision);
+            index = next(index, rows.len(), &mut status);
+        }
+    }
+
+    drop(terminal);
+
+    println!(
+        "{} of {} records reviewed, decisions in {}",
+        rows.iter()
+            .filter(|r| decided.contains_key(&key(r.record)))
+            .count(),
+        rows.len(),
+        path.display()
+    );
+
+    Ok(true)
+}
+
+fn default_path(input: &Path) -> PathBuf {
+    let stem = input.file_stem().unwrap_or_default().to_string_lossy();
+    input.with_file_name(format!("{stem}.review.csv"))
+}
+
+fn draw(
+    terminal: &mut Terminal,
+    rows: &[Row],
+    index: usize,
+    decision: Option<&Decision>,
+    status: &str,
+) -> Result<()> {
+    let (lines, columns) = terminal.size();
+    let row = &rows[index];
+    let text = row.record.input();
+    let span = row.grade_span();
+    let mut screen = String::from(CLEAR);
+
+    let grade = row.grade().map_or("none".to_string(), |g| g.to_string());
+
+    let rule = row.mention.as_ref().map_or("", |m| m.rule.as_str());
+
+    let decision = match decision {
+        Some(d) if d.decision == "corrected" => format!("corrected to {}", d.grade),
+        Some(d) => d.decision.to_string(),
+        None => "undecided".to_string(),
+    };
+
+    screen.push_str(&format!(
+        "{BOLD}Record {} of {}{RESET}  {DIM}{} #{}{RESET}\n\n",
+        index + 1,
+        rows.len(),
+        row.record.source,
+        row.record.id
+    ));
+
+    screen.push_str(&format!(
+        "Grade: {BOLD}{grade}{RESET}  {DIM}{rule}{RESET}  Decision: {decision}\n\n"
+    ));
+
+    let wrapped = wrap(text, columns.max(20));
+    let room = lines.saturating_sub(CHROME_LINES).max(1);
+    let focus = span.map_or(0, |(start, _)| {
+        wrapped
+            .iter()
+            .rposition(|line| line.first().is_some_and(|&(offset, _)| offset <= start))
+            .unwrap_or(0)
+    });
+
+    let first = focus
+        .saturating_sub(room / 2)
+        .min(wrapped.len().saturating_sub(room));
+
+    for line in wrapped.iter().skip(first).take(room) {
+        for (n, &(offset, word)) in line.iter().enumerate() {
+            if n > 0 {
+                screen.push(' ');
+            }
+
+            match span {
+                Some((start, end)) if offset < end && offset + word.len() > start => {
+                    let from = start.saturating_sub(offset);
+                    let to = (end - offset).min(word.len());
+
+                    screen.push_str(&word[..from]);
+                    screen.push_str(HIGHLIGHT);
+                    screen.push_str(&word[from..to]);
+                    screen.push_str(RESET);
+                    screen.push_str(&word[to..]);
+                }
+                _ => screen.push_str(word),
+            }
+        }
+
+        screen.push('\n');
+    }
+
+    screen.push_str(&format!("\n{DIM}{HELP}{RESET}\n{status}\n"));
+
+    terminal.tty.write_all(screen.as_bytes())?;
+    terminal.tty.flush()
+}
+
+fn key(record: &Record) -> (Arc<str>, usize) {
+    (record.source.clone(), record.id)
+}
+
+/// Reads the decisions of earlier sessions from `path`, if it exists.
+fn load(path: &Path) -> Result<HashMap<(Arc<str>, usize), Decision>> {
+    let mut decided = HashMap::new();
+
+    if !path.exists() {
+        return Ok(decided);
+    }
+
+    let mut reader = ReaderBuilder::new().from_path(path)?;
+
+    for record in reader.records() {
+        let record = record?;
+
+        let (Some(source), Some(Ok(id)), Some(decision), Some(grade)) = (
+            record.get(0),
+            record.get(1).map(str::parse),
+            record.get(3),
+            record.get(4),
+        ) else {
+            continue;
+        };
+
+        let decision = match decision {
+            "accepted" => "accepted",
+            "corrected" => "corrected",
+            "rejected" => "rejected",
+            _ => continue,
+        };
+
+        decided.insert(
+            (Arc::from(source), id),
+            Decision {
+                decision,
+                grade: grade.to_string(),
+            },
+        );
+    }
+
+    Ok(decided)
+}
+
+fn next(index: usize, count: usize, status: &mut String) -> usize {
+    if index + 1 < count {
+        index + 1
+    } else {
+        status.push_str("This is the last record.");
+        index
+    }
+}
+
+/// Runs `stty` on the terminal and returns what it prints.
+fn stty(args: &[&str]) -> Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(File::open("/dev/tty")?)
+        .stderr(Stdio::inherit())
+        .output()?;
+
+    if !output.status.success() {
+        return Err(Error::other(format!("stty failed with {}", output.status)));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Splits `text` into lines of words no longer than `width` characters, keeping the byte offset
+/// of each word.
+fn wrap(text: &str, width: usize) -> Vec<Vec<(usize, &str)>> {
+    let mut lines = Vec::new();
+    let mut line: Vec<(usize, &str)> = Vec::new();
+    let mut length = 0;
+    let mut words = Vec::new();
+    let mut start = None;
+
+    for (i, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        match start {
+            None if !c.is_whitespace() => start = Some(i),
+            Some(offset) if c.is_whitespace() => {
+                words.push((offset, &text[offset..i]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    for (offset, word) in words {
+        let count = word.chars().count();
+
+        if !line.is_empty() && length + 1 + count > width {
+            lines.push(std::mem::take(&mut line));
+            length = 0;
+        }
+
+        length += count + if line.is_empty() { 0 } else { 1 };
+        line.push((offset, word));
+    }
+
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use crate::review::*;
+
+    #[test]
+    fn test_default_path() {
+        assert_eq!(
+            default_path(Path::new("data/jobs.csv")),
+            Path::new("data/jobs.review.csv")
+        );
+        assert_eq!(
+            default_path(Path::new("jobs.csv.gz")),
+            Path::new("jobs.csv.review.csv")
+        );
+    }
+
+    #[test]
+    fn test_load() {
+        let path = env::temp_dir().join(format!("fpl-{}-decisions.csv", process::id()));
+
+        assert!(load(&path).unwrap().is_empty());
+
+        fs::write(
+            &path,
+            concat!(
+                "source,id,extracted,decision,grade\n",
+                "a.csv,1,12,accepted,12\n",
+                "a.csv,2,12,corrected,13\n",
+                "a.csv,1,12,rejected,\n",
+                "b.csv,1,,skipped,\n",
+                "b.csv,x,12,accepted,12\n",
+            ),
+        )
+        .unwrap();
+
+        let decided = load(&path).unwrap();
+        let decision = |source: &str, id| {
+            decided
+                .get(&(Arc::from(source), id))
+                .map(|d| (d.decision, d.grade.as_str()))
+        };
+
+        // The last decision on a record counts, and unknown decisions and IDs are skipped.
+        assert_eq!(decided.len(), 2);
+        assert_eq!(decision("a.csv", 1), Some(("rejected", "")));
+        assert_eq!(decision("a.csv", 2), Some(("corrected", "13")));
+        assert_eq!(decision("b.csv", 1), None);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_next() {
+        let mut status = String::new();
+
+        assert_eq!(next(0, 3, &mut status), 1);
+        assert_eq!(status, "");
+        assert_eq!(next(2, 3, &mut status), 2);
+        assert_eq!(status, "This is the last record.");
+    }
+
+    #[test]
+    fn test_wrap() {
+        let text = "Full performance  level:\nGS-13, série 0343";
+        let lines = wrap(text, 16);
+
+        assert_eq!(
+            lines,
+            [
+                vec![(0, "Full"), (5, "performance")],
+                vec![(18, "level:"), (25, "GS-13,")],
+                vec![(32, "série"), (39, "0343")],
+            ]
+        );
+
+        for &(offset, word) in lines.iter().flatten() {
+            assert_eq!(&text[offset..offset + word.len()], word);
+        }
+
+        // A word longer than the width gets a line of its own.
+        assert_eq!(
+            wrap("a GS-0343-13 b", 4),
+            [[(0, "a")], [(2, "GS-0343-13")], [(13, "b")]]
+        );
+        assert!(wrap(" \n", 10).is_empty());
+    }
+}
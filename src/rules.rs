@@ -0,0 +1,260 @@
+use std::{
+    io::{Error, ErrorKind, Result},
+    sync::OnceLock,
+};
+
+use nom::{
+    branch::alt,
+    bytes::complete::{escaped_transform, is_not, tag, take_while1},
+    character::complete::{char, multispace1, not_line_ending},
+    combinator::{map, opt, value},
+    multi::{many0, separated_list0},
+    sequence::{delimited, pair, preceded, tuple},
+    IResult,
+};
+
+const DEFAULT_RULES: &str = include_str!("../rules.toml");
+
+/// Connector phrases, additional trigger spellings and accepted pay plans used to find mentions.
+///
+/// The default rules are read from `rules.toml`, which is compiled into the library.
+#[derive(Clone, Debug)]
+pub struct Rules {
+    pub(crate) fpl_connectors: Vec<Vec<String>>,
+    pub(crate) fuzzy: bool,
+    pub(crate) pay_plans: Vec<String>,
+    pub(crate) strict_pay_plans: bool,
+    pub(crate) target_connectors: Vec<Vec<String>>,
+    pub(crate) variants: Vec<String>,
+}
+
+#[derive(Clone)]
+enum Value {
+    Array(Vec<String>),
+    Boolean(bool),
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self::builtin().clone()
+    }
+}
+
+impl Rules {
+    pub(crate) fn accepts_pay_plan(&self, pay_plan: &str) -> bool {
+        !self.strict_pay_plans
+            || self
+                .pay_plans
+                .iter()
+                .any(|p| p.eq_ignore_ascii_case(pay_plan))
+    }
+
+    /// Adds the misspellings of "performance" listed in `text`, one per line. Empty lines and
+    /// lines starting with `#` are ignored.
+    pub fn add_variants(&mut self, text: &str) {
+        self.variants.extend(
+            text.lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                .map(String::from),
+        );
+    }
+
+    pub(crate) fn builtin() -> &'static Self {
+        static RULES: OnceLock<Rules> = OnceLock::new();
+
+        RULES.get_or_init(|| {
+            let mut rules = Self {
+                fpl_connectors: Vec::new(),
+                fuzzy: false,
+                pay_plans: Vec::new(),
+                strict_pay_plans: false,
+                target_connectors: Vec::new(),
+                variants: Vec::new(),
+            };
+
+            rules.load(DEFAULT_RULES).expect("invalid default rules");
+            rules
+        })
+    }
+
+    /// Parses rules in the format of `rules.toml`. Rules missing from `text` keep their defaults.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut rules = Self::default();
+
+        rules.load(text)?;
+        Ok(rules)
+    }
+
+    /// Sets whether any word within two edits of "performance" or "promotion" is taken for a
+    /// misspelling of it after "full" or "poll".
+    pub fn set_fuzzy(&mut self, fuzzy: bool) {
+        self.fuzzy = fuzzy;
+    }
+
+    /// Replaces the pay plans accepted in strict mode.
+    pub fn set_pay_plans<S: AsRef<str>>(&mut self, pay_plans: &[S]) {
+        self.pay_plans = pay_plans.iter().map(|p| p.as_ref().to_string()).collect();
+    }
+
+    /// Sets whether grades with pay plans other than the accepted ones are rejected.
+    pub fn set_strict_pay_plans(&mut self, strict: bool) {
+        self.strict_pay_plans = strict;
+    }
+
+    fn load(&mut self, text: &str) -> Result<()> {
+        let mut s = space(text);
+        let mut table = "";
+
+        while !s.is_empty() {
+            let start = s;
+
+            if let Ok((rest, name)) = header(s) {
+                table = name;
+                s = space(rest);
+                continue;
+            }
+
+            let (rest, (key, value)) =
+                entry(s).map_err(|_| error(text, start, "invalid syntax"))?;
+
+            let name = if table.is_empty() {
+                key.to_string()
+            } else {
+                format!("{table}.{key}")
+            };
+
+            let message = match (name.as_str(), value) {
+                ("fpl.connectors", Value::Array(values)) => {
+                    self.fpl_connectors = phrases(values);
+                    None
+                }
+                ("fpl.fuzzy", Value::Boolean(fuzzy)) => {
+                    self.fuzzy = fuzzy;
+                    None
+                }
+                ("fpl.variants", Value::Array(values)) => {
+                    self.variants = values;
+                    None
+                }
+                ("grade.pay_plans", Value::Array(values)) => {
+                    self.pay_plans = values;
+                    None
+                }
+                ("grade.strict", Value::Boolean(strict)) => {
+                    self.strict_pay_plans = strict;
+                    None
+                }
+                ("target.connectors", Value::Array(values)) => {
+                    self.target_connectors = phrases(values);
+                    None
+                }
+                (
+                    "fpl.connectors" | "fpl.variants" | "grade.pay_plans" | "target.connectors",
+                    _,
+                ) => Some(format!("'{name}' must be an array of strings")),
+                ("fpl.fuzzy" | "grade.strict", _) => Some(format!("'{name}' must be a boolean")),
+                _ => Some(format!("unknown rule '{name}'")),
+            };
+
+            if let Some(message) = message {
+                return Err(error(text, start, &message));
+            }
+
+            s = space(rest);
+        }
+
+        Ok(())
+    }
+}
+
+fn array(s: &str) -> IResult<&str, Vec<String>> {
+    let (s, _) = pair(char('['), space0)(s)?;
+    let (s, values) = separated_list0(delimited(space0, char(','), space0), string)(s)?;
+    let (s, _) = tuple((space0, opt(char(',')), space0, char(']')))(s)?;
+
+    Ok((s, values))
+}
+
+fn comment_or_space(s: &str) -> IResult<&str, ()> {
+    alt((
+        value((), multispace1),
+        value((), preceded(char('#'), not_line_ending)),
+    ))(s)
+}
+
+fn entry(s: &str) -> IResult<&str, (&str, Value)> {
+    let (s, key) = name(s)?;
+    let (s, _) = delimited(space0, char('='), space0)(s)?;
+
+    let (s, value) = alt((
+        map(array, Value::Array),
+        value(Value::Boolean(false), tag("false")),
+        value(Value::Boolean(true), tag("true")),
+    ))(s)?;
+
+    Ok((s, (key, value)))
+}
+
+fn error(text: &str, at: &str, message: &str) -> Error {
+    let line = text[0..text.len() - at.len()].lines().count() + 1;
+
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("rules, line {line}: {message}"),
+    )
+}
+
+fn header(s: &str) -> IResult<&str, &str> {
+    delimited(char('['), name, char(']'))(s)
+}
+
+fn name(s: &str) -> IResult<&str, &str> {
+    take_while1(|c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-')(s)
+}
+
+fn phrases(values: Vec<String>) -> Vec<Vec<String>> {
+    values
+        .iter()
+        .map(|v| v.split_whitespace().map(String::from).collect::<Vec<_>>())
+        .filter(|words| !words.is_empty())
+        .collect()
+}
+
+fn space(s: &str) -> &str {
+    space0(s).map_or(s, |(s, _)| s)
+}
+
+fn space0(s: &str) -> IResult<&str, ()> {
+    value((), many0(comment_or_space))(s)
+}
+
+fn string(s: &str) -> IResult<&str, String> {
+    let basic = delimited(
+        char('"'),
+        map(
+            opt(escaped_transform(
+                is_not("\\\""),
+                '\\',
+                alt((
+                    value("\\", tag("\\")),
+                    value("\"", tag("\"")),
+                    value("\n", tag("n")),
+                    value("\t", tag("t")),
+                )),
+            )),
+            Option::unwrap_or_default,
+        ),
+        char('"'),
+    );
+
+    let literal = delimited(
+        char('\''),
+        map(opt(is_not("'")), |s: Option<&str>| {
+            s.unwrap_or_default().to_string()
+        }),
+        char('\''),
+    );
+
+    alt((basic, literal))(s)
+}
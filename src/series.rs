@@ -0,0 +1,432 @@
+/// The titles of the white-collar occupational series of the General Schedule, as published in the
+/// OPM Handbook of Occupational Groups and Families, sorted by series number.
+const SERIES: [(u16, &str); 412] = [
+    (6, "Correctional Institution Administration"),
+    (7, "Correctional Officer"),
+    (17, "Explosives Safety"),
+    (18, "Safety and Occupational Health Management"),
+    (19, "Safety Technician"),
+    (20, "Community Planning"),
+    (21, "Community Planning Technician"),
+    (23, "Outdoor Recreation Planning"),
+    (25, "Park Ranger"),
+    (28, "Environmental Protection Specialist"),
+    (29, "Environmental Protection Assistant"),
+    (30, "Sports Specialist"),
+    (50, "Funeral Directing"),
+    (60, "Chaplain"),
+    (62, "Clothing Design"),
+    (72, "Fingerprint Identification"),
+    (80, "Security Administration"),
+    (81, "Fire Protection and Prevention"),
+    (83, "Police"),
+    (84, "Nuclear Materials Courier"),
+    (85, "Security Guard"),
+    (86, "Security Clerical and Assistance"),
+    (90, "Guide"),
+    (95, "Foreign Law Specialist"),
+    (99, "General Student Trainee"),
+    (101, "Social Science"),
+    (102, "Social Science Aid and Technician"),
+    (105, "Social Insurance Administration"),
+    (106, "Unemployment Insurance"),
+    (107, "Health Insurance Administration"),
+    (110, "Economist"),
+    (119, "Economics Assistant"),
+    (130, "Foreign Affairs"),
+    (131, "International Relations"),
+    (132, "Intelligence"),
+    (134, "Intelligence Aid and Clerk"),
+    (135, "Foreign Agricultural Affairs"),
+    (136, "International Cooperation"),
+    (140, "Workforce Research and Analysis"),
+    (150, "Geography"),
+    (160, "Civil Rights Analysis"),
+    (170, "History"),
+    (180, "Psychology"),
+    (181, "Psychology Aid and Technician"),
+    (184, "Sociology"),
+    (185, "Social Work"),
+    (186, "Social Services Aid and Assistant"),
+    (187, "Social Services"),
+    (188, "Recreation Specialist"),
+    (189, "Recreation Aid and Assistant"),
+    (190, "General Anthropology"),
+    (193, "Archeology"),
+    (199, "Social Science Student Trainee"),
+    (201, "Human Resources Management"),
+    (203, "Human Resources Assistance"),
+    (260, "Equal Employment Opportunity"),
+    (299, "Human Resources Management Student Trainee"),
+    (301, "Miscellaneous Administration and Program"),
+    (302, "Messenger"),
+    (303, "Miscellaneous Clerk and Assistant"),
+    (304, "Information Receptionist"),
+    (305, "Mail and File"),
+    (306, "Government Information Specialist"),
+    (308, "Records and Information Management"),
+    (309, "Correspondence Clerk"),
+    (312, "Clerk-Stenographer and Reporter"),
+    (313, "Work Unit Supervising"),
+    (318, "Secretary"),
+    (319, "Closed Microphone Reporting"),
+    (322, "Clerk-Typist"),
+    (326, "Office Automation Clerical and Assistance"),
+    (332, "Computer Operation"),
+    (335, "Computer Clerk and Assistant"),
+    (340, "Program Management"),
+    (341, "Administrative Officer"),
+    (342, "Support Services Administration"),
+    (343, "Management and Program Analysis"),
+    (344, "Management and Program Clerical and Assistance"),
+    (346, "Logistics Management"),
+    (350, "Equipment Operator"),
+    (356, "Data Transcriber"),
+    (357, "Coding"),
+    (360, "Equal Opportunity Compliance"),
+    (361, "Equal Opportunity Assistance"),
+    (382, "Telephone Operating"),
+    (390, "Telecommunications Processing"),
+    (391, "Telecommunications"),
+    (392, "General Telecommunications"),
+    (394, "Communications Clerical"),
+    (399, "Administration and Office Support Student Trainee"),
+    (
+        401,
+        "General Natural Resources Management and Biological Sciences",
+    ),
+    (403, "Microbiology"),
+    (404, "Biological Science Technician"),
+    (405, "Pharmacology"),
+    (408, "Ecology"),
+    (410, "Zoology"),
+    (413, "Physiology"),
+    (414, "Entomology"),
+    (415, "Toxicology"),
+    (421, "Plant Protection Technician"),
+    (430, "Botany"),
+    (434, "Plant Pathology"),
+    (435, "Plant Physiology"),
+    (437, "Horticulture"),
+    (440, "Genetics"),
+    (454, "Rangeland Management"),
+    (455, "Range Technician"),
+    (457, "Soil Conservation"),
+    (458, "Soil Conservation Technician"),
+    (459, "Irrigation System Operation"),
+    (460, "Forestry"),
+    (462, "Forestry Technician"),
+    (470, "Soil Science"),
+    (471, "Agronomy"),
+    (480, "Fish and Wildlife Administration"),
+    (482, "Fish Biology"),
+    (485, "Wildlife Refuge Management"),
+    (486, "Wildlife Biology"),
+    (487, "Animal Science"),
+    (499, "Biological Science Student Trainee"),
+    (501, "Financial Administration and Program"),
+    (503, "Financial Clerical and Technician"),
+    (505, "Financial Management"),
+    (510, "Accounting"),
+    (511, "Auditing"),
+    (512, "Internal Revenue Agent"),
+    (525, "Accounting Technician"),
+    (526, "Tax Specialist"),
+    (530, "Cash Processing"),
+    (540, "Voucher Examining"),
+    (544, "Civilian Pay"),
+    (545, "Military Pay"),
+    (560, "Budget Analysis"),
+    (561, "Budget Clerical and Assistance"),
+    (570, "Financial Institution Examining"),
+    (592, "Tax Examining"),
+    (593, "Insurance Accounts"),
+    (599, "Financial Management Student Trainee"),
+    (601, "General Health Science"),
+    (602, "Medical Officer"),
+    (603, "Physician Assistant"),
+    (610, "Nurse"),
+    (620, "Practical Nurse"),
+    (621, "Nursing Assistant"),
+    (622, "Medical Supply Aide and Technician"),
+    (625, "Autopsy Assistant"),
+    (630, "Dietitian and Nutritionist"),
+    (631, "Occupational Therapist"),
+    (633, "Physical Therapist"),
+    (635, "Kinesiotherapy"),
+    (636, "Rehabilitation Therapy Assistant"),
+    (637, "Manual Arts Therapist"),
+    (638, "Recreation/Creative Arts Therapist"),
+    (639, "Educational Therapist"),
+    (640, "Health Aid and Technician"),
+    (642, "Nuclear Medicine Technician"),
+    (644, "Medical Technologist"),
+    (645, "Medical Technician"),
+    (646, "Pathology Technician"),
+    (647, "Diagnostic Radiologic Technician"),
+    (648, "Therapeutic Radiologic Technician"),
+    (649, "Medical Instrument Technician"),
+    (650, "Medical Technical Assistant"),
+    (651, "Respiratory Therapist"),
+    (660, "Pharmacist"),
+    (661, "Pharmacy Technician"),
+    (662, "Optometrist"),
+    (664, "Restoration Technician"),
+    (665, "Speech Pathology and Audiology"),
+    (667, "Orthotist and Prosthetist"),
+    (668, "Podiatrist"),
+    (669, "Medical Records Administration"),
+    (670, "Health System Administration"),
+    (671, "Health System Specialist"),
+    (672, "Prosthetic Representative"),
+    (673, "Hospital Housekeeping Management"),
+    (675, "Medical Records Technician"),
+    (679, "Medical Support Assistance"),
+    (680, "Dental Officer"),
+    (681, "Dental Assistant"),
+    (682, "Dental Hygiene"),
+    (683, "Dental Laboratory Aid and Technician"),
+    (685, "Public Health Program Specialist"),
+    (688, "Sanitarian"),
+    (690, "Industrial Hygiene"),
+    (696, "Consumer Safety"),
+    (698, "Environmental Health Technician"),
+    (699, "Medical and Health Student Trainee"),
+    (701, "Veterinary Medical Science"),
+    (704, "Animal Health Technician"),
+    (799, "Veterinary Student Trainee"),
+    (801, "General Engineering"),
+    (802, "Engineering Technical"),
+    (803, "Safety Engineering"),
+    (804, "Fire Protection Engineering"),
+    (806, "Materials Engineering"),
+    (807, "Landscape Architecture"),
+    (808, "Architecture"),
+    (809, "Construction Control Technical"),
+    (810, "Civil Engineering"),
+    (817, "Survey Technical"),
+    (818, "Engineering Drafting"),
+    (819, "Environmental Engineering"),
+    (830, "Mechanical Engineering"),
+    (840, "Nuclear Engineering"),
+    (850, "Electrical Engineering"),
+    (854, "Computer Engineering"),
+    (855, "Electronics Engineering"),
+    (856, "Electronics Technical"),
+    (858, "Biomedical Engineering"),
+    (861, "Aerospace Engineering"),
+    (871, "Naval Architecture"),
+    (873, "Ship Surveying"),
+    (880, "Mining Engineering"),
+    (881, "Petroleum Engineering"),
+    (890, "Agricultural Engineering"),
+    (892, "Ceramic Engineering"),
+    (893, "Chemical Engineering"),
+    (894, "Welding Engineering"),
+    (895, "Industrial Engineering Technical"),
+    (896, "Industrial Engineering"),
+    (899, "Engineering and Architecture Student Trainee"),
+    (901, "General Legal and Kindred Administration"),
+    (904, "Law Clerk"),
+    (905, "General Attorney"),
+    (920, "Estate Tax Examining"),
+    (930, "Hearings and Appeals"),
+    (945, "Clerk of Court"),
+    (950, "Paralegal Specialist"),
+    (958, "Employee Benefits Law"),
+    (962, "Contact Representative"),
+    (963, "Legal Instruments Examining"),
+    (965, "Land Law Examining"),
+    (967, "Passport and Visa Examining"),
+    (986, "Legal Assistance"),
+    (987, "Tax Law Specialist"),
+    (991, "Workers Compensation Claims Examining"),
+    (993, "Railroad Retirement Claims Examining"),
+    (996, "Veterans Claims Examining"),
+    (998, "Claims Assistance and Examining"),
+    (999, "Legal Occupations Student Trainee"),
+    (1001, "General Arts and Information"),
+    (1008, "Interior Design"),
+    (1010, "Exhibits Specialist"),
+    (1015, "Museum Curator"),
+    (1016, "Museum Specialist and Technician"),
+    (1020, "Illustrating"),
+    (1021, "Office Drafting"),
+    (1035, "Public Affairs"),
+    (1040, "Language Specialist"),
+    (1046, "Language Clerical"),
+    (1051, "Music Specialist"),
+    (1054, "Theater Specialist"),
+    (1056, "Art Specialist"),
+    (1060, "Photography"),
+    (1071, "Audiovisual Production"),
+    (1082, "Writing and Editing"),
+    (1083, "Technical Writing and Editing"),
+    (1084, "Visual Information"),
+    (1087, "Editorial Assistance"),
+    (1099, "Information and Arts Student Trainee"),
+    (1101, "General Business and Industry"),
+    (1102, "Contracting"),
+    (1103, "Industrial Property Management"),
+    (1104, "Property Disposal"),
+    (1105, "Purchasing"),
+    (1106, "Procurement Clerical and Technician"),
+    (1107, "Property Disposal Clerical and Technician"),
+    (1130, "Public Utilities Specialist"),
+    (1140, "Trade Specialist"),
+    (1144, "Commissary Management"),
+    (1145, "Agricultural Program Specialist"),
+    (1146, "Agricultural Marketing"),
+    (1147, "Agricultural Market Reporting"),
+    (1150, "Industrial Specialist"),
+    (1152, "Production Control"),
+    (1160, "Financial Analysis"),
+    (1163, "Insurance Examining"),
+    (1165, "Loan Specialist"),
+    (1169, "Internal Revenue Officer"),
+    (1170, "Realty"),
+    (1171, "Appraising"),
+    (1173, "Housing Management"),
+    (1176, "Building Management"),
+    (1199, "Business and Industry Student Trainee"),
+    (1210, "Copyright"),
+    (1220, "Patent Administration"),
+    (1221, "Patent Adviser"),
+    (1222, "Patent Attorney"),
+    (1223, "Patent Classifying"),
+    (1224, "Patent Examining"),
+    (1226, "Design Patent Examining"),
+    (1299, "Copyright and Patent Student Trainee"),
+    (1301, "General Physical Science"),
+    (1306, "Health Physics"),
+    (1310, "Physics"),
+    (1311, "Physical Science Technician"),
+    (1313, "Geophysics"),
+    (1315, "Hydrology"),
+    (1316, "Hydrologic Technician"),
+    (1320, "Chemistry"),
+    (1321, "Metallurgy"),
+    (1330, "Astronomy and Space Science"),
+    (1340, "Meteorology"),
+    (1341, "Meteorological Technician"),
+    (1350, "Geology"),
+    (1360, "Oceanography"),
+    (1361, "Navigational Information"),
+    (1370, "Cartography"),
+    (1371, "Cartographic Technician"),
+    (1372, "Geodesy"),
+    (1373, "Land Surveying"),
+    (1374, "Geodetic Technician"),
+    (1380, "Forest Products Technology"),
+    (1382, "Food Technology"),
+    (1384, "Textile Technology"),
+    (1386, "Photographic Technology"),
+    (1397, "Document Analysis"),
+    (1399, "Physical Science Student Trainee"),
+    (1410, "Librarian"),
+    (1411, "Library Technician"),
+    (1412, "Technical Information Services"),
+    (1420, "Archivist"),
+    (1421, "Archives Technician"),
+    (1499, "Library and Archives Student Trainee"),
+    (1501, "General Mathematics and Statistics"),
+    (1510, "Actuarial Science"),
+    (1515, "Operations Research"),
+    (1520, "Mathematics"),
+    (1521, "Mathematics Technician"),
+    (1529, "Mathematical Statistics"),
+    (1530, "Statistics"),
+    (1531, "Statistical Assistant"),
+    (1550, "Computer Science"),
+    (1560, "Data Science"),
+    (1599, "Mathematics and Statistics Student Trainee"),
+    (1601, "Equipment, Facilities, and Services"),
+    (1603, "Equipment, Facilities, and Services Assistance"),
+    (1630, "Cemetery Administration Services"),
+    (1640, "Facility Operations Services"),
+    (1654, "Printing Services"),
+    (1658, "Laundry Operations Services"),
+    (1667, "Food Services"),
+    (1670, "Equipment Services"),
+    (1699, "Equipment, Facilities, and Services Student Trainee"),
+    (1701, "General Education and Training"),
+    (1702, "Education and Training Technician"),
+    (1710, "Education and Vocational Training"),
+    (1712, "Training Instruction"),
+    (1715, "Vocational Rehabilitation"),
+    (1720, "Education Program"),
+    (1725, "Public Health Educator"),
+    (1730, "Education Research"),
+    (1740, "Education Services"),
+    (1750, "Instructional Systems"),
+    (1799, "Education Student Trainee"),
+    (
+        1801,
+        "General Inspection, Investigation, Enforcement, and Compliance",
+    ),
+    (1802, "Compliance Inspection and Support"),
+    (1805, "Investigative Analysis"),
+    (1810, "General Investigation"),
+    (1811, "Criminal Investigation"),
+    (1812, "Game Law Enforcement"),
+    (1815, "Air Safety Investigating"),
+    (1816, "Immigration Inspection"),
+    (1822, "Mine Safety and Health"),
+    (1825, "Aviation Safety"),
+    (1831, "Securities Compliance Examining"),
+    (1850, "Agricultural Warehouse Inspection"),
+    (1854, "Alcohol, Tobacco and Firearms Inspection"),
+    (1862, "Consumer Safety Inspection"),
+    (1863, "Food Inspection"),
+    (1881, "Customs and Border Protection Interdiction"),
+    (1884, "Customs Patrol Officer"),
+    (1889, "Import Specialist"),
+    (1890, "Customs Inspection"),
+    (1894, "Customs Entry and Liquidating"),
+    (1895, "Customs and Border Protection"),
+    (1896, "Border Patrol Enforcement"),
+    (1899, "Investigation Student Trainee"),
+    (1910, "Quality Assurance"),
+    (1980, "Agricultural Commodity Grading"),
+    (1981, "Agricultural Commodity Aid"),
+    (1999, "Quality Inspection Student Trainee"),
+    (2001, "General Supply"),
+    (2003, "Supply Program Management"),
+    (2005, "Supply Clerical and Technician"),
+    (2010, "Inventory Management"),
+    (2030, "Distribution Facilities and Storage Management"),
+    (2032, "Packaging"),
+    (2091, "Sales Store Clerical"),
+    (2099, "Supply Student Trainee"),
+    (2101, "Transportation Specialist"),
+    (2102, "Transportation Clerk and Assistant"),
+    (2110, "Transportation Industry Analysis"),
+    (2121, "Railroad Safety"),
+    (2123, "Motor Carrier Safety"),
+    (2125, "Highway Safety"),
+    (2130, "Traffic Management"),
+    (2131, "Freight Rate"),
+    (2135, "Transportation Loss/Damage Claims Examining"),
+    (2144, "Cargo Scheduling"),
+    (2150, "Transportation Operations"),
+    (2151, "Dispatching"),
+    (2152, "Air Traffic Control"),
+    (2154, "Air Traffic Assistance"),
+    (2161, "Marine Cargo"),
+    (2181, "Aircraft Operation"),
+    (2183, "Air Navigation"),
+    (2185, "Aircrew Technician"),
+    (2199, "Transportation Student Trainee"),
+    (2210, "Information Technology Management"),
+    (2299, "Information Technology Student Trainee"),
+];
+
+/// Returns the title of an occupational series such as `0510`, if it is a known series.
+pub fn series_title(series: &str) -> Option<&'static str> {
+    let number = series.parse().ok()?;
+
+    SERIES
+        .binary_search_by_key(&number, |&(n, _)| n)
+        .ok()
+        .map(|i| SERIES[i].1)
+}
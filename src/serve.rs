@@ -0,0 +1,354 @@
+use std::{
+    io::{BufRead, BufReader, Error, ErrorKind, Read, Result, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Condvar, Mutex},
+    thread,
+    time::Duration,
+};
+
+use fpl::{extract_with, Rules};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    extract_rows,
+    input::{read_csv, Record},
+    preprocess_records,
+    print::print_csv,
+    Args,
+};
+
+/// The largest request body accepted, in bytes.
+const MAX_BODY_SIZE: usize = 64 * 1024 * 1024;
+
+/// The number of connections handled at once, further ones waiting to be accepted.
+const MAX_CONNECTIONS: usize = 64;
+
+/// How long a read from a client may block before the connection is dropped.
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Deserialize)]
+struct ExtractRequest {
+    text: String,
+}
+
+#[derive(Serialize)]
+struct ExtractResponse<'a> {
+    grade: Option<&'a str>,
+    pay_plan: Option<String>,
+    series: Option<&'a str>,
+    kind: Option<&'static str>,
+    valid: Option<bool>,
+    confidence: Option<f64>,
+    rule: Option<&'static str>,
+    source: Option<&'static str>,
+    spans: Option<Spans>,
+}
+
+/// The byte ranges of the trigger phrase and the grade in the text, with exclusive ends.
+#[derive(Serialize)]
+struct Spans {
+    trigger: [usize; 2],
+    grade: [usize; 2],
+}
+
+struct Request {
+    body: Vec<u8>,
+    method: String,
+    path: String,
+}
+
+struct Response {
+    body: Vec<u8>,
+    content_type: &'static str,
+    status: &'static str,
+}
+
+impl Response {
+    fn error(status: &'static str, message: &str) -> Self {
+        Self {
+            body: format!("{message}\n").into_bytes(),
+            content_type: "text/plain; charset=utf-8",
+            status,
+        }
+    }
+}
+
+/// Serves the extraction over HTTP on `host` and `port`, answering `POST /extract` with the
+/// grade of a JSON `{"text": ...}` body and `POST /batch` with the CSV output for an uploaded CSV
+/// file.
+pub fn serve(host: &str, port: u16, rules: &Rules, args: &Args) -> Result<bool> {
+    let listener = TcpListener::bind((host, port))?;
+    let active = (Mutex::new(0), Condvar::new());
+
+    eprintln!("Listening on http://{}", listener.local_addr()?);
+
+    thread::scope(|scope| {
+        for stream in listener.incoming() {
+            // A failed accept, e.g. when out of file descriptors, only loses that connection.
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(error) => {
+                    eprintln!("Error: {error}");
+                    continue;
+                }
+            };
+
+            let (count, finished) = &active;
+            let mut count = count.lock().unwrap();
+
+            count = finished
+                .wait_while(count, |count| *count >= MAX_CONNECTIONS)
+                .unwrap();
+
+            *count += 1;
+            drop(count);
+
+            let active = &active;
+
+            scope.spawn(move || {
+                let result = stream
+                    .set_read_timeout(Some(READ_TIMEOUT))
+                    .and_then(|_| handle(stream, rules, args));
+
+                if let Err(error) = result {
+                    eprintln!("Error: {error}");
+                }
+
+                let (count, finished) = active;
+                *count.lock().unwrap() -= 1;
+                finished.notify_one();
+            });
+        }
+
+        Ok(true)
+    })
+}
+
+fn batch(body: Vec<u8>, rules: &Rules, args: &Args) -> Result<Response> {
+    let mut records = read_csv(args, body, "upload")?;
+
+    preprocess_records(&mut records, args);
+
+    let mut rows = extract_rows(&records, rules, args);
+    let mut out = Vec::new();
+
+    rows.retain(|r| r.is_selected(args));
+    print_csv(&mut out, &rows, &[], args)?;
+
+    Ok(Response {
+        body: out,
+        content_type: "text/csv; charset=utf-8",
+        status: "200 OK",
+    })
+}
+
+fn extract(body: &[u8], rules: &Rules, args: &Args) -> Result<Response> {
+    let request: ExtractRequest =
+        serde_json::from_slice(body).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+    let mut records = [Record {
+        id: 0,
+        normalized: None,
+        source: Arc::from("request"),
+        text: request.text,
+    }];
+
+    preprocess_records(&mut records, args);
+
+    let extraction = extract_with(rules, records[0].input());
+    let extraction = extraction.as_ref();
+
+    let response = ExtractResponse {
+        grade: extraction.map(|e| e.grade),
+        pay_plan: extraction.and_then(|e| e.pay_plan.map(str::to_uppercase)),
+        series: extraction.and_then(|e| e.series),
+        kind: extraction.map(|e| e.kind.as_str()),
+        valid: extraction.map(|e| e.valid),
+        confidence: extraction.map(|e| (e.confidence * 100.0).round() / 100.0),
+        rule: extraction.map(|e| e.rule.as_str()),
+        source: extraction.map(|e| e.rule.source()),
+        spans: extraction.map(|e| Spans {
+            trigger: [e.trigger_start, e.trigger_end],
+            grade: [e.start, e.end],
+        }),
+    };
+
+    Ok(Response {
+        body: serde_json::to_vec(&response)?,
+        content_type: "application/json",
+        status: "200 OK",
+    })
+}
+
+fn handle(stream: TcpStream, rules: &Rules, args: &Args) -> Result<()> {
+    let mut reader = BufReader::new(&stream);
+
+    let response = match read_request(&mut reader) {
+        Ok(request) => {
+            let path = request.path.split('?').next().unwrap_or_default();
+
+            let response = match (request.method.as_str(), path) {
+                ("POST", "/extract") => extract(&request.body, rules, args),
+                ("POST", "/batch") => batch(request.body, rules, args),
+                (_, "/extract" | "/batch") => {
+                    Ok(Response::error("405 Method Not Allowed", "use POST"))
+                }
+                _ => Ok(Response::error("404 Not Found", "not found")),
+            };
+
+            response.unwrap_or_else(|e| Response::error("400 Bad Request", &e.to_string()))
+        }
+        Err(error) if error.kind() == ErrorKind::OutOfMemory => {
+            Response::error("413 Content Too Large", &error.to_string())
+        }
+        Err(error) => Response::error("400 Bad Request", &error.to_string()),
+    };
+
+    let mut stream = &stream;
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.content_type,
+        response.body.len()
+    )?;
+
+    stream.write_all(&response.body)?;
+    stream.flush()
+}
+
+fn read_request(reader: &mut impl BufRead) -> Result<Request> {
+    let invalid = |message| Error::new(ErrorKind::InvalidData, message);
+
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+
+    let mut parts = line.split_whitespace();
+    let method = parts.next().ok_or_else(|| invalid("empty request"))?;
+    let path = parts
+        .next()
+        .ok_or_else(|| invalid("request without a path"))?;
+
+    let mut request = Request {
+        body: Vec::new(),
+        method: method.to_string(),
+        path: path.to_string(),
+    };
+
+    let mut length = 0;
+
+    loop {
+        let mut header = String::new();
+
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| invalid("invalid Content-Length"))?;
+            }
+        }
+    }
+
+    if length > MAX_BODY_SIZE {
+        return Err(Error::new(
+            ErrorKind::OutOfMemory,
+            format!("request body exceeds {MAX_BODY_SIZE} bytes"),
+        ));
+    }
+
+    // The body is read as it arrives rather than allocated up front from the Content-Length.
+    reader.take(length as u64).read_to_end(&mut request.body)?;
+
+    if request.body.len() < length {
+        return Err(Error::new(
+            ErrorKind::UnexpectedEof,
+            "request body is shorter than its Content-Length",
+        ));
+    }
+
+    Ok(request)
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use crate::serve::*;
+
+    fn args() -> Args {
+        Args::parse_from(["fpl", "serve"])
+    }
+
+    #[test]
+    fn test_batch() {
+        let body = b"1,The fpl is gs-13.\n2,No grade here.\n".to_vec();
+        let response = batch(body, &Rules::default(), &args()).unwrap();
+
+        assert_eq!(response.status, "200 OK");
+        assert_eq!(response.content_type, "text/csv; charset=utf-8");
+        assert_eq!(
+            String::from_utf8(response.body).unwrap(),
+            "1,13,GS,,grade,true,0.98,fpl_abbrev,fpl,The fpl is gs-13.\n2,,,,,,,,,No grade here.\n"
+        );
+    }
+
+    #[test]
+    fn test_extract() {
+        let body = br#"{"text": "The fpl is gs-13."}"#;
+        let response = extract(body, &Rules::default(), &args()).unwrap();
+
+        assert_eq!(response.status, "200 OK");
+        assert_eq!(response.content_type, "application/json");
+        assert_eq!(
+            String::from_utf8(response.body).unwrap(),
+            concat!(
+                r#"{"grade":"13","pay_plan":"GS","series":null,"kind":"grade","valid":true,"#,
+                r#""confidence":0.98,"rule":"fpl_abbrev","source":"fpl","#,
+                r#""spans":{"trigger":[4,7],"grade":[14,16]}}"#
+            )
+        );
+
+        let error = extract(b"{}", &Rules::default(), &args()).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_read_request() {
+        let mut input =
+            &b"POST /extract?x=1 HTTP/1.1\r\nHost: localhost\r\ncontent-length: 5\r\n\r\nhello!"[..];
+        let request = read_request(&mut input).unwrap();
+
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/extract?x=1");
+        assert_eq!(request.body, b"hello");
+
+        let mut input = &b"GET / HTTP/1.1\r\n\r\n"[..];
+        assert!(read_request(&mut input).unwrap().body.is_empty());
+
+        for (input, kind) in [
+            (&b""[..], ErrorKind::InvalidData),
+            (b"GET\r\n\r\n", ErrorKind::InvalidData),
+            (
+                b"POST / HTTP/1.1\r\nContent-Length: x\r\n\r\n",
+                ErrorKind::InvalidData,
+            ),
+            (
+                b"POST / HTTP/1.1\r\nContent-Length: 9\r\n\r\nhello",
+                ErrorKind::UnexpectedEof,
+            ),
+            (
+                b"POST / HTTP/1.1\r\nContent-Length: 99999999999\r\n\r\n",
+                ErrorKind::OutOfMemory,
+            ),
+        ] {
+            let mut input = input;
+            assert_eq!(read_request(&mut input).err().unwrap().kind(), kind);
+        }
+    }
+}
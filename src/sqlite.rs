@@ -0,0 +1,445 @@
+use std::io::{Result, Seek, SeekFrom, Write};
+
+/// The number of children that always fit on an interior table page, even with the largest keys.
+const INTERIOR_CHILDREN: usize = (PAGE_SIZE - INTERIOR_HEADER_SIZE) / (4 + 9 + 2) + 1;
+
+const INTERIOR_HEADER_SIZE: usize = 12;
+const LEAF_HEADER_SIZE: usize = 8;
+const PAGE_SIZE: usize = 4096;
+
+/// The largest payload stored entirely on a table leaf page.
+const MAX_LOCAL: usize = PAGE_SIZE - 35;
+
+/// The smallest part of an overflowing payload stored on a table leaf page.
+const MIN_LOCAL: usize = (PAGE_SIZE - 12) * 32 / 255 - 23;
+
+pub enum Value<'a> {
+    Integer(i64),
+    Null,
+    Text(&'a str),
+}
+
+/// Writes a database with a single table, streaming its rows into leaf pages and building the
+/// interior pages of the table at the end.
+pub struct Database {
+    leaf: Page,
+    leaf_rowid: i64,
+    leaves: Vec<(u32, i64)>,
+    pages: u32,
+    rowid: i64,
+    schema: String,
+    table: String,
+}
+
+struct Page {
+    cells: Vec<Vec<u8>>,
+    header_size: usize,
+    size: usize,
+}
+
+impl Database {
+    pub fn finish(mut self, out: &mut (impl Write + Seek)) -> Result<()> {
+        if !self.leaf.cells.is_empty() || self.leaves.is_empty() {
+            self.flush_leaf(out)?;
+        }
+
+        let mut level = std::mem::take(&mut self.leaves);
+
+        while level.len() > 1 {
+            let mut parents = Vec::new();
+            let mut start = 0;
+
+            while start < level.len() {
+                let mut end = (start + INTERIOR_CHILDREN).min(level.len());
+
+                if level.len() - end == 1 {
+                    end -= 1;
+                }
+
+                let (&(right, key), children) = level[start..end].split_last().unwrap();
+
+                let mut page = Page::new(INTERIOR_HEADER_SIZE);
+
+                for &(child, key) in children {
+                    let mut cell = child.to_be_bytes().to_vec();
+
+                    varint(&mut cell, key as u64);
+                    page.push(cell);
+                }
+
+                let number = self.write_page(out, &page.to_bytes(0x05, Some(right), 0))?;
+
+                parents.push((number, key));
+                start = end;
+            }
+
+            level = parents;
+        }
+
+        let root = level[0].0;
+        let mut schema = Page::new(LEAF_HEADER_SIZE);
+
+        schema.push(leaf_cell(
+            1,
+            &record(&[
+                Value::Text("table"),
+                Value::Text(&self.table),
+                Value::Text(&self.table),
+                Value::Integer(root as i64),
+                Value::Text(&self.schema),
+            ]),
+            &mut Vec::new(),
+            &mut self.pages,
+        ));
+
+        let mut page = header(self.pages).to_vec();
+        page.extend(&schema.to_bytes(0x0d, None, 100)[100..]);
+
+        out.seek(SeekFrom::Start(0))?;
+        out.write_all(&page)?;
+        out.seek(SeekFrom::End(0))?;
+
+        Ok(())
+    }
+
+    pub fn insert(&mut self, out: &mut impl Write, values: &[Value]) -> Result<()> {
+        self.rowid += 1;
+
+        let mut overflow = Vec::new();
+        let cell = leaf_cell(self.rowid, &record(values), &mut overflow, &mut self.pages);
+
+        for page in overflow {
+            out.write_all(&page)?;
+        }
+
+        if !self.leaf.fits(&cell) {
+            self.flush_leaf(out)?;
+        }
+
+        self.leaf.push(cell);
+        self.leaf_rowid = self.rowid;
+
+        Ok(())
+    }
+
+    /// Starts a database with a table called `table` created by the `create` SQL statement.
+    pub fn new(out: &mut impl Write, table: &str, create: &str) -> Result<Self> {
+        out.write_all(&[0; PAGE_SIZE])?;
+
+        Ok(Self {
+            leaf: Page::new(LEAF_HEADER_SIZE),
+            leaf_rowid: 0,
+            leaves: Vec::new(),
+            pages: 1,
+            rowid: 0,
+            schema: create.to_string(),
+            table: table.to_string(),
+        })
+    }
+
+    fn flush_leaf(&mut self, out: &mut impl Write) -> Result<()> {
+        let leaf = std::mem::replace(&mut self.leaf, Page::new(LEAF_HEADER_SIZE));
+        let number = self.write_page(out, &leaf.to_bytes(0x0d, None, 0))?;
+
+        self.leaves.push((number, self.leaf_rowid));
+
+        Ok(())
+    }
+
+    fn write_page(&mut self, out: &mut impl Write, page: &[u8]) -> Result<u32> {
+        self.pages += 1;
+        out.write_all(page)?;
+
+        Ok(self.pages)
+    }
+}
+
+impl Page {
+    fn fits(&self, cell: &[u8]) -> bool {
+        self.header_size + self.size + cell.len() + 2 <= PAGE_SIZE
+    }
+
+    fn new(header_size: usize) -> Self {
+        Self {
+            cells: Vec::new(),
+            header_size,
+            size: 0,
+        }
+    }
+
+    fn push(&mut self, cell: Vec<u8>) {
+        self.size += cell.len() + 2;
+        self.cells.push(cell);
+    }
+
+    fn to_bytes(&self, kind: u8, right: Option<u32>, offset: usize) -> Vec<u8> {
+        let mut page = vec![0; PAGE_SIZE];
+        let mut content = PAGE_SIZE;
+        let mut pointer = offset + self.header_size;
+
+        for cell in &self.cells {
+            content -= cell.len();
+            page[content..content + cell.len()].copy_from_slice(cell);
+            page[pointer..pointer + 2].copy_from_slice(&(content as u16).to_be_bytes());
+            pointer += 2;
+        }
+
+        page[offset] = kind;
+        page[offset + 3..offset + 5].copy_from_slice(&(self.cells.len() as u16).to_be_bytes());
+        page[offset + 5..offset + 7].copy_from_slice(&(content as u16).to_be_bytes());
+
+        if let Some(right) = right {
+            page[offset + 8..offset + 12].copy_from_slice(&right.to_be_bytes());
+        }
+
+        page
+    }
+}
+
+fn header(pages: u32) -> [u8; 100] {
+    let mut header = [0; 100];
+
+    header[0..16].copy_from_slice(b"SQLite format 3\0");
+    header[16..18].copy_from_slice(&(PAGE_SIZE as u16).to_be_bytes());
+    header[18] = 1;
+    header[19] = 1;
+    header[21] = 64;
+    header[22] = 32;
+    header[23] = 32;
+    header[24..28].copy_from_slice(&1u32.to_be_bytes());
+    header[28..32].copy_from_slice(&pages.to_be_bytes());
+    header[40..44].copy_from_slice(&1u32.to_be_bytes());
+    header[44..48].copy_from_slice(&4u32.to_be_bytes());
+    header[56..60].copy_from_slice(&1u32.to_be_bytes());
+    header[92..96].copy_from_slice(&1u32.to_be_bytes());
+    header[96..100].copy_from_slice(&3042000u32.to_be_bytes());
+
+    header
+}
+
+/// Builds a table leaf cell, moving the part of the payload that does not fit on the leaf page
+/// into overflow pages numbered from `pages + 1`.
+fn leaf_cell(rowid: i64, payload: &[u8], overflow: &mut Vec<Vec<u8>>, pages: &mut u32) -> Vec<u8> {
+    let mut cell = Vec::new();
+
+    varint(&mut cell, payload.len() as u64);
+    varint(&mut cell, rowid as u64);
+
+    if payload.len() <= MAX_LOCAL {
+        cell.extend(payload);
+        return cell;
+    }
+
+    let local = MIN_LOCAL + (payload.len() - MIN_LOCAL) % (PAGE_SIZE - 4);
+    let local = if local <= MAX_LOCAL { local } else { MIN_LOCAL };
+
+    cell.extend(&payload[0..local]);
+    cell.extend((*pages + 1).to_be_bytes());
+
+    let chunks = payload[local..].chunks(PAGE_SIZE - 4).collect::<Vec<_>>();
+
+    for (i, chunk) in chunks.iter().enumerate() {
+        *pages += 1;
+
+        let next = if i + 1 < chunks.len() { *pages + 1 } else { 0 };
+        let mut page = next.to_be_bytes().to_vec();
+
+        page.extend(*chunk);
+        page.resize(PAGE_SIZE, 0);
+        overflow.push(page);
+    }
+
+    cell
+}
+
+fn record(values: &[Value]) -> Vec<u8> {
+    let mut types = Vec::new();
+    let mut body: Vec<u8> = Vec::new();
+
+    for value in values {
+        match value {
+            Value::Integer(0) => types.push(8),
+            Value::Integer(1) => types.push(9),
+            Value::Integer(integer) => {
+                let (kind, size) = match integer {
+                    -0x80..=0x7f => (1, 1),
+                    -0x8000..=0x7fff => (2, 2),
+                    -0x80_0000..=0x7f_ffff => (3, 3),
+                    -0x8000_0000..=0x7fff_ffff => (4, 4),
+                    -0x8000_0000_0000..=0x7fff_ffff_ffff => (5, 6),
+                    _ => (6, 8),
+                };
+
+                types.push(kind);
+                body.extend(&integer.to_be_bytes()[8 - size..]);
+            }
+            Value::Null => types.push(0),
+            Value::Text(text) => {
+                types.push(13 + 2 * text.len() as u64);
+                body.extend(text.as_bytes());
+            }
+        }
+    }
+
+    let mut header = Vec::new();
+
+    for kind in types {
+        varint(&mut header, kind);
+    }
+
+    let mut size = header.len() + 1;
+
+    while header.len() + varint_len(size as u64) != size {
+        size += 1;
+    }
+
+    let mut record = Vec::new();
+
+    varint(&mut record, size as u64);
+    record.extend(header);
+    record.extend(body);
+    record
+}
+
+fn varint(out: &mut Vec<u8>, value: u64) {
+    if value > 0x00ff_ffff_ffff_ffff {
+        let mut bytes = [0; 9];
+        let mut value = value;
+
+        bytes[8] = value as u8;
+        value >>= 8;
+
+        for i in (0..8).rev() {
+            bytes[i] = (value & 0x7f) as u8 | 0x80;
+            value >>= 7;
+        }
+
+        out.extend(bytes);
+        return;
+    }
+
+    let mut bytes = vec![(value & 0x7f) as u8];
+    let mut value = value >> 7;
+
+    while value > 0 {
+        bytes.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+
+    bytes.reverse();
+    out.extend(bytes);
+}
+
+fn varint_len(value: u64) -> usize {
+    let mut bytes = Vec::new();
+
+    varint(&mut bytes, value);
+    bytes.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::sqlite::*;
+
+    /// Returns the number of rows in the tree under `page`, checking that its leaves are all
+    /// `depth` levels down.
+    fn count(data: &[u8], page: u32, depth: usize) -> usize {
+        let start = (page as usize - 1) * PAGE_SIZE;
+        let page = &data[start..start + PAGE_SIZE];
+        let u16_at = |i: usize| u16::from_be_bytes([page[i], page[i + 1]]) as usize;
+        let u32_at = |i: usize| u32::from_be_bytes(page[i..i + 4].try_into().unwrap());
+
+        match page[0] {
+            0x0d => {
+                assert_eq!(depth, 0);
+                u16_at(3)
+            }
+            0x05 => (0..u16_at(3))
+                .map(|i| u32_at(u16_at(INTERIOR_HEADER_SIZE + 2 * i)))
+                .chain([u32_at(8)])
+                .map(|child| count(data, child, depth - 1))
+                .sum(),
+            kind => panic!("unexpected page type {kind:#x}"),
+        }
+    }
+
+    fn v(value: u64) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        varint(&mut bytes, value);
+        bytes
+    }
+
+    #[test]
+    fn test_finish() {
+        let mut out = Cursor::new(Vec::new());
+        let mut database =
+            Database::new(&mut out, "results", "CREATE TABLE results(text)").unwrap();
+        let text = "GS-12 ".repeat(660);
+
+        // A row fills a leaf, so that the leaves need two levels of interior pages.
+        for _ in 0..INTERIOR_CHILDREN + 27 {
+            database.insert(&mut out, &[Value::Text(&text)]).unwrap();
+        }
+
+        database.finish(&mut out).unwrap();
+
+        let data = out.into_inner();
+        let pages = data.len() / PAGE_SIZE;
+
+        assert_eq!(&data[0..16], b"SQLite format 3\0");
+        assert_eq!(data[28..32], (pages as u32).to_be_bytes());
+
+        // The root is the last page written.
+        assert_eq!(count(&data, pages as u32, 2), INTERIOR_CHILDREN + 27);
+    }
+
+    #[test]
+    fn test_record() {
+        assert_eq!(
+            record(&[Value::Null, Value::Integer(0), Value::Integer(1)]),
+            [4, 0, 8, 9]
+        );
+        assert_eq!(record(&[Value::Text("GS-12")]), b"\x02\x17GS-12");
+        assert_eq!(record(&[Value::Integer(-1)]), [2, 1, 0xff]);
+        assert_eq!(record(&[Value::Integer(0x7fff)]), [2, 2, 0x7f, 0xff]);
+        assert_eq!(record(&[Value::Integer(0x8000)]), [2, 3, 0, 0x80, 0]);
+        assert_eq!(
+            record(&[Value::Integer(-0x8000_0000)]),
+            [2, 4, 0x80, 0, 0, 0]
+        );
+        assert_eq!(record(&[Value::Integer(1 << 40)]), [2, 5, 1, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            record(&[Value::Integer(i64::MIN)]),
+            [2, 6, 0x80, 0, 0, 0, 0, 0, 0, 0]
+        );
+
+        // A header of 128 bytes or more takes two bytes for its own size.
+        let nulls = (0..127).map(|_| Value::Null).collect::<Vec<_>>();
+        let long = record(&nulls);
+
+        assert_eq!(long[0..2], [0x81, 0x01]);
+        assert_eq!(long.len(), 129);
+    }
+
+    #[test]
+    fn test_varint() {
+        assert_eq!(v(0), [0]);
+        assert_eq!(v(0x7f), [0x7f]);
+        assert_eq!(v(0x80), [0x81, 0]);
+        assert_eq!(v(0x3fff), [0xff, 0x7f]);
+        assert_eq!(v(0x4000), [0x81, 0x80, 0]);
+        assert_eq!(
+            v(0x00ff_ffff_ffff_ffff),
+            [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f]
+        );
+        assert_eq!(
+            v(0x0100_0000_0000_0000),
+            [0x80, 0xc0, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0]
+        );
+        assert_eq!(v(u64::MAX), [0xff; 9]);
+        assert_eq!(varint_len(u64::MAX), 9);
+    }
+}
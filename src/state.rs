@@ -0,0 +1,197 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{input::Record, output::Output, UniqueBy};
+
+/// The records processed by earlier runs, which `--state` skips.
+pub struct State {
+    /// Where the state is saved, or `None` for a state kept in memory.
+    path: Option<PathBuf>,
+    file: StateFile,
+}
+
+#[derive(Deserialize, Serialize)]
+struct StateFile {
+    /// `id` or `text`, what the records are told apart by.
+    by: String,
+    /// The IDs of the records, or the hashes of their texts.
+    records: BTreeSet<String>,
+}
+
+impl State {
+    /// Reads the state from `path`, or starts an empty one if the file does not exist.
+    pub fn load(path: &Path, by: UniqueBy) -> Result<Self> {
+        let empty = Self::new(by);
+        let by = empty.file.by.clone();
+
+        let file = match fs::read(path) {
+            Ok(bytes) => serde_json::from_slice::<StateFile>(&bytes).map_err(|e| {
+                Error::new(ErrorKind::InvalidData, format!("{}: {e}", path.display()))
+            })?,
+            Err(error) if error.kind() == ErrorKind::NotFound => empty.file,
+            Err(error) => return Err(error),
+        };
+
+        if file.by != by {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "{} tells records apart by {}, not by {by}",
+                    path.display(),
+                    file.by
+                ),
+            ));
+        }
+
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            file,
+        })
+    }
+
+    /// Starts an empty state that is only kept in memory.
+    pub fn new(by: UniqueBy) -> Self {
+        let by = match by {
+            UniqueBy::Id => "id",
+            UniqueBy::Text => "text",
+        };
+
+        Self {
+            path: None,
+            file: StateFile {
+                by: by.to_string(),
+                records: BTreeSet::new(),
+            },
+        }
+    }
+
+    /// Returns whether `record` was not processed before, remembering it.
+    pub fn insert(&mut self, record: &Record) -> bool {
+        let key = match self.file.by.as_str() {
+            "id" => record.id.to_string(),
+            _ => format!("{:016x}", hash(record.input())),
+        };
+
+        self.file.records.insert(key)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let mut out = Output::new(Some(path))?;
+
+        serde_json::to_writer(&mut out, &self.file)?;
+        out.commit()
+    }
+}
+
+/// Returns the 64-bit FNV-1a hash of `text`, which unlike the hashers of the standard library is
+/// the same in every build.
+fn hash(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process, sync::Arc};
+
+    use crate::{input::Record, state::*, UniqueBy};
+
+    fn r(id: usize, text: &str) -> Record {
+        Record {
+            id,
+            normalized: None,
+            source: Arc::from("input.csv"),
+            text: text.to_string(),
+        }
+    }
+
+    fn temp(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("fpl-{}-{name}.json", process::id()));
+
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_state_by_id() {
+        let mut state = State::load(&temp("by-id"), UniqueBy::Id).unwrap();
+
+        assert!(state.insert(&r(1, "GS-12")));
+        assert!(!state.insert(&r(1, "GS-13")));
+        assert!(state.insert(&r(2, "GS-12")));
+    }
+
+    #[test]
+    fn test_state_by_text() {
+        let mut state = State::load(&temp("by-text"), UniqueBy::Text).unwrap();
+        let normalized = Record {
+            normalized: Some("GS-12".to_string()),
+            ..r(3, "GS&#8209;12")
+        };
+
+        assert!(state.insert(&r(1, "GS-12")));
+        assert!(!state.insert(&r(2, "GS-12")));
+        assert!(!state.insert(&normalized));
+        assert!(state.insert(&r(1, "GS-13")));
+    }
+
+    #[test]
+    fn test_state_mismatch() {
+        let path = temp("mismatch");
+
+        State::load(&path, UniqueBy::Id).unwrap().save().unwrap();
+
+        let error = State::load(&path, UniqueBy::Text).err().unwrap();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(
+            error.to_string(),
+            format!("{} tells records apart by id, not by text", path.display())
+        );
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_state_new() {
+        let mut state = State::new(UniqueBy::Id);
+
+        assert!(state.insert(&r(1, "GS-12")));
+        assert!(!state.insert(&r(1, "GS-12")));
+        assert!(state.save().is_ok());
+    }
+
+    #[test]
+    fn test_state_save() {
+        let path = temp("save");
+        let mut state = State::load(&path, UniqueBy::Id).unwrap();
+
+        state.insert(&r(2, "GS-12"));
+        state.insert(&r(10, "GS-13"));
+        state.save().unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            r#"{"by":"id","records":["10","2"]}"#
+        );
+
+        let mut state = State::load(&path, UniqueBy::Id).unwrap();
+
+        assert!(!state.insert(&r(2, "GS-12")));
+        assert!(!state.insert(&r(10, "GS-13")));
+        assert!(state.insert(&r(11, "GS-14")));
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,159 @@
+use std::{
+    collections::BTreeMap,
+    io::{Result, Write},
+};
+
+use crate::Row;
+
+/// The width of the longest bar of the grade histogram.
+const BAR_WIDTH: usize = 40;
+
+/// Aggregates extraction results instead of printing them record by record.
+#[derive(Default)]
+pub struct Stats {
+    grades: BTreeMap<u32, usize>,
+    matched: usize,
+    pay_plans: BTreeMap<String, usize>,
+    records: usize,
+    rules: BTreeMap<&'static str, usize>,
+}
+
+impl Stats {
+    pub fn add(&mut self, rows: &[Row]) {
+        self.records += rows.len();
+
+        for mention in rows.iter().filter_map(|r| r.mention.as_ref()) {
+            let grade = mention.grade();
+            let pay_plan = grade.pay_plan.map(str::to_uppercase).unwrap_or_default();
+
+            self.matched += 1;
+            *self.grades.entry(grade.value()).or_default() += 1;
+            *self.pay_plans.entry(pay_plan).or_default() += 1;
+            *self.rules.entry(mention.rule.as_str()).or_default() += 1;
+        }
+    }
+
+    pub fn print(&self, out: &mut impl Write) -> Result<()> {
+        let rate = if self.records == 0 {
+            0.0
+        } else {
+            self.matched as f64 * 100.0 / self.records as f64
+        };
+
+        writeln!(out, "Records: {}", self.records)?;
+        writeln!(out, "Matched: {} ({rate:.1}%)", self.matched)?;
+        writeln!(out)?;
+        writeln!(out, "Grades:")?;
+
+        let max = self.grades.values().copied().max().unwrap_or_default();
+
+        for (grade, &count) in &self.grades {
+            let bar = "#".repeat((count * BAR_WIDTH).div_ceil(max));
+            writeln!(out, "  {grade:>3} {count:>8} {bar}")?;
+        }
+
+        print_counts(out, "Rules", &self.rules)?;
+        print_counts(out, "Pay plans", &self.pay_plans)
+    }
+}
+
+fn print_counts<K: AsRef<str>>(
+    out: &mut impl Write,
+    title: &str,
+    counts: &BTreeMap<K, usize>,
+) -> Result<()> {
+    writeln!(out)?;
+    writeln!(out, "{title}:")?;
+
+    for (key, count) in counts {
+        let key = key.as_ref();
+        let key = if key.is_empty() { "(none)" } else { key };
+
+        writeln!(out, "  {key:<24} {count:>8}")?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use clap::Parser;
+    use fpl::Rules;
+
+    use crate::{extract_rows, input::Record, stats::*, Args};
+
+    fn printed(stats: &Stats) -> String {
+        let mut out = Vec::new();
+
+        stats.print(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_stats() {
+        let args = Args::parse_from(["fpl", "stats", "input.csv"]);
+        let records = [
+            "The FPL is GS-12",
+            "Full performance level: gs-12",
+            "Full performance level: 13",
+            "The FPL is WG-10",
+            "No grade here",
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(id, text)| Record {
+            id,
+            normalized: None,
+            source: Arc::from("input.csv"),
+            text: text.to_string(),
+        })
+        .collect::<Vec<_>>();
+
+        let rows = extract_rows(&records, &Rules::default(), &args);
+        let mut stats = Stats::default();
+
+        stats.add(&rows[..2]);
+        stats.add(&rows[2..]);
+
+        assert_eq!(
+            printed(&stats),
+            concat!(
+                "Records: 5\n",
+                "Matched: 4 (80.0%)\n",
+                "\n",
+                "Grades:\n",
+                "   10        1 ####################\n",
+                "   12        2 ########################################\n",
+                "   13        1 ####################\n",
+                "\n",
+                "Rules:\n",
+                "  fpl_abbrev                      2\n",
+                "  full_performance                2\n",
+                "\n",
+                "Pay plans:\n",
+                "  (none)                          1\n",
+                "  GS                              2\n",
+                "  WG                              1\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_stats_empty() {
+        assert_eq!(
+            printed(&Stats::default()),
+            concat!(
+                "Records: 0\n",
+                "Matched: 0 (0.0%)\n",
+                "\n",
+                "Grades:\n",
+                "\n",
+                "Rules:\n",
+                "\n",
+                "Pay plans:\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,425 @@
+use std::io::{Error, ErrorKind, Result};
+
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+const DISTANCE_BASE: [usize; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+
+const DISTANCE_EXTRA: [u32; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+const LENGTH_BASE: [usize; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+
+const LENGTH_EXTRA: [u32; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+/// A ZIP archive read fully into memory.
+pub struct Archive {
+    data: Vec<u8>,
+    entries: Vec<Entry>,
+}
+
+struct Bits<'a> {
+    buffer: u32,
+    count: u32,
+    data: &'a [u8],
+    position: usize,
+}
+
+struct Entry {
+    method: u16,
+    name: String,
+    offset: usize,
+    size: usize,
+}
+
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Archive {
+    pub fn new(data: Vec<u8>) -> Result<Self> {
+        let end = (0..data.len().saturating_sub(21))
+            .rev()
+            .find(|&i| data[i..].starts_with(&[0x50, 0x4b, 0x05, 0x06]))
+            .ok_or_else(|| invalid("not a ZIP archive"))?;
+
+        let count = u16_at(&data, end + 10)? as usize;
+        let mut position = u32_at(&data, end + 16)? as usize;
+        let mut entries = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            if u32_at(&data, position)? != 0x02014b50 {
+                return Err(invalid("corrupt ZIP central directory"));
+            }
+
+            let method = u16_at(&data, position + 10)?;
+            let size = u32_at(&data, position + 20)? as usize;
+            let name_length = u16_at(&data, position + 28)? as usize;
+            let extra_length = u16_at(&data, position + 30)? as usize;
+            let comment_length = u16_at(&data, position + 32)? as usize;
+            let header = u32_at(&data, position + 42)? as usize;
+
+            let name = data
+                .get(position + 46..position + 46 + name_length)
+                .ok_or_else(|| invalid("corrupt ZIP central directory"))?;
+
+            let offset = header
+                + 30
+                + u16_at(&data, header + 26)? as usize
+                + u16_at(&data, header + 28)? as usize;
+
+            entries.push(Entry {
+                method,
+                name: String::from_utf8_lossy(name).into_owned(),
+                offset,
+                size,
+            });
+
+            position += 46 + name_length + extra_length + comment_length;
+        }
+
+        Ok(Self { data, entries })
+    }
+
+    /// Returns the uncompressed content of the entry called `name`, if there is one.
+    pub fn read(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        let Some(entry) = self.entries.iter().find(|e| e.name == name) else {
+            return Ok(None);
+        };
+
+        let data = self
+            .data
+            .get(entry.offset..entry.offset + entry.size)
+            .ok_or_else(|| invalid("corrupt ZIP entry"))?;
+
+        match entry.method {
+            0 => Ok(Some(data.to_vec())),
+            8 => inflate(data).map(Some),
+            method => Err(invalid(&format!(
+                "unsupported ZIP compression method {method}"
+            ))),
+        }
+    }
+}
+
+impl Bits<'_> {
+    fn bits(&mut self, count: u32) -> Result<u32> {
+        while self.count < count {
+            let byte = *self
+                .data
+                .get(self.position)
+                .ok_or_else(|| invalid("truncated deflate stream"))?;
+
+            self.buffer |= (byte as u32) << self.count;
+            self.count += 8;
+            self.position += 1;
+        }
+
+        let value = self.buffer & ((1u64 << count) - 1) as u32;
+
+        self.buffer >>= count;
+        self.count -= count;
+
+        Ok(value)
+    }
+
+    fn decode(&mut self, huffman: &Huffman) -> Result<usize> {
+        let mut code = 0;
+        let mut first = 0;
+        let mut index = 0;
+
+        for length in 1..16 {
+            code |= self.bits(1)? as i32;
+
+            let count = huffman.counts[length] as i32;
+
+            if code - count < first {
+                return Ok(huffman.symbols[(index + code - first) as usize] as usize);
+            }
+
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+
+        Err(invalid("invalid deflate code"))
+    }
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0; 16];
+
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+
+        counts[0] = 0;
+
+        let mut offsets = [0; 16];
+
+        for length in 1..16 {
+            offsets[length] = offsets[length - 1] + counts[length - 1];
+        }
+
+        let mut symbols = vec![0; lengths.len()];
+
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+
+        Self { counts, symbols }
+    }
+}
+
+fn dynamic_tables(bits: &mut Bits) -> Result<(Huffman, Huffman)> {
+    let literals = bits.bits(5)? as usize + 257;
+    let distances = bits.bits(5)? as usize + 1;
+    let codes = bits.bits(4)? as usize + 4;
+
+    let mut lengths = [0; 19];
+
+    for &i in &CODE_LENGTH_ORDER[0..codes] {
+        lengths[i] = bits.bits(3)? as u8;
+    }
+
+    let code_lengths = Huffman::new(&lengths);
+    let mut lengths = Vec::with_capacity(literals + distances);
+
+    while lengths.len() < literals + distances {
+        let (length, repeat) = match bits.decode(&code_lengths)? {
+            symbol @ 0..=15 => (symbol as u8, 1),
+            16 => (
+                *lengths
+                    .last()
+                    .ok_or_else(|| invalid("invalid deflate code lengths"))?,
+                3 + bits.bits(2)?,
+            ),
+            17 => (0, 3 + bits.bits(3)?),
+            _ => (0, 11 + bits.bits(7)?),
+        };
+
+        lengths.extend((0..repeat).map(|_| length));
+    }
+
+    if lengths.len() > literals + distances {
+        return Err(invalid("invalid deflate code lengths"));
+    }
+
+    Ok((
+        Huffman::new(&lengths[0..literals]),
+        Huffman::new(&lengths[literals..]),
+    ))
+}
+
+fn fixed_tables() -> (Huffman, Huffman) {
+    let mut lengths = [0; 288];
+
+    lengths[0..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..288].fill(8);
+
+    (Huffman::new(&lengths), Huffman::new(&[5; 30]))
+}
+
+fn inflate(data: &[u8]) -> Result<Vec<u8>> {
+    let mut bits = Bits {
+        buffer: 0,
+        count: 0,
+        data,
+        position: 0,
+    };
+
+    let mut out = Vec::new();
+
+    loop {
+        let last = bits.bits(1)? == 1;
+
+        match bits.bits(2)? {
+            0 => {
+                bits.buffer = 0;
+                bits.count = 0;
+
+                let length = u16_at(data, bits.position)? as usize;
+                let start = bits.position + 4;
+
+                let block = data
+                    .get(start..start + length)
+                    .ok_or_else(|| invalid("truncated deflate stream"))?;
+
+                out.extend_from_slice(block);
+                bits.position = start + length;
+            }
+            1 => {
+                let (literals, distances) = fixed_tables();
+                inflate_block(&mut bits, &mut out, &literals, &distances)?;
+            }
+            2 => {
+                let (literals, distances) = dynamic_tables(&mut bits)?;
+                inflate_block(&mut bits, &mut out, &literals, &distances)?;
+            }
+            _ => return Err(invalid("invalid deflate block type")),
+        }
+
+        if last {
+            return Ok(out);
+        }
+    }
+}
+
+fn inflate_block(
+    bits: &mut Bits,
+    out: &mut Vec<u8>,
+    literals: &Huffman,
+    distances: &Huffman,
+) -> Result<()> {
+    loop {
+        let symbol = bits.decode(literals)?;
+
+        if symbol < 256 {
+            out.push(symbol as u8);
+            continue;
+        }
+
+        if symbol == 256 {
+            return Ok(());
+        }
+
+        let symbol = symbol - 257;
+
+        if symbol >= LENGTH_BASE.len() {
+            return Err(invalid("invalid deflate length"));
+        }
+
+        let length = LENGTH_BASE[symbol] + bits.bits(LENGTH_EXTRA[symbol])? as usize;
+        let symbol = bits.decode(distances)?;
+
+        if symbol >= DISTANCE_BASE.len() {
+            return Err(invalid("invalid deflate distance"));
+        }
+
+        let distance = DISTANCE_BASE[symbol] + bits.bits(DISTANCE_EXTRA[symbol])? as usize;
+
+        if distance > out.len() {
+            return Err(invalid("invalid deflate distance"));
+        }
+
+        let start = out.len() - distance;
+
+        for i in 0..length {
+            out.push(out[start + i]);
+        }
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message.to_string())
+}
+
+fn u16_at(data: &[u8], position: usize) -> Result<u16> {
+    data.get(position..position + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| invalid("truncated ZIP archive"))
+}
+
+fn u32_at(data: &[u8], position: usize) -> Result<u32> {
+    data.get(position..position + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| invalid("truncated ZIP archive"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::unzip::*;
+
+    /// A ZIP archive with `a.txt` deflated with a fixed Huffman block.
+    const ARCHIVE: [u8; 118] = [
+        0x50, 0x4b, 0x03, 0x04, 0x14, 0x00, 0x00, 0x00, 0x08, 0x00, 0x9c, 0x34, 0x4e, 0x5d, 0xb1,
+        0xef, 0x01, 0xca, 0x0a, 0x00, 0x00, 0x00, 0x11, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00,
+        0x61, 0x2e, 0x74, 0x78, 0x74, 0x73, 0x0f, 0xd6, 0x35, 0x34, 0x52, 0x70, 0x47, 0x90, 0x00,
+        0x50, 0x4b, 0x01, 0x02, 0x14, 0x03, 0x14, 0x00, 0x00, 0x00, 0x08, 0x00, 0x9c, 0x34, 0x4e,
+        0x5d, 0xb1, 0xef, 0x01, 0xca, 0x0a, 0x00, 0x00, 0x00, 0x11, 0x00, 0x00, 0x00, 0x05, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x01, 0x00, 0x00, 0x00,
+        0x00, 0x61, 0x2e, 0x74, 0x78, 0x74, 0x50, 0x4b, 0x05, 0x06, 0x00, 0x00, 0x00, 0x00, 0x01,
+        0x00, 0x01, 0x00, 0x33, 0x00, 0x00, 0x00, 0x2d, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    const DYNAMIC: [u8; 25] = [
+        0x05, 0xc1, 0x01, 0x01, 0x00, 0x00, 0x00, 0x82, 0xa0, 0xad, 0xd8, 0xff, 0x0f, 0x01, 0x00,
+        0x00, 0x00, 0x00, 0x40, 0x55, 0x55, 0x55, 0x55, 0xd5, 0x0e,
+    ];
+
+    #[test]
+    fn test_archive() {
+        let archive = Archive::new(ARCHIVE.to_vec()).unwrap();
+
+        assert_eq!(
+            archive.read("a.txt").unwrap().unwrap(),
+            b"GS-12 GS-12 GS-12"
+        );
+        assert!(archive.read("b.txt").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_archive_corrupt() {
+        let error = |data: &[u8]| Archive::new(data.to_vec()).err().unwrap().to_string();
+
+        assert_eq!(error(&ARCHIVE[..ARCHIVE.len() - 1]), "not a ZIP archive");
+        assert_eq!(error(b"GS-12"), "not a ZIP archive");
+
+        let mut corrupt = ARCHIVE;
+
+        corrupt[45] = 0;
+        assert_eq!(error(&corrupt), "corrupt ZIP central directory");
+
+        let mut corrupt = ARCHIVE;
+
+        // The entry is said to be longer than the archive.
+        corrupt[68] = 0xff;
+
+        let archive = Archive::new(corrupt.to_vec()).unwrap();
+
+        assert_eq!(
+            archive.read("a.txt").err().unwrap().to_string(),
+            "corrupt ZIP entry"
+        );
+    }
+
+    #[test]
+    fn test_inflate() {
+        let stored = [0x01, 0x05, 0x00, 0xfa, 0xff, 0x47, 0x53, 0x2d, 0x31, 0x32];
+        let fixed = [0x73, 0x0f, 0xd6, 0x35, 0x34, 0x52, 0x70, 0x47, 0x90, 0x00];
+
+        assert_eq!(inflate(&stored).unwrap(), b"GS-12");
+        assert_eq!(inflate(&fixed).unwrap(), b"GS-12 GS-12 GS-12");
+        assert_eq!(
+            inflate(&DYNAMIC).unwrap(),
+            ("a".repeat(40) + &"b".repeat(20) + "c").as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_inflate_truncated() {
+        assert!(inflate(&[]).is_err());
+        assert!(inflate(&[0x01, 0x05, 0x00, 0xfa, 0xff, 0x47]).is_err());
+        assert!(inflate(&[0x73, 0x0f, 0xd6]).is_err());
+        assert!(inflate(&DYNAMIC[..12]).is_err());
+        assert!(inflate(&[0x07]).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::Result,
+    path::{Path, PathBuf},
+    slice, thread,
+    time::{Duration, SystemTime},
+};
+
+use fpl::Rules;
+
+use crate::{
+    glob, input::Reader, print::Printer, process, progress::Progress, skip_processed, state::State,
+    Args, UniqueBy,
+};
+
+/// How long to wait between two scans of the watched directory.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+type Stamp = (SystemTime, u64);
+
+/// Processes the input files in `dir` and then keeps scanning it, processing the records added to
+/// files whenever they appear or change. The records are told apart by their IDs, so that those
+/// already read from a file are skipped when lines are appended to it. A file is only read once
+/// it has not changed between two scans, so that files still being written are not read
+/// half-way, and the files that fpl writes itself are never read.
+pub fn watch(dir: &Path, rules: &Rules, args: &Args) -> Result<bool> {
+    let mut printer = Printer::new(args)?;
+    let mut processed = HashMap::<PathBuf, Stamp>::new();
+    let mut observed = HashMap::<PathBuf, Stamp>::new();
+    let mut consumed = HashMap::<PathBuf, State>::new();
+    let dirs = [dir.to_path_buf()];
+
+    loop {
+        let mut current = HashMap::new();
+        let outputs = outputs(args);
+
+        for path in glob::expand(&dirs, args.recursive)? {
+            if is_output(&path, &outputs) {
+                continue;
+            }
+
+            let Ok(stamp) = stamp(&path) else {
+                continue;
+            };
+
+            if observed.get(&path) == Some(&stamp) && processed.get(&path) != Some(&stamp) {
+                let state = consumed
+                    .entry(path.clone())
+                    .or_insert_with(|| State::new(UniqueBy::Id));
+
+                let reader = Reader::new(args, slice::from_ref(&path));
+                let reader = skip_processed(reader, Some(state));
+                let progress = &mut Progress::hidden();
+
+                if let Err(error) =
+                    process(reader, rules, args, progress, |rows| printer.print(rows))
+                {
+                    eprintln!("Error: {}: {error}", path.display());
+                }
+
+                printer.flush()?;
+                processed.insert(path.clone(), stamp);
+            }
+
+            current.insert(path, stamp);
+        }
+
+        observed = current;
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Returns whether `path` is written by fpl rather than read: one of the canonical `outputs` or
+/// a temporary file that an output is written to first.
+fn is_output(path: &Path, outputs: &[PathBuf]) -> bool {
+    path.extension().is_some_and(|e| e == "tmp")
+        || fs::canonicalize(path).is_ok_and(|path| outputs.contains(&path))
+}
+
+/// Returns the canonical paths of the files written with `args` that already exist.
+fn outputs(args: &Args) -> Vec<PathBuf> {
+    let extract = args.extract();
+
+    [
+        &args.output,
+        &extract.parquet,
+        &extract.provenance,
+        &extract.sqlite,
+        &extract.state,
+        &extract.xlsx,
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(|path| fs::canonicalize(path).ok())
+    .collect()
+}
+
+fn stamp(path: &Path) -> Result<Stamp> {
+    let metadata = fs::metadata(path)?;
+
+    Ok((metadata.modified()?, metadata.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use clap::Parser;
+
+    use crate::watch::*;
+
+    #[test]
+    fn test_outputs() {
+        let dir = env::temp_dir().join(format!("fpl-{}-watch", process::id()));
+        let input = dir.join("input.csv");
+        let output = dir.join("output.csv");
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&input, "1,fpl is gs-13\n").unwrap();
+        fs::write(&output, "").unwrap();
+        fs::write(dir.join("output.csv.tmp"), "").unwrap();
+
+        let args = Args::parse_from([
+            "fpl".as_ref(),
+            "extract".as_ref(),
+            "--output".as_ref(),
+            output.as_os_str(),
+            "--state".as_ref(),
+            dir.join("state.json").as_os_str(),
+            dir.as_os_str(),
+        ]);
+
+        let outputs = outputs(&args);
+        assert_eq!(outputs, [fs::canonicalize(&output).unwrap()]);
+
+        let files = glob::expand(slice::from_ref(&dir), false).unwrap();
+        assert_eq!(files, [input.clone(), output.clone()]);
+
+        assert!(!is_output(&input, &outputs));
+        assert!(is_output(&output, &outputs));
+        assert!(is_output(&dir.join(".").join("output.csv"), &outputs));
+        assert!(is_output(&dir.join("output.csv.tmp"), &outputs));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,733 @@
+use std::{
+    fs,
+    io::{Error, ErrorKind, Result, Write},
+    path::Path,
+};
+
+use html_escape::{decode_html_entities, encode_text};
+
+use crate::unzip::Archive;
+
+const CONTENT_TYPES: &str = concat!(
+    "<?xml version='1.0' encoding='UTF-8' standalone='yes'?>\n",
+    "<Types xmlns='http://schemas.openxmlformats.org/package/2006/content-types'>",
+    "<Default Extension='rels' ContentType='application/vnd.openxmlformats-package.relationships+xml'/>",
+    "<Default Extension='xml' ContentType='application/xml'/>",
+    "<Override PartName='/xl/workbook.xml' ContentType='application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml'/>",
+    "<Override PartName='/xl/styles.xml' ContentType='application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml'/>",
+    "<Override PartName='/xl/worksheets/sheet1.xml' ContentType='application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml'/>",
+    "</Types>",
+);
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const RELATIONSHIPS: &str = concat!(
+    "<?xml version='1.0' encoding='UTF-8' standalone='yes'?>\n",
+    "<Relationships xmlns='http://schemas.openxmlformats.org/package/2006/relationships'>",
+    "<Relationship Id='rId1' Type='http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument' Target='xl/workbook.xml'/>",
+    "</Relationships>",
+);
+
+const SHEET_FOOTER: &str = "</sheetData></worksheet>";
+
+const SHEET_HEADER: &str = concat!(
+    "<?xml version='1.0' encoding='UTF-8' standalone='yes'?>\n",
+    "<worksheet xmlns='http://schemas.openxmlformats.org/spreadsheetml/2006/main'>",
+    "<sheetViews><sheetView workbookViewId='0'>",
+    "<pane ySplit='1' topLeftCell='A2' activePane='bottomLeft' state='frozen'/>",
+    "</sheetView></sheetViews>",
+    "<sheetData>",
+);
+
+const STYLES: &str = concat!(
+    "<?xml version='1.0' encoding='UTF-8' standalone='yes'?>\n",
+    "<styleSheet xmlns='http://schemas.openxmlformats.org/spreadsheetml/2006/main'>",
+    "<fonts count='2'><font><sz val='11'/><name val='Calibri'/></font>",
+    "<font><b/><sz val='11'/><name val='Calibri'/></font></fonts>",
+    "<fills count='2'><fill><patternFill patternType='none'/></fill>",
+    "<fill><patternFill patternType='gray125'/></fill></fills>",
+    "<borders count='1'><border><left/><right/><top/><bottom/><diagonal/></border></borders>",
+    "<cellStyleXfs count='1'><xf numFmtId='0' fontId='0' fillId='0' borderId='0'/></cellStyleXfs>",
+    "<cellXfs count='2'><xf numFmtId='0' fontId='0' fillId='0' borderId='0' xfId='0'/>",
+    "<xf numFmtId='0' fontId='1' fillId='0' borderId='0' xfId='0' applyFont='1'/></cellXfs>",
+    "</styleSheet>",
+);
+
+const WORKBOOK: &str = concat!(
+    "<?xml version='1.0' encoding='UTF-8' standalone='yes'?>\n",
+    "<workbook xmlns='http://schemas.openxmlformats.org/spreadsheetml/2006/main' ",
+    "xmlns:r='http://schemas.openxmlformats.org/officeDocument/2006/relationships'>",
+    "<sheets><sheet name='FPL' sheetId='1' r:id='rId1'/></sheets>",
+    "</workbook>",
+);
+
+const WORKBOOK_RELATIONSHIPS: &str = concat!(
+    "<?xml version='1.0' encoding='UTF-8' standalone='yes'?>\n",
+    "<Relationships xmlns='http://schemas.openxmlformats.org/package/2006/relationships'>",
+    "<Relationship Id='rId1' Type='http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet' Target='worksheets/sheet1.xml'/>",
+    "<Relationship Id='rId2' Type='http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles' Target='styles.xml'/>",
+    "</Relationships>",
+);
+
+pub enum Cell<'a> {
+    Empty,
+    Highlighted(&'a str, &'a str, &'a str),
+    Number(usize),
+    Text(&'a str),
+}
+
+enum Token<'a> {
+    End(&'a str),
+    Start(&'a str, &'a str, bool),
+    Text(&'a str),
+}
+
+struct Entry {
+    crc: u32,
+    name: &'static str,
+    offset: u32,
+    size: u32,
+}
+
+/// Writes a single-sheet workbook as an uncompressed ZIP archive, streaming the sheet rows.
+pub struct Workbook {
+    crc: u32,
+    entries: Vec<Entry>,
+    offset: u32,
+    size: u32,
+}
+
+impl Workbook {
+    pub fn finish(mut self, out: &mut impl Write) -> Result<()> {
+        self.write(out, SHEET_FOOTER.as_bytes())?;
+        self.end_entry(out)?;
+
+        let start = self.offset;
+        let mut directory = Vec::new();
+
+        for entry in &self.entries {
+            directory.extend(0x02014b50u32.to_le_bytes());
+            directory.extend([20, 0, 20, 0, 8, 0, 0, 0, 0, 0, 0x21, 0]);
+            directory.extend(entry.crc.to_le_bytes());
+            directory.extend(entry.size.to_le_bytes());
+            directory.extend(entry.size.to_le_bytes());
+            directory.extend((entry.name.len() as u16).to_le_bytes());
+            directory.extend([0; 12]);
+            directory.extend(entry.offset.to_le_bytes());
+            directory.extend(entry.name.as_bytes());
+        }
+
+        let count = (self.entries.len() as u16).to_le_bytes();
+
+        directory.extend(0x06054b50u32.to_le_bytes());
+        directory.extend([0, 0, 0, 0]);
+        directory.extend(count);
+        directory.extend(count);
+        directory.extend((directory.len() as u32 - 12).to_le_bytes());
+        directory.extend(start.to_le_bytes());
+        directory.extend([0, 0]);
+
+        out.write_all(&directory)
+    }
+
+    pub fn new(out: &mut impl Write) -> Result<Self> {
+        let mut workbook = Self {
+            crc: 0,
+            entries: Vec::new(),
+            offset: 0,
+            size: 0,
+        };
+
+        for (name, content) in [
+            ("[Content_Types].xml", CONTENT_TYPES),
+            ("_rels/.rels", RELATIONSHIPS),
+            ("xl/workbook.xml", WORKBOOK),
+            ("xl/_rels/workbook.xml.rels", WORKBOOK_RELATIONSHIPS),
+            ("xl/styles.xml", STYLES),
+        ] {
+            workbook.start_entry(out, name)?;
+            workbook.write(out, content.as_bytes())?;
+            workbook.end_entry(out)?;
+        }
+
+        workbook.start_entry(out, "xl/worksheets/sheet1.xml")?;
+        workbook.write(out, SHEET_HEADER.as_bytes())?;
+
+        Ok(workbook)
+    }
+
+    /// Writes a row of bold column names.
+    pub fn write_header(&mut self, out: &mut impl Write, names: &[&str]) -> Result<()> {
+        let mut xml = String::from("<row>");
+
+        for name in names {
+            xml.push_str(&format!(
+                "<c t='inlineStr' s='1'><is><t>{}</t></is></c>",
+                xml_text(name)
+            ));
+        }
+
+        xml.push_str("</row>");
+        self.write(out, xml.as_bytes())
+    }
+
+    pub fn write_row(&mut self, out: &mut impl Write, cells: &[Cell]) -> Result<()> {
+        let mut xml = String::from("<row>");
+
+        for cell in cells {
+            match cell {
+                Cell::Empty => xml.push_str("<c/>"),
+                Cell::Highlighted(prefix, text, suffix) => xml.push_str(&format!(
+                    concat!(
+                        "<c t='inlineStr'><is>",
+                        "<r><t xml:space='preserve'>{}</t></r>",
+                        "<r><rPr><color rgb='FFFF0000'/></rPr><t xml:space='preserve'>{}</t></r>",
+                        "<r><t xml:space='preserve'>{}</t></r>",
+                        "</is></c>"
+                    ),
+                    xml_text(prefix),
+                    xml_text(text),
+                    xml_text(suffix)
+                )),
+                Cell::Number(number) => xml.push_str(&format!("<c><v>{number}</v></c>")),
+                Cell::Text(text) => xml.push_str(&format!(
+                    "<c t='inlineStr'><is><t xml:space='preserve'>{}</t></is></c>",
+                    xml_text(text)
+                )),
+            }
+        }
+
+        xml.push_str("</row>");
+        self.write(out, xml.as_bytes())
+    }
+
+    fn end_entry(&mut self, out: &mut impl Write) -> Result<()> {
+        let entry = self.entries.last_mut().expect("no entry started");
+
+        entry.crc = !self.crc;
+        entry.size = self.size;
+
+        let mut descriptor = Vec::new();
+
+        descriptor.extend(0x08074b50u32.to_le_bytes());
+        descriptor.extend(entry.crc.to_le_bytes());
+        descriptor.extend(entry.size.to_le_bytes());
+        descriptor.extend(entry.size.to_le_bytes());
+
+        self.offset = advance(self.offset, descriptor.len())?;
+        out.write_all(&descriptor)
+    }
+
+    fn start_entry(&mut self, out: &mut impl Write, name: &'static str) -> Result<()> {
+        let mut header = Vec::new();
+
+        header.extend(0x04034b50u32.to_le_bytes());
+        header.extend([20, 0, 8, 0, 0, 0, 0, 0, 0x21, 0]);
+        header.extend([0; 12]);
+        header.extend((name.len() as u16).to_le_bytes());
+        header.extend([0, 0]);
+        header.extend(name.as_bytes());
+
+        self.entries.push(Entry {
+            crc: 0,
+            name,
+            offset: self.offset,
+            size: 0,
+        });
+
+        self.crc = !0;
+        self.size = 0;
+        self.offset = advance(self.offset, header.len())?;
+
+        out.write_all(&header)
+    }
+
+    fn write(&mut self, out: &mut impl Write, data: &[u8]) -> Result<()> {
+        for byte in data {
+            self.crc = CRC_TABLE[((self.crc ^ *byte as u32) & 0xff) as usize] ^ (self.crc >> 8);
+        }
+
+        self.size = advance(self.size, data.len())?;
+        self.offset = advance(self.offset, data.len())?;
+
+        out.write_all(data)
+    }
+}
+
+/// Reads the rows of the sheet called `name`, or of the first sheet, from an XLSX workbook.
+pub fn read_sheet(path: &Path, name: Option<&str>) -> Result<Vec<Vec<String>>> {
+    let archive = Archive::new(fs::read(path)?)?;
+
+    let workbook = read_part(&archive, "xl/workbook.xml")?
+        .ok_or_else(|| invalid("workbook has no xl/workbook.xml"))?;
+
+    let relationships = read_part(&archive, "xl/_rels/workbook.xml.rels")?.unwrap_or_default();
+
+    let sheet = tokens(&workbook)
+        .filter_map(|token| match token {
+            Token::Start("sheet", attributes, _) => Some(attributes),
+            _ => None,
+        })
+        .find(|attributes| name.is_none() || attribute(attributes, "name").as_deref() == name)
+        .ok_or_else(|| match name {
+            Some(name) => Error::new(
+                ErrorKind::InvalidInput,
+                format!("sheet '{name}' not found in workbook"),
+            ),
+            None => invalid("workbook has no sheets"),
+        })?;
+
+    let id = attribute(sheet, "r:id").unwrap_or_default();
+
+    let target = tokens(&relationships)
+        .find_map(|token| match token {
+            Token::Start("Relationship", attributes, _)
+                if attribute(attributes, "Id").as_deref() == Some(&id) =>
+            {
+                attribute(attributes, "Target")
+            }
+            _ => None,
+        })
+        .ok_or_else(|| invalid("sheet has no relationship target"))?;
+
+    let target = match target.strip_prefix('/') {
+        Some(target) => target.to_string(),
+        None => format!("xl/{target}"),
+    };
+
+    let strings = read_part(&archive, "xl/sharedStrings.xml")?
+        .map(|xml| shared_strings(&xml))
+        .unwrap_or_default();
+
+    let sheet = read_part(&archive, &target)?
+        .ok_or_else(|| invalid(&format!("workbook has no {target}")))?;
+
+    Ok(rows(&sheet, &strings))
+}
+
+/// Adds `length` to the `offset` or size of an entry, failing when the archive gets too large for
+/// the 32 bits that ZIP without ZIP64 has for them.
+fn advance(offset: u32, length: usize) -> Result<u32> {
+    u32::try_from(length)
+        .ok()
+        .and_then(|length| offset.checked_add(length))
+        .ok_or_else(|| Error::other("workbook larger than the 4 GiB a ZIP archive can hold"))
+}
+
+fn attribute(attributes: &str, name: &str) -> Option<String> {
+    let mut rest = attributes;
+
+    while let Some(equals) = rest.find('=') {
+        let key = rest[0..equals].trim();
+        let value = rest[equals + 1..].trim_start();
+        let quote = value.chars().next()?;
+        let end = value[1..].find(quote)? + 1;
+
+        if key == name {
+            return Some(decode_html_entities(&value[1..end]).into_owned());
+        }
+
+        rest = &value[end + 1..];
+    }
+
+    None
+}
+
+fn column_index(reference: &str) -> Option<usize> {
+    let letters = reference
+        .chars()
+        .take_while(char::is_ascii_alphabetic)
+        .collect::<String>();
+
+    if letters.is_empty() {
+        return None;
+    }
+
+    let index = letters.chars().fold(0, |index, c| {
+        index * 26 + (c.to_ascii_uppercase() as usize - 'A' as usize + 1)
+    });
+
+    Some(index - 1)
+}
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                0xedb88320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+
+            bit += 1;
+        }
+
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+}
+
+fn invalid(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message.to_string())
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn read_part(archive: &Archive, name: &str) -> Result<Option<String>> {
+    archive.read(name)?.map_or(Ok(None), |data| {
+        String::from_utf8(data)
+            .map(Some)
+            .map_err(|_| invalid(&format!("{name} is not valid UTF-8")))
+    })
+}
+
+fn rows(xml: &str, strings: &[String]) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut column = 0;
+    let mut kind = String::new();
+    let mut value = String::new();
+    let mut text = String::new();
+    let mut element = "";
+    let mut phonetic = false;
+
+    for token in tokens(xml) {
+        match token {
+            Token::Start("row", _, empty) => {
+                row = Vec::new();
+                column = 0;
+
+                if empty {
+                    rows.push(Vec::new());
+                }
+            }
+            Token::End("row") => rows.push(std::mem::take(&mut row)),
+            Token::Start("c", attributes, empty) => {
+                if let Some(index) = attribute(attributes, "r").and_then(|r| column_index(&r)) {
+                    column = index;
+                }
+
+                kind = attribute(attributes, "t").unwrap_or_default();
+                value.clear();
+                text.clear();
+
+                if empty {
+                    column += 1;
+                }
+            }
+            Token::End("c") => {
+                let cell = match kind.as_str() {
+                    "s" => value
+                        .trim()
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|i| strings.get(i))
+                        .cloned()
+                        .unwrap_or_default(),
+                    "inlineStr" => text.clone(),
+                    "b" => if value.trim() == "1" { "TRUE" } else { "FALSE" }.to_string(),
+                    _ => value.clone(),
+                };
+
+                if row.len() < column {
+                    row.resize(column, String::new());
+                }
+
+                row.push(cell);
+                column = row.len();
+            }
+            Token::Start("rPh", _, false) => phonetic = true,
+            Token::End("rPh") => phonetic = false,
+            Token::Start(name @ ("v" | "t"), _, false) => element = name,
+            Token::End("v" | "t") => element = "",
+            Token::Text(content) if !phonetic => match element {
+                "v" => value.push_str(&decode_html_entities(content)),
+                "t" => text.push_str(&decode_html_entities(content)),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    rows
+}
+
+fn shared_strings(xml: &str) -> Vec<String> {
+    let mut strings = Vec::new();
+    let mut string = String::new();
+    let mut in_text = false;
+    let mut phonetic = false;
+
+    for token in tokens(xml) {
+        match token {
+            Token::Start("si", _, empty) => {
+                string.clear();
+
+                if empty {
+                    strings.push(String::new());
+                }
+            }
+            Token::End("si") => strings.push(std::mem::take(&mut string)),
+            Token::Start("rPh", _, false) => phonetic = true,
+            Token::End("rPh") => phonetic = false,
+            Token::Start("t", _, false) => in_text = true,
+            Token::End("t") => in_text = false,
+            Token::Text(content) if in_text && !phonetic => {
+                string.push_str(&decode_html_entities(content))
+            }
+            _ => {}
+        }
+    }
+
+    strings
+}
+
+fn tokens(xml: &str) -> impl Iterator<Item = Token<'_>> {
+    let mut rest = xml;
+
+    std::iter::from_fn(move || loop {
+        if rest.is_empty() {
+            return None;
+        }
+
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = &rest[0..end];
+
+            rest = &rest[end..];
+            return Some(Token::Text(text));
+        }
+
+        // A tag cut off at the end of the part ends it.
+        let end = rest.find('>')?;
+        let tag = &rest[1..end];
+
+        rest = &rest[end + 1..];
+
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+
+        if let Some(name) = tag.strip_prefix('/') {
+            return Some(Token::End(local_name(name.trim())));
+        }
+
+        let (tag, empty) = match tag.strip_suffix('/') {
+            Some(tag) => (tag, true),
+            None => (tag, false),
+        };
+
+        let (name, attributes) = tag
+            .split_once(|c: char| c.is_ascii_whitespace())
+            .unwrap_or((tag, ""));
+
+        return Some(Token::Start(local_name(name), attributes, empty));
+    })
+}
+
+fn xml_text(text: &str) -> String {
+    let text = text
+        .chars()
+        .filter(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+        .collect::<String>();
+
+    encode_text(&text).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, path::PathBuf, process};
+
+    use crate::xlsx::*;
+
+    const SHARED_STRINGS: &str = concat!(
+        "<sst><si><t>ID</t></si><si><t>Text</t></si>",
+        "<si><r><t>Grade: </t></r><r><rPr><b/></rPr><t>GS-12</t></r><rPh><t>x</t></rPh></si></sst>"
+    );
+
+    const SHEET: &str = concat!(
+        "<worksheet><sheetData>",
+        "<row r='1'><c r='A1' t='s'><v>0</v></c><c r='B1' t='s'><v>1</v></c></row>",
+        "<row r='2'><c r='A2'><v>1</v></c><c r='B2' t='s'><v>2</v></c></row>",
+        "<row r='3'><c r='A3'><v>2</v></c><c r='B3' t='inlineStr'><is><t>GS-13 &amp; up</t></is></c>",
+        "</row><row r='5'><c r='C5' t='b'><v>1</v></c><c r='E5'><v>3</v></c><c/></row>",
+        "</sheetData></worksheet>"
+    );
+
+    /// Writes a workbook with the sheets `Cover` and `Grades` to a temporary file.
+    fn workbook(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("fpl-{}-{name}.xlsx", process::id()));
+
+        fs::write(
+            &path,
+            zip(&[
+                (
+                    "xl/workbook.xml",
+                    concat!(
+                        "<workbook xmlns:r='r'><sheets>",
+                        "<sheet name='Cover' sheetId='1' r:id='rId1'/>",
+                        "<sheet name='Grades' sheetId='2' r:id='rId2'/>",
+                        "</sheets></workbook>"
+                    ),
+                ),
+                (
+                    "xl/_rels/workbook.xml.rels",
+                    concat!(
+                        "<Relationships>",
+                        "<Relationship Id='rId1' Target='worksheets/sheet1.xml'/>",
+                        "<Relationship Id='rId2' Target='/xl/worksheets/sheet2.xml'/>",
+                        "</Relationships>"
+                    ),
+                ),
+                ("xl/sharedStrings.xml", SHARED_STRINGS),
+                (
+                    "xl/worksheets/sheet1.xml",
+                    "<worksheet><sheetData><row><c t='inlineStr'><is><t>Cover</t></is></c></row></sheetData></worksheet>",
+                ),
+                ("xl/worksheets/sheet2.xml", SHEET),
+            ]),
+        )
+        .unwrap();
+
+        path
+    }
+
+    /// Returns a ZIP archive of `parts`, which are stored without checksums.
+    fn zip(parts: &[(&str, &str)]) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut directory = Vec::new();
+
+        for (name, content) in parts {
+            let offset = data.len() as u32;
+            let sizes = [content.len() as u32; 2].map(u32::to_le_bytes).concat();
+
+            data.extend(0x04034b50u32.to_le_bytes());
+            data.extend([20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            data.extend(&sizes);
+            data.extend((name.len() as u16).to_le_bytes());
+            data.extend([0, 0]);
+            data.extend(name.as_bytes());
+            data.extend(content.as_bytes());
+
+            directory.extend(0x02014b50u32.to_le_bytes());
+            directory.extend([20, 0, 20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            directory.extend(&sizes);
+            directory.extend((name.len() as u16).to_le_bytes());
+            directory.extend([0; 12]);
+            directory.extend(offset.to_le_bytes());
+            directory.extend(name.as_bytes());
+        }
+
+        let start = data.len() as u32;
+        let count = (parts.len() as u16).to_le_bytes();
+        let size = directory.len() as u32;
+
+        data.extend(directory);
+        data.extend(0x06054b50u32.to_le_bytes());
+        data.extend([0, 0, 0, 0]);
+        data.extend(count);
+        data.extend(count);
+        data.extend(size.to_le_bytes());
+        data.extend(start.to_le_bytes());
+        data.extend([0, 0]);
+        data
+    }
+
+    #[test]
+    fn test_advance() {
+        assert_eq!(advance(10, 20).unwrap(), 30);
+        assert_eq!(advance(u32::MAX - 1, 1).unwrap(), u32::MAX);
+        assert!(advance(u32::MAX, 1).is_err());
+        assert!(advance(0, u32::MAX as usize + 1).is_err());
+    }
+
+    #[test]
+    fn test_read_sheet() {
+        let path = workbook("read-sheet");
+
+        assert_eq!(read_sheet(&path, None).unwrap(), [["Cover"]]);
+        assert_eq!(read_sheet(&path, Some("Cover")).unwrap(), [["Cover"]]);
+        assert_eq!(
+            read_sheet(&path, Some("Grades")).unwrap()[1],
+            ["1", "Grade: GS-12"]
+        );
+        assert_eq!(
+            read_sheet(&path, Some("Summary"))
+                .err()
+                .unwrap()
+                .to_string(),
+            "sheet 'Summary' not found in workbook"
+        );
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_rows() {
+        let strings = shared_strings(SHARED_STRINGS);
+
+        assert_eq!(strings, ["ID", "Text", "Grade: GS-12"]);
+        assert_eq!(
+            rows(SHEET, &strings),
+            [
+                vec!["ID", "Text"],
+                vec!["1", "Grade: GS-12"],
+                vec!["2", "GS-13 & up"],
+                vec!["", "", "TRUE", "", "3"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokens_truncated() {
+        let xml = r#"<row r="1"><c t="inlineStr"><is><t>GS-12</t></is></c></row><c r="B"#;
+
+        assert_eq!(rows(xml, &[]), [["GS-12"]]);
+        assert_eq!(tokens("<").count(), 0);
+        assert_eq!(tokens("text<").count(), 1);
+    }
+
+    #[test]
+    fn test_workbook() {
+        let mut out = Vec::new();
+        let mut workbook = Workbook::new(&mut out).unwrap();
+
+        workbook.write_header(&mut out, &["ID", "Text"]).unwrap();
+        workbook
+            .write_row(
+                &mut out,
+                &[
+                    Cell::Number(1),
+                    Cell::Highlighted("Grade: ", "GS-12", " & up"),
+                    Cell::Empty,
+                    Cell::Text("<none>"),
+                ],
+            )
+            .unwrap();
+        workbook.finish(&mut out).unwrap();
+
+        let archive = Archive::new(out).unwrap();
+        let sheet = read_part(&archive, "xl/worksheets/sheet1.xml")
+            .unwrap()
+            .unwrap();
+
+        assert!(read_part(&archive, "xl/workbook.xml").unwrap().is_some());
+        assert_eq!(
+            rows(&sheet, &[]),
+            [
+                vec!["ID", "Text"],
+                vec!["1", "Grade: GS-12 & up", "", "<none>"]
+            ]
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use std::{env, fs, process};
+
+/// Runs `fpl check` over a corpus with `text` and returns its exit code and standard output.
+fn check(name: &str, text: Option<&str>) -> (Option<i32>, String) {
+    let corpus = env::temp_dir().join(format!("fpl-{}-{name}.csv", process::id()));
+
+    if let Some(text) = text {
+        fs::write(&corpus, text).unwrap();
+    }
+
+    let output = process::Command::new(env!("CARGO_BIN_EXE_fpl"))
+        .arg("check")
+        .arg(&corpus)
+        .output()
+        .unwrap();
+
+    if text.is_some() {
+        fs::remove_file(corpus).unwrap();
+    }
+
+    (
+        output.status.code(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+#[test]
+fn test_check_error() {
+    let (code, stdout) = check("check-error", None);
+
+    assert_eq!(code, Some(2));
+    assert_eq!(stdout, "");
+
+    let (code, _) = check("check-error-column", Some("text\nThe FPL is GS-12\n"));
+
+    assert_eq!(code, Some(2));
+}
+
+#[test]
+fn test_check_passed() {
+    let (code, stdout) = check(
+        "check-passed",
+        Some("text,grade\nThe FPL is GS-12,GS-12\nNo grade here,\n"),
+    );
+
+    assert_eq!(code, Some(0));
+    assert_eq!(stdout, "2 of 2 passed\n");
+}
+
+#[test]
+fn test_check_unmatched() {
+    let (code, stdout) = check(
+        "check-unmatched",
+        Some("text,grade\nThe FPL is GS-12,GS-13\nNo grade here,\n"),
+    );
+
+    assert_eq!(code, Some(1));
+    assert_eq!(
+        stdout,
+        "line 2: expected GS-13, got GS-12: The FPL is GS-12\n1 of 2 passed\n"
+    );
+}
@@ -0,0 +1,56 @@
+use std::fs;
+
+use fpl::get_fpl_mention;
+
+/// Checks every labeled record in `tests/corpus/*.csv`, a CSV file with `text`, `grade` and `rule`
+/// columns where the grade is written as `13` or `GS-13` and left empty when no grade should be
+/// found, and the rule is the name of the rule expected to find it, as printed in the `Rule`
+/// column. The rule may be left empty, and the column omitted, to check only the grade.
+///
+/// A new phrasing is covered by appending a line to `tests/corpus/basic.csv`.
+#[test]
+fn test_corpus() {
+    let mut failures = Vec::new();
+
+    for entry in fs::read_dir("tests/corpus").unwrap() {
+        let path = entry.unwrap().path();
+
+        if path.extension().is_none_or(|e| e != "csv") {
+            continue;
+        }
+
+        let mut reader = csv::Reader::from_path(&path).unwrap();
+
+        for record in reader.records() {
+            let record = record.unwrap();
+            let (text, expected) = (&record[0], record[1].trim());
+            let rule = record.get(2).map(str::trim).unwrap_or_default();
+            let mention = get_fpl_mention(text);
+            let actual = mention.as_ref().map(|(_, m)| m.grade());
+
+            let passed = match actual {
+                Some(grade) => grade.matches(expected),
+                None => expected.is_empty(),
+            };
+
+            if !passed {
+                failures.push(format!(
+                    "{}: expected {expected:?}, got {:?}: {text}",
+                    path.display(),
+                    actual.map(|g| g.grade)
+                ));
+            }
+
+            let actual = mention.as_ref().map(|(_, m)| m.rule.as_str());
+
+            if !rule.is_empty() && actual != Some(rule) {
+                failures.push(format!(
+                    "{}: expected rule {rule:?}, got {actual:?}: {text}",
+                    path.display()
+                ));
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
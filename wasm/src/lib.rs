@@ -0,0 +1,40 @@
+//! JavaScript bindings of the extractor, built with
+//! `wasm-pack build --target web` into a package that highlights grades client-side.
+
+use fpl::extract as extract_grade;
+use wasm_bindgen::prelude::*;
+
+/// The full performance level found in a text. The offsets count UTF-16 code units, as
+/// JavaScript strings do, so that they can be passed to `String.prototype.slice` directly.
+#[wasm_bindgen(getter_with_clone)]
+pub struct Extraction {
+    pub grade: String,
+    #[wasm_bindgen(js_name = payPlan)]
+    pub pay_plan: Option<String>,
+    pub series: Option<String>,
+    pub rule: String,
+    /// The start of the grade.
+    pub start: usize,
+    /// The end of the grade, exclusive.
+    pub end: usize,
+    /// The start of the trigger phrase the grade follows.
+    #[wasm_bindgen(js_name = triggerStart)]
+    pub trigger_start: usize,
+}
+
+/// Returns the full performance level found in `text`, or `undefined` if there is none.
+#[wasm_bindgen]
+pub fn extract(text: &str) -> Option<Extraction> {
+    let extraction = extract_grade(text)?;
+    let utf16_offset = |offset: usize| text[0..offset].encode_utf16().count();
+
+    Some(Extraction {
+        grade: extraction.grade.to_string(),
+        pay_plan: extraction.pay_plan.map(str::to_uppercase),
+        series: extraction.series.map(str::to_string),
+        rule: extraction.rule.as_str().to_string(),
+        start: utf16_offset(extraction.start),
+        end: utf16_offset(extraction.end),
+        trigger_start: utf16_offset(extraction.trigger_start),
+    })
+}